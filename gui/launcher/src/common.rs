@@ -8,8 +8,11 @@ pub enum Msg
   DrawSelectorGame,
   DrawSelectorExecutable,
   DrawEnv,
+  DrawDllOverrides,
+  DrawGraphics,
   DrawEnablerExecutable,
   DrawMenu,
+  DrawDiagnostics,
   WindActivate,
   WindDeactivate,
   Quit,
@@ -34,6 +34,17 @@ pub fn new(tx : Sender<Msg>)
   {
     println!("Failed to set launcher image");
   } // else
+  // Logo over the cover, it keeps its aspect ratio
+  if let Ok(env_image_logo) = env::var("GIMG_LAUNCHER_IMG_LOGO")
+  && let Ok(mut shared_image) = SharedImage::load(env_image_logo)
+  {
+    let mut frame_logo = Frame::default()
+      .with_pos(dimm::border(), dimm::border())
+      .with_size(dimm::width_launcher() - dimm::border()*2, dimm::height_launcher() / 3)
+      .with_align(enums::Align::Inside | enums::Align::Center);
+    shared_image.scale(frame_logo.w(), frame_logo.h(), true, true);
+    frame_logo.set_image(Some(shared_image));
+  } // if
   // Buttons
   column!(col,
     col.add(&Frame::default());
@@ -77,6 +88,24 @@ pub fn new(tx : Sender<Msg>)
     s.set_image(Some(image));
   });
 
+  // Games that cannot be launched are shown greyed out
+  if let Ok(reason) = env::var("GIMG_LAUNCHER_UNLAUNCHABLE")
+  {
+    if let Ok(env_image_launcher) = env::var("GIMG_LAUNCHER_IMG_GRAYSCALE")
+    && let Ok(shared_image) = SharedImage::load(env_image_launcher)
+    {
+      frame_background.set_image_scaled(Some(shared_image.clone()));
+      frame_background.resize_callback(move |s,_,_,_,_| { s.set_image_scaled(Some(shared_image.clone())); });
+    } // if
+    Frame::default()
+      .with_pos(dimm::border(), dimm::height_launcher() / 2)
+      .with_size(dimm::width_launcher() - dimm::border()*2, dimm::height_text() * 2)
+      .with_frame(enums::FrameType::FlatBox)
+      .with_color(enums::Color::BackGround)
+      .with_label(&format!("Cannot launch: {}", reason));
+    btn_play.clone().deactivate();
+  } // if
+
   // Button left aligned
   btn_menu.clone().emit(tx, Msg::DrawMenu);
   hover_blink!(btn_menu);
@@ -0,0 +1,94 @@
+use fltk::prelude::*;
+use fltk::{
+  app::Sender,
+  enums::{Color,FrameType},
+  frame::Frame,
+  text,
+};
+
+use common::Msg;
+use shared::dimm;
+use shared::fltk::WidgetExtExtra;
+use shared::std::PathBufExt;
+use shared::{fixed,hover_blink,hseparator_fixed,column,row,add};
+
+use crate::common;
+use crate::games;
+
+// pub fn report() {{{
+// Describes the directories that failed to load and the games that cannot be launched
+pub fn report() -> String
+{
+  let scan = games::diagnose();
+  let failures = scan.failures;
+  let unlaunchable : Vec<(String, String)> = scan.games
+    .into_iter()
+    .filter_map(|e| Some((e.path_root.string(), e.opt_reason_unlaunchable?)))
+    .collect();
+  if failures.is_empty() && unlaunchable.is_empty()
+  {
+    return "All games were loaded successfully".into();
+  } // if
+  let mut report = String::new();
+  if ! failures.is_empty()
+  {
+    report.push_str("Directories that failed to load:\n");
+    for (path, reason) in failures
+    {
+      report.push_str(&format!("\n{}\n  {}\n", path.string(), reason));
+    } // for
+  } // if
+  if ! unlaunchable.is_empty()
+  {
+    if ! report.is_empty() { report.push('\n'); } // if
+    report.push_str("Games that cannot be launched:\n");
+    for (path, reason) in unlaunchable
+    {
+      report.push_str(&format!("\n{}\n  {}\n", path, reason));
+    } // for
+  } // if
+  report
+} // fn: report }}}
+
+// pub fn display() {{{
+// Read-only text area with the diagnostics report
+pub fn display() -> text::TextDisplay
+{
+  let mut display = text::TextDisplay::default()
+    .with_color(Color::BackGround)
+    .with_frame(FrameType::NoBox);
+  display.wrap_mode(text::WrapMode::AtBounds, 0);
+  display.visible_focus(false);
+  display.set_buffer(text::TextBuffer::default());
+  display.insert(&report());
+  display
+} // fn: display }}}
+
+// fn: new {{{
+pub fn new(tx : Sender<Msg>)
+{
+  // Layout
+  column!(col,
+    col.set_margin(dimm::border_half());
+    fixed!(col, frame_title, Frame::default(), dimm::height_text());
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    add!(col, _display_report, display());
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    column!(col_bottom,
+      row!(row_bottom,
+        fixed!(row_bottom, btn_back, shared::fltk::button::rect::back(), dimm::width_button_rec());
+      );
+      col_bottom.fixed(&row_bottom, dimm::height_button_rec());
+    );
+    col.fixed(&col_bottom, dimm::height_button_rec());
+  );
+  // Title
+  let mut frame_title = frame_title.clone();
+  frame_title.set_label("Diagnostics");
+  // Footer button
+  let mut btn_back = btn_back.clone();
+  btn_back.emit(tx, Msg::DrawMenu);
+  hover_blink!(btn_back);
+} // fn: new }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -3,12 +3,17 @@ use fltk::{
   frame::Frame,
 };
 
-use shared::column;
+use shared::dimm;
+use shared::{column,fixed,add};
 
 // fn: new {{{
 pub fn new()
 {
-  column!(col, Frame::default().with_label("No game found inside this image"););
+  column!(col,
+    col.set_margin(dimm::border_half());
+    fixed!(col, _frame_title, Frame::default().with_label("No game found inside this image"), dimm::height_text());
+    add!(col, _display_report, crate::frame::diagnostics::display());
+  );
 } // fn: new }}}
 
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -16,6 +16,8 @@ use crate::common;
 
 pub mod enabler_executable;
 pub mod environment;
+pub mod dll_overrides;
+pub mod graphics;
 
 // fn: new {{{
 pub fn new(tx : Sender<Msg>)
@@ -66,7 +68,10 @@ pub fn new(tx : Sender<Msg>)
     && platform == common::Platform::WINE
     {
       f_make_entry("Executable Configuration").emit(tx, Msg::DrawEnablerExecutable);
+      f_make_entry("DLL Overrides").emit(tx, Msg::DrawDllOverrides);
     }
+    // Games that could not be loaded
+    f_make_entry("Diagnostics").emit(tx, Msg::DrawDiagnostics);
   );
 } // fn: new }}}
 
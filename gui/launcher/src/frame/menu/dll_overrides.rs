@@ -0,0 +1,127 @@
+use std::sync::{Arc,Mutex};
+
+use fltk::prelude::*;
+use fltk::{
+  app::Sender,
+  dialog,
+  enums::Color,
+  frame::Frame,
+  group,
+};
+
+use shared::dimm;
+use shared::wine::dll;
+use shared::{fixed,row,column,hpack,scroll,hover_blink,hseparator_fixed,rescope};
+
+use crate::common::Msg;
+use crate::path;
+
+// fn: new {{{
+pub fn new(tx : Sender<Msg>)
+{
+  // Layout
+  column!(col,
+    col.set_margin(dimm::border_half());
+    fixed!(col, frame_title, Frame::default(), dimm::height_text());
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    // Content
+    scroll!(scroll,
+      hpack!(col_scroll,);
+      col_scroll.set_spacing(dimm::border_half());
+      col_scroll.set_size(0,0);
+    );
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    column!(col_bottom,
+      row!(row_bottom,
+        fixed!(row_bottom, btn_back, &shared::fltk::button::rect::back(), dimm::width_button_rec());
+        row_bottom.add(&Frame::default());
+        fixed!(row_bottom, btn_home, &shared::fltk::button::rect::home(), dimm::width_button_rec());
+        row_bottom.add(&Frame::default());
+        fixed!(row_bottom, btn_add, &shared::fltk::button::rect::add(), dimm::width_button_rec());
+      );
+      col_bottom.fixed(&row_bottom, dimm::height_button_rec());
+    );
+    col.fixed(&col_bottom, dimm::height_button_rec());
+  );
+
+  // Title
+  let mut frame_title = frame_title.clone();
+  frame_title.set_label("DLL Overrides");
+
+  // Scroll resize callback
+  scroll.resize_callback({let mut c = col_scroll.clone(); move |_,_,_,w,_|
+  {
+    c.resize(c.x(),c.y(),w-dimm::border_half()*3,c.h());
+  }});
+  scroll.set_type(group::ScrollType::VerticalAlways);
+
+  // Configure buttons
+  let mut btn_back = btn_back.clone();
+  btn_back.emit(tx, Msg::DrawMenu);
+  hover_blink!(btn_back);
+  let mut btn_home = btn_home.clone();
+  btn_home.set_color(Color::Blue);
+  btn_home.emit(tx, Msg::DrawCover);
+  hover_blink!(btn_home);
+
+  // Prefix
+  let path_dir_wine = match path::dir_boot()
+  {
+    Ok(e) => e.join("wine"),
+    Err(e) => { eprintln!("Could not retrieve path to wine prefix: {}", e); return; }
+  }; // match
+
+  let path_file_env = match path::db_env()
+  {
+    Ok(e) => e,
+    Err(e) => { eprintln!("Could not retrieve path to environment database: {}", e); return; }
+  }; // match
+
+  // Overrides of the registry and of the environment, the environment takes precedence
+  let overrides = Arc::new(Mutex::new(dll::read(&path_file_env, &path_dir_wine)));
+
+  // Libraries found and the ones already overridden
+  let mut libraries = dll::list(path::dir_game().map_err(|e| eprintln!("{}", e)).ok().as_ref(), &path_dir_wine);
+  overrides.lock().unwrap().keys().for_each(|e| { libraries.entry(e.clone()).or_default(); });
+
+  rescope!(col_scroll,
+    for (name, origin) in libraries
+    {
+      let opt_mode = overrides.lock().unwrap().get(&name).copied();
+      let overrides = overrides.clone();
+      let path_dir_wine = path_dir_wine.clone();
+      let path_file_env = path_file_env.clone();
+      let _ = shared::fltk::overrides::entry(&name.clone(), &origin, opt_mode, move |opt_mode|
+      {
+        let mut overrides = overrides.lock().unwrap();
+        match opt_mode
+        {
+          Some(mode) => { overrides.insert(name.clone(), mode); },
+          None => { overrides.remove(&name); },
+        } // match
+        match dll::write(&path_file_env, &path_dir_wine, &overrides)
+        {
+          Ok(()) => println!("Set override of '{}' to '{}'", name, opt_mode.map(|e| e.label()).unwrap_or("Default")),
+          Err(e) => eprintln!("Could not write overrides: {}", e),
+        } // match
+      });
+    } // for
+  );
+
+  // Add a library that was not found
+  let mut btn_add = btn_add.clone();
+  btn_add.set_color(Color::Green);
+  btn_add.set_callback(move |_|
+  {
+    let Some(name) = dialog::input_default("Enter the name of the library, e.g., d3d9", "") else { return; };
+    let name = name.trim().to_lowercase().trim_end_matches(".dll").to_string();
+    if name.is_empty() { return; } // if
+    let mut overrides = overrides.lock().unwrap();
+    overrides.insert(name, dll::Mode::NativeBuiltin);
+    if let Err(e) = dll::write(&path_file_env, &path_dir_wine, &overrides) { eprintln!("Could not write overrides: {}", e); } // if
+    tx.send(Msg::DrawDllOverrides);
+  });
+  hover_blink!(btn_add);
+} // fn: new }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
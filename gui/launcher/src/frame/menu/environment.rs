@@ -11,15 +11,7 @@ use shared::dimm;
 use shared::{add,fixed,row,column,hpack,scroll,hover_blink,hseparator,hseparator_fixed,rescope};
 
 use crate::common::Msg;
-
-// get_path_db_env() {{{
-fn get_path_db_env() -> anyhow::Result<std::path::PathBuf>
-{
-  let mut path_db : std::path::PathBuf = std::env::var("GIMG_LAUNCHER_ROOT")?.into();
-  path_db.push("gameimage.env.json");
-
-  Ok(path_db)
-} // get_path_db_env() }}}
+use crate::path;
 
 // fn: new_entry {{{
 fn new_entry(tx: Sender<crate::common::Msg>, mut col: group::Pack, key: &str, val: &str)
@@ -40,7 +32,7 @@ fn new_entry(tx: Sender<crate::common::Msg>, mut col: group::Pack, key: &str, va
   btn_del.set_color(Color::Red);
   btn_del.set_callback(move |_|
   {
-    let path_file_db = match get_path_db_env()
+    let path_file_db = match path::db_env()
     {
       Ok(e) => e,
       Err(e) => { eprintln!("Could not retrieve path to db file: {}", e); std::path::PathBuf::default() }
@@ -57,7 +49,7 @@ fn new_entry(tx: Sender<crate::common::Msg>, mut col: group::Pack, key: &str, va
 // fn: new_dialog {{{
 fn new_dialog(tx: Sender<crate::common::Msg>)
 {
-  let path_file_db = match get_path_db_env()
+  let path_file_db = match path::db_env()
   {
     Ok(e) => e,
     Err(e) => { eprintln!("Could not retrieve path to db file: {}", e); std::path::PathBuf::default() }
@@ -103,6 +95,7 @@ pub fn new(tx : Sender<Msg>)
         row_bottom.add(&Frame::default());
         fixed!(row_bottom, btn_home, &shared::fltk::button::rect::home(), dimm::width_button_rec());
         row_bottom.add(&Frame::default());
+        fixed!(row_bottom, btn_graphics, &shared::fltk::button::rect::configure(), dimm::width_button_rec());
         fixed!(row_bottom, btn_add, &shared::fltk::button::rect::add(), dimm::width_button_rec());
       );
       col_bottom.fixed(&row_bottom, dimm::height_button_rec());
@@ -129,12 +122,23 @@ pub fn new(tx : Sender<Msg>)
   btn_home.set_color(Color::Blue);
   btn_home.emit(tx, Msg::DrawCover);
   hover_blink!(btn_home);
+  let mut btn_graphics = btn_graphics.clone();
+  btn_graphics.set_color(Color::Blue);
+  btn_graphics.emit(tx, Msg::DrawGraphics);
+  hover_blink!(btn_graphics);
+  // Graphics settings only apply to wine
+  if ! std::env::var("GIMG_PLATFORM").ok()
+    .and_then(|e| crate::common::Platform::from_str(&e).ok())
+    .is_some_and(|e| e == crate::common::Platform::WINE)
+  {
+    btn_graphics.hide();
+  } // if
   let mut btn_add = btn_add.clone();
   btn_add.set_color(Color::Green);
   btn_add.set_callback(move |_| new_dialog(tx));
   hover_blink!(btn_add);
 
-  let path_file_db = match get_path_db_env()
+  let path_file_db = match path::db_env()
   {
     Ok(e) => e,
     Err(e) => { eprintln!("Could not retrieve path to db file: {}", e); std::path::PathBuf::default() }
@@ -0,0 +1,79 @@
+use fltk::prelude::*;
+use fltk::{
+  app::Sender,
+  enums::Color,
+  frame::Frame,
+  group,
+};
+
+use shared::dimm;
+use shared::{fixed,row,column,hpack,scroll,hover_blink,hseparator_fixed,rescope};
+
+use crate::common::Msg;
+use crate::path;
+
+// fn: new {{{
+pub fn new(tx : Sender<Msg>)
+{
+  // Layout
+  column!(col,
+    col.set_margin(dimm::border_half());
+    fixed!(col, frame_title, Frame::default(), dimm::height_text());
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    // Content
+    scroll!(scroll,
+      hpack!(col_scroll,);
+      col_scroll.set_spacing(dimm::border_half());
+      col_scroll.set_size(0,0);
+    );
+    hseparator_fixed!(col, col.w() - dimm::border()*2, dimm::border_half());
+    column!(col_bottom,
+      row!(row_bottom,
+        fixed!(row_bottom, btn_back, &shared::fltk::button::rect::back(), dimm::width_button_rec());
+        row_bottom.add(&Frame::default());
+        fixed!(row_bottom, btn_home, &shared::fltk::button::rect::home(), dimm::width_button_rec());
+        row_bottom.add(&Frame::default());
+      );
+      col_bottom.fixed(&row_bottom, dimm::height_button_rec());
+    );
+    col.fixed(&col_bottom, dimm::height_button_rec());
+  );
+
+  // Title
+  let mut frame_title = frame_title.clone();
+  frame_title.set_label("Graphics and Synchronization");
+
+  // Scroll resize callback
+  scroll.resize_callback({let mut c = col_scroll.clone(); move |_,_,_,w,_|
+  {
+    c.resize(c.x(),c.y(),w-dimm::border_half()*3,c.h());
+  }});
+  scroll.set_type(group::ScrollType::VerticalAlways);
+
+  // Configure buttons
+  let mut btn_back = btn_back.clone();
+  btn_back.emit(tx, Msg::DrawEnv);
+  hover_blink!(btn_back);
+  let mut btn_home = btn_home.clone();
+  btn_home.set_color(Color::Blue);
+  btn_home.emit(tx, Msg::DrawCover);
+  hover_blink!(btn_home);
+
+  let path_file_env = match path::db_env()
+  {
+    Ok(e) => e,
+    Err(e) => { eprintln!("Could not retrieve path to db file: {}", e); return; }
+  }; // match
+
+  // dxvk reads its configuration from the directory of the game
+  let opt_path_file_conf = path::dir_game()
+    .map(|e| e.join("dxvk.conf"))
+    .map_err(|e| eprintln!("Could not retrieve directory of the game: {}", e))
+    .ok();
+
+  rescope!(col_scroll,
+    let _ = shared::fltk::graphics::panel(path_file_env, opt_path_file_conf);
+  );
+} // fn: new }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -1,5 +1,6 @@
 pub mod cover;
 pub mod selector_game;
 pub mod fail;
+pub mod diagnostics;
 pub mod menu;
 pub mod selector_executable;
@@ -6,6 +6,7 @@ use fltk::{
   frame::Frame,
   enums,
   group,
+  image::SharedImage,
 };
 
 use anyhow::anyhow as ah;
@@ -67,6 +68,16 @@ pub fn get_menu_entries() -> anyhow::Result<(Vec<String>,HashMap<String,String>)
 // fn: new {{{
 pub fn new(tx : Sender<Msg>)
 {
+  // Background image, visible behind the list
+  let mut frame_background = Frame::default_fill();
+  if let Ok(env_image_background) = std::env::var("GIMG_LAUNCHER_IMG_BACKGROUND")
+  && let Ok(shared_image) = SharedImage::load(env_image_background)
+  {
+    frame_background.set_image_scaled(Some(shared_image.clone()));
+    frame_background.resize_callback(move |s,_,_,_,_| { s.set_image_scaled(Some(shared_image.clone())); });
+    // Draw the inactive variant to keep the list readable
+    frame_background.deactivate();
+  } // if
   // Layout
   column!(col,
     col.set_margin(dimm::border_half());
@@ -5,11 +5,13 @@ use fltk::{
   app::Sender,
   button::Button,
   frame::Frame,
+  image::SharedImage,
 };
 
 use shared::dimm;
 use shared::fltk::WidgetExtExtra;
 use shared::fltk::SenderExt;
+use shared::image::Artwork;
 
 use crate::games;
 use crate::common;
@@ -64,14 +66,28 @@ pub fn new(tx : Sender<Msg>)
       // Set entry name
       let osstr_name_file = game.path_root.file_name().unwrap_or_default();
       let str_name_file = osstr_name_file.to_str().unwrap_or_default();
-      let entry = Button::default()
+      let mut entry = Button::default()
         .with_size(0, dimm::height_button_wide() + dimm::border_half())
         .with_label(&str_name_file)
         .with_frame(enums::FrameType::FlatBox)
         .with_color(enums::Color::BackGround)
         .with_color_selected(enums::Color::BackGround.lighter())
-        .with_align(enums::Align::Left | enums::Align::Inside)
-        .with_callback(move |_| { games::select(&game); tx.send_awake(Msg::DrawCover); });
+        .with_align(enums::Align::Left | enums::Align::Inside | enums::Align::ImageNextToText);
+      // Banner next to the game name
+      if let Some(path_banner) = game.artwork(Artwork::Banner)
+      && let Ok(mut shared_image) = SharedImage::load(path_banner)
+      {
+        let height = entry.h() - dimm::border_half();
+        shared_image.scale(height * 460 / 215, height, true, true);
+        entry.set_image(Some(shared_image));
+      } // if
+      // Greyed out with the reason if it cannot be launched
+      if let Some(reason) = &game.opt_reason_unlaunchable
+      {
+        entry.set_label(&format!("{} ({})", str_name_file, reason));
+        entry.set_label_color(enums::Color::Inactive);
+      } // if
+      entry.set_callback(move |_| { games::select(&game); tx.send_awake(Msg::DrawCover); });
       hover_blink!(entry);
       col_scroll.add(&entry);
     } // for
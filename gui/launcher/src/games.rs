@@ -1,8 +1,11 @@
 use std::fs;
 use std::fs::DirEntry;
 use std::path::PathBuf;
+use std::collections::HashMap;
+use std::os::unix::fs::PermissionsExt;
 
 use shared::std::PathBufExt;
+use shared::image::Artwork;
 
 use anyhow::anyhow as ah;
 
@@ -16,8 +19,45 @@ pub struct Game
   pub path_icon : PathBuf,
   pub path_icon_grayscale : PathBuf,
   pub path_boot : PathBuf,
+  // Images set for each role, the cover is path_icon
+  pub path_artwork : HashMap<Artwork, PathBuf>,
+  // Reason why the game cannot be launched
+  pub opt_reason_unlaunchable : Option<String>,
 } // Game
 
+// Directories in the games root that could not be loaded, with the reason
+pub type Failures = Vec<(PathBuf, String)>;
+
+pub struct Scan
+{
+  pub games : Vec<Game>,
+  pub failures : Failures,
+} // Scan
+
+impl Game
+{
+
+// pub fn artwork() {{{
+// Image of the role or the first available fallback
+pub fn artwork(&self, artwork : Artwork) -> Option<PathBuf>
+{
+  std::iter::once(&artwork)
+    .chain(artwork.fallbacks())
+    .find_map(|e| match e
+    {
+      Artwork::Cover => Some(self.path_icon.clone()),
+      _ => self.path_artwork.get(e).cloned(),
+    })
+} // fn: artwork }}}
+
+// pub fn is_launchable() {{{
+pub fn is_launchable(&self) -> bool
+{
+  self.opt_reason_unlaunchable.is_none()
+} // fn: is_launchable }}}
+
+}
+
 // pub fn launch() {{{
 pub fn launch()
 {
@@ -36,53 +76,150 @@ pub fn select(game: &Game)
   std::env::set_var("GIMG_LAUNCHER_ROOT", game.path_root.to_str().unwrap_or(""));
   std::env::set_var("GIMG_LAUNCHER_IMG", game.path_icon.to_str().unwrap_or(""));
   std::env::set_var("GIMG_LAUNCHER_IMG_GRAYSCALE", game.path_icon_grayscale.to_str().unwrap_or(""));
+  std::env::set_var("GIMG_LAUNCHER_IMG_BACKGROUND", game.artwork(Artwork::Background).unwrap_or_default());
+  // The logo has no fallback
+  std::env::set_var("GIMG_LAUNCHER_IMG_LOGO", game.artwork(Artwork::Logo).unwrap_or_default());
+  match &game.opt_reason_unlaunchable
+  {
+    Some(reason) => std::env::set_var("GIMG_LAUNCHER_UNLAUNCHABLE", reason),
+    None => std::env::remove_var("GIMG_LAUNCHER_UNLAUNCHABLE"),
+  } // match
 } // fn: select }}}
 
 // pub fn select_by_index() {{{
 pub fn select_by_index(index: usize) -> anyhow::Result<()>
 {
-  select(games()?.get(index).ok_or(ah!("Index out of bounds"))?);
+  let path_root = scan(false)?.games.get(index).ok_or(ah!("Index out of bounds"))?.path_root.clone();
+  // Only the selected game is rendered, it needs a placeholder if it has no icon
+  let game = game(path_root, true)?;
+  if let Some(reason) = &game.opt_reason_unlaunchable
+  {
+    return Err(ah!("Game '{}' cannot be launched: {}", game.path_root.string(), reason));
+  } // if
+  select(&game);
   Ok(())
 } // fn: select_by_index }}}
 
-// fn game() {{{
-fn game(path_root : PathBuf) -> anyhow::Result<Game>
+// fn icon_grayscale() {{{
+// Returns the grayscale icon of the game, it is generated in a temporary directory if missing
+fn icon_grayscale(path_root : &PathBuf, path_icon : &PathBuf) -> PathBuf
 {
-  let db_project = db::project::read(&path_root.join("gameimage.json"))?;
-  let path_icon = path_root.join("icon/icon.png");
   let path_icon_grayscale = path_root.join("icon").join("icon.grayscale.png");
-  let path_boot = path_root.join("boot");
-  let platform = common::Platform::from_str(&db_project.platform)?;
-  if path_icon.exists() && path_boot.exists()
+  if path_icon_grayscale.exists() || ! path_icon.exists() { return path_icon_grayscale; } // if
+  let path_icon_generated = std::env::temp_dir()
+    .join("gameimage")
+    .join(format!("{}.grayscale.png", path_root.file_name_string()));
+  if path_icon_generated.exists() { return path_icon_generated; } // if
+  let result = path_icon_generated.parent()
+    .map(|e| fs::create_dir_all(e))
+    .unwrap_or(Ok(()))
+    .map_err(|e| ah!(e))
+    .and_then(|_| shared::image::grayscale(path_icon_generated.clone(), path_icon.clone()));
+  match result
+  {
+    Ok(()) => path_icon_generated,
+    Err(e) => { eprintln!("Could not generate grayscale icon for '{}': {}", path_root.string(), e); path_icon_grayscale },
+  } // match
+} // fn icon_grayscale() }}}
+
+// fn icon_placeholder() {{{
+// Renders a cover with the game name and platform in a temporary directory
+fn icon_placeholder(path_root : &PathBuf, platform : &common::Platform) -> anyhow::Result<PathBuf>
+{
+  let path_icon = std::env::temp_dir()
+    .join("gameimage")
+    .join(format!("{}.placeholder.png", path_root.file_name_string()));
+  if path_icon.exists() { return Ok(path_icon); } // if
+  fs::create_dir_all(path_icon.parent().ok_or(ah!("Invalid placeholder path"))?)?;
+  shared::image::placeholder(&path_icon
+    , &path_root.file_name_string()
+    , &platform.as_str().to_uppercase()
+    , shared::dimm::width_launcher()
+    , shared::dimm::height_launcher()
+  )?;
+  Ok(path_icon)
+} // fn icon_placeholder() }}}
+
+// fn reason_unlaunchable() {{{
+fn reason_unlaunchable(path_boot : &PathBuf) -> Option<String>
+{
+  match fs::metadata(path_boot)
   {
-    return Ok(Game{ platform, path_boot, path_root, path_icon, path_icon_grayscale })
+    Ok(metadata) if ! metadata.is_file() => Some("Boot script is not a file".into()),
+    Ok(metadata) if metadata.permissions().mode() & 0o111 == 0 => Some("Boot script is not executable".into()),
+    Ok(_) => None,
+    Err(e) => Some(format!("Could not read boot script: {}", e)),
+  } // match
+} // fn reason_unlaunchable() }}}
+
+// fn game() {{{
+// is_render enables the generation of placeholder images, which requires a display
+fn game(path_root : PathBuf, is_render : bool) -> anyhow::Result<Game>
+{
+  let path_file_db = path_root.join("gameimage.json");
+  let db_project = db::project::read(&path_file_db)
+    .map_err(|e| ah!("Could not read '{}': {}", path_file_db.string(), e))?;
+  let platform = common::Platform::from_str(&db_project.platform)
+    .map_err(|e| ah!("Invalid platform '{}': {}", db_project.platform, e))?;
+  let mut path_icon = path_root.join("icon/icon.png");
+  if ! path_icon.exists() && is_render
+  {
+    match icon_placeholder(&path_root, &platform)
+    {
+      Ok(path_icon_placeholder) => path_icon = path_icon_placeholder,
+      Err(e) => eprintln!("Could not create placeholder for '{}': {}", path_root.string(), e),
+    } // match
   } // if
-  Err(ah!("Could not include project from '{}'", path_root.string()))
+  let path_icon_grayscale = icon_grayscale(&path_root, &path_icon);
+  let path_boot = path_root.join("boot");
+  let opt_reason_unlaunchable = reason_unlaunchable(&path_boot);
+  // Optional images by role name, relative to the project directory
+  let path_artwork = shared::db::kv::read(&path_root.join("gameimage.artwork.json"))
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|(k,v)| Some((k.parse::<Artwork>().ok()?, path_root.join(v))))
+    .filter(|(_,v)| v.exists())
+    .collect();
+  Ok(Game{ platform, path_boot, path_root, path_icon, path_icon_grayscale, path_artwork, opt_reason_unlaunchable })
 } // fn game() }}}
 
-// pub fn games() {{{
-pub fn games() -> anyhow::Result<Vec<Game>>
+// pub fn scan() {{{
+pub fn scan(is_render : bool) -> anyhow::Result<Scan>
 {
   let vec_entries : Vec<DirEntry> = fs::read_dir("/opt/gameimage-games")?
     .filter_map(|e| { e.ok() })
     .filter(|e|{ e.path().is_dir() })
     .collect();
 
-  let mut vec_data : Vec<Game> = vec![];
+  let mut scan = Scan{ games: vec![], failures: vec![] };
 
   for entry in vec_entries
   {
-    match game(entry.path())
+    match game(entry.path(), is_render)
     {
-      Ok(game) => vec_data.push(game),
-      Err(e) => eprintln!("{}", e),
+      Ok(game) => scan.games.push(game),
+      Err(e) => { eprintln!("{}", e); scan.failures.push((entry.path(), e.to_string())); },
     }
   } // for
 
   // Sort
-  vec_data.sort_by(|a, b| return a.path_root.string().partial_cmp(&b.path_root.string()).unwrap());
+  scan.games.sort_by(|a, b| return a.path_root.string().partial_cmp(&b.path_root.string()).unwrap());
+  scan.failures.sort_by(|a, b| a.0.cmp(&b.0));
 
-  Ok(vec_data)
+  Ok(scan)
+} // scan() }}}
+
+// pub fn games() {{{
+pub fn games() -> anyhow::Result<Vec<Game>>
+{
+  Ok(scan(true)?.games)
 } // games() }}}
 
+// pub fn diagnose() {{{
+// Scan without placeholders, a games directory that cannot be read is reported as a failure
+pub fn diagnose() -> Scan
+{
+  scan(false).unwrap_or_else(|e| Scan{ games: vec![], failures: vec![(PathBuf::from("/opt/gameimage-games"), e.to_string())] })
+} // diagnose() }}}
+
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
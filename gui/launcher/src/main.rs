@@ -14,6 +14,7 @@ mod games;
 mod frame;
 mod common;
 mod db;
+mod path;
 
 use common::Msg;
 
@@ -83,7 +84,10 @@ fn redraw(&mut self, msg: Msg)
     Some(Msg::DrawSelectorExecutable) => frame::selector_executable::new(self.tx),
     Some(Msg::DrawEnablerExecutable) => frame::menu::enabler_executable::new(self.tx),
     Some(Msg::DrawEnv) => frame::menu::environment::new(self.tx),
+    Some(Msg::DrawDllOverrides) => frame::menu::dll_overrides::new(self.tx),
+    Some(Msg::DrawGraphics) => frame::menu::graphics::new(self.tx),
     Some(Msg::DrawMenu) => frame::menu::new(self.tx),
+    Some(Msg::DrawDiagnostics) => frame::diagnostics::new(self.tx),
     _ => (),
   }
   self.wind.end();
@@ -107,8 +111,8 @@ fn init(&mut self)
   {
     // Create initial cover frame
     self.tx.send(common::Msg::DrawCover);
-    // Select the first game as the current
-    games::select(vec_games.first().unwrap());
+    // Select the first launchable game as the current
+    games::select(vec_games.iter().find(|e| e.is_launchable()).unwrap_or(vec_games.first().unwrap()));
   } // else
 
   // Show window
@@ -170,9 +174,13 @@ fn main() -> anyhow::Result<()>
   } // if
   else if args.select_list
   {
-    for (index, game) in games::games()?.into_iter().enumerate()
+    for (index, game) in games::scan(false)?.games.into_iter().enumerate()
     {
-      println!("{}: {}", index, game.path_root.file_name_string());
+      match game.opt_reason_unlaunchable
+      {
+        Some(reason) => println!("{}: {} ({})", index, game.path_root.file_name_string(), reason),
+        None => println!("{}: {}", index, game.path_root.file_name_string()),
+      } // match
     } // for
     return Ok(());
   } // else if
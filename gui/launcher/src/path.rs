@@ -0,0 +1,44 @@
+use anyhow::anyhow as ah;
+
+use crate::db;
+
+// pub fn db_env() {{{
+// Database with the environment variables of the game
+pub fn db_env() -> anyhow::Result<std::path::PathBuf>
+{
+  let mut path_db : std::path::PathBuf = std::env::var("GIMG_LAUNCHER_ROOT")?.into();
+  path_db.push("gameimage.env.json");
+  Ok(path_db)
+} // pub fn db_env() }}}
+
+// pub fn dir_boot() {{{
+// Directory that contains the wine prefix, executables are relative to it
+pub fn dir_boot() -> anyhow::Result<std::path::PathBuf>
+{
+  Ok(std::path::PathBuf::from(std::env::var("GIMG_LAUNCHER_BOOT")?)
+    .parent()
+    .ok_or(ah!("Could not fetch parent path for boot directory"))?
+    .to_owned())
+} // pub fn dir_boot() }}}
+
+// pub fn dir_game() {{{
+// Directory of the selected executable, or of the default one
+pub fn dir_game() -> anyhow::Result<std::path::PathBuf>
+{
+  let path_file_executable = match std::env::var("GIMG_LAUNCHER_EXECUTABLE")
+  {
+    Ok(executable) => std::path::PathBuf::from(executable),
+    Err(_) =>
+    {
+      let path_file_db = std::path::PathBuf::from(std::env::var("GIMG_LAUNCHER_ROOT")? + "/gameimage.json");
+      db::project::read(&path_file_db)?.path_file_rom.ok_or(ah!("Could not read path_file_rom"))?
+    },
+  }; // match
+  Ok(dir_boot()?
+    .join(path_file_executable)
+    .parent()
+    .ok_or(ah!("Could not fetch parent path for executable"))?
+    .to_owned())
+} // pub fn dir_game() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
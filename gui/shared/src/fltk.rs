@@ -14,6 +14,8 @@ pub mod separator;
 pub mod frame;
 pub mod macros;
 pub mod paginator;
+pub mod overrides;
+pub mod graphics;
 
 // pub fn theme() {{{
 pub fn theme()
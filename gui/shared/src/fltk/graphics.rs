@@ -0,0 +1,124 @@
+use std::
+{
+  path::PathBuf,
+  sync::{Arc,Mutex},
+};
+
+use fltk::prelude::*;
+use fltk::{
+  enums::{Align,Color},
+  frame::Frame,
+  menu::MenuButton,
+};
+
+use crate::dimm;
+use crate::fltk::WidgetExtExtra;
+use crate::{hpack,row,fixed};
+use crate::wine::graphics::{Settings,HUDS,FRAME_RATES,FEATURE_LEVELS};
+
+// struct Target {{{
+// Where the settings are written
+#[derive(Clone)]
+struct Target
+{
+  settings           : Arc<Mutex<Settings>>,
+  path_file_env      : PathBuf,
+  opt_path_file_conf : Option<PathBuf>,
+} // struct Target }}}
+
+impl Target
+{
+
+// fn update() {{{
+fn update(&self, f_change : impl FnOnce(&mut Settings))
+{
+  let mut settings = self.settings.lock().unwrap();
+  f_change(&mut settings);
+  if let Err(e) = settings.write(&self.path_file_env, self.opt_path_file_conf.as_ref())
+  {
+    eprintln!("Could not write graphics settings: {}", e);
+  } // if
+} // fn update() }}}
+
+} // impl Target
+
+// fn menu() {{{
+// Row with a label and a menu of options, the callback receives the index of the option
+fn menu(label : &str, options : Vec<String>, index : usize, target : Target, f_change : fn(&mut Settings, usize))
+{
+  row!(row,
+    row.add(&Frame::default().with_align(Align::Left | Align::Inside).with_label(label));
+    fixed!(row, menu, MenuButton::default(), dimm::width_button_wide() * 3);
+  );
+  row.resize(row.x(), row.y(), row.w(), dimm::height_button_wide());
+  let mut menu = menu.clone();
+  options.iter().for_each(|e| { menu.add_choice(&e.replace("/", "\\/")); });
+  menu.set_label(options.get(index).map(|e| e.as_str()).unwrap_or_default());
+  menu.set_callback(move |e|
+  {
+    let index = e.value();
+    if index < 0 { return; } // if
+    e.set_label(&options[index as usize]);
+    target.update(|settings| f_change(settings, index as usize));
+  });
+} // fn menu() }}}
+
+// fn check() {{{
+// Check button, the callback receives if it is checked
+fn check(label : &str, value : bool, target : Target, f_change : fn(&mut Settings, bool))
+{
+  let mut btn = crate::fltk::button::rect::checkbutton()
+    .with_size(0, dimm::height_button_wide())
+    .with_align(Align::Inside | Align::Left)
+    .with_color(Color::BackGround)
+    .with_label(&format!(" {}", label));
+  btn.set_value(value);
+  btn.set_callback(move |e|
+  {
+    let value = e.is_checked();
+    target.update(|settings| f_change(settings, value));
+  });
+} // fn check() }}}
+
+// pub fn panel() {{{
+// Column with the graphics and synchronization settings of a wine project, changes are written to
+// the environment database and to 'dxvk.conf' as they are made
+pub fn panel(path_file_env : PathBuf, opt_path_file_conf : Option<PathBuf>) -> fltk::group::Pack
+{
+  let settings = Settings::read(&path_file_env, opt_path_file_conf.as_ref());
+  let target = Target{ settings: Arc::new(Mutex::new(settings.clone())), path_file_env, opt_path_file_conf };
+  hpack!(col,
+    col.set_spacing(dimm::border_half());
+    menu("DXVK overlay"
+      , HUDS.iter().map(|e| e.0.to_string()).collect()
+      , HUDS.iter().position(|e| e.1 == settings.hud).unwrap_or(0)
+      , target.clone()
+      , |settings, index| settings.hud = HUDS[index].1.to_string()
+    );
+    menu("Frame rate limit"
+      , FRAME_RATES.iter().map(|e| if *e == 0 { "Unlimited".to_string() } else { e.to_string() }).collect()
+      , FRAME_RATES.iter().position(|e| *e == settings.frame_rate).unwrap_or(0)
+      , target.clone()
+      , |settings, index| settings.frame_rate = FRAME_RATES[index]
+    );
+    menu("VKD3D feature level"
+      , FEATURE_LEVELS.iter().map(|e| if e.is_empty() { "Default".to_string() } else { e.replace('_', ".") }).collect()
+      , FEATURE_LEVELS.iter().position(|e| *e == settings.feature_level).unwrap_or(0)
+      , target.clone()
+      , |settings, index| settings.feature_level = FEATURE_LEVELS[index].to_string()
+    );
+    check("Asynchronous shader compilation (dxvk-async)", settings.is_async, target.clone()
+      , |settings, value| settings.is_async = value);
+    check("DXVK state cache", settings.is_state_cache, target.clone()
+      , |settings, value| settings.is_state_cache = value);
+    check("Eventfd synchronization (esync)", settings.is_esync, target.clone()
+      , |settings, value| settings.is_esync = value);
+    check("Futex synchronization (fsync)", settings.is_fsync, target.clone()
+      , |settings, value| settings.is_fsync = value);
+    check("Large address aware for 32-bit games", settings.is_large_address_aware, target.clone()
+      , |settings, value| settings.is_large_address_aware = value);
+  );
+  col
+} // fn panel() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,45 @@
+use fltk::prelude::*;
+use fltk::{
+  enums::Align,
+  menu::MenuButton,
+  output::Output,
+};
+
+use crate::dimm;
+use crate::{row,add,fixed};
+use crate::wine::dll::Mode;
+
+// Label of the entry without an override
+const LABEL_DEFAULT : &str = "Default";
+
+// pub fn entry() {{{
+// Row with a library and a menu to select how wine loads it, 'None' keeps the default of wine
+pub fn entry(name : &str
+  , origin : &str
+  , opt_mode : Option<Mode>
+  , mut f_change : impl FnMut(Option<Mode>) + 'static) -> fltk::group::Flex
+{
+  row!(row,
+    add!(row, output, Output::default());
+    fixed!(row, menu, MenuButton::default(), dimm::width_button_wide() * 2);
+  );
+  row.resize(row.x(), row.y(), row.w(), dimm::height_button_wide());
+  // Library and where it was found
+  let mut output = output.clone();
+  output.set_align(Align::Left | Align::Inside);
+  output.set_value(&if origin.is_empty() { name.to_string() } else { format!("{}  ({})", name, origin) });
+  // Modes
+  let mut menu = menu.clone();
+  menu.add_choice(LABEL_DEFAULT);
+  Mode::ALL.iter().for_each(|e| { menu.add_choice(e.label()); });
+  menu.set_label(opt_mode.map(|e| e.label()).unwrap_or(LABEL_DEFAULT));
+  menu.set_callback(move |e|
+  {
+    let Some(choice) = e.choice() else { return; };
+    e.set_label(&choice);
+    f_change(Mode::ALL.iter().find(|m| m.label() == choice).copied());
+  });
+  row
+} // fn entry() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -1,9 +1,463 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow as ah;
+
+use fltk::prelude::ImageExt;
+use fltk::prelude::SurfaceDevice;
+
+use crate::dimm;
+
+// pub enum Format {{{
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format
+{
+  Jpg,
+  Png,
+  Webp,
+  Bmp,
+  Ico,
+  Svg,
+} // }}}
+
+// impl Format {{{
+impl Format
+{
+  pub fn as_str(&self) -> &'static str
+  {
+    match self
+    {
+      Format::Jpg  => "jpg",
+      Format::Png  => "png",
+      Format::Webp => "webp",
+      Format::Bmp  => "bmp",
+      Format::Ico  => "ico",
+      Format::Svg  => "svg",
+    } // match
+  } // as_str
+
+  pub fn from_path(path : &PathBuf) -> anyhow::Result<Format>
+  {
+    let ext = path.extension().ok_or(ah!("No file extension in '{}'", path.display()))?;
+    ext.to_string_lossy().parse::<Format>().map_err(|_| ah!("Unsupported image format '{}'", path.display()))
+  } // from_path
+
+  // Filter string for fltk file choosers
+  pub fn filter() -> &'static str
+  {
+    "*.{jpg,jpeg,png,webp,bmp,ico,svg}"
+  } // filter
+
+  fn image_format(&self) -> Option<image::ImageFormat>
+  {
+    match self
+    {
+      Format::Jpg  => Some(image::ImageFormat::Jpeg),
+      Format::Png  => Some(image::ImageFormat::Png),
+      Format::Webp => Some(image::ImageFormat::WebP),
+      Format::Bmp  => Some(image::ImageFormat::Bmp),
+      Format::Ico  => Some(image::ImageFormat::Ico),
+      Format::Svg  => None,
+    } // match
+  } // image_format
+} // impl Format }}}
+
+// impl FromStr for Format {{{
+impl std::str::FromStr for Format
+{
+  type Err = anyhow::Error;
+
+  fn from_str(src : &str) -> anyhow::Result<Format>
+  {
+    match src.to_lowercase().as_str()
+    {
+      "jpg" | "jpeg" => Ok(Format::Jpg),
+      "png"          => Ok(Format::Png),
+      "webp"         => Ok(Format::Webp),
+      "bmp"          => Ok(Format::Bmp),
+      "ico"          => Ok(Format::Ico),
+      "svg"          => Ok(Format::Svg),
+      _              => Err(ah!("Unknown image format '{}'", src)),
+    } // match
+  } // from_str
+} // impl FromStr for Format }}}
+
+// pub enum Fit {{{
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fit
+{
+  Crop,
+  Pad,
+  Stretch,
+  Keep,
+} // }}}
+
+// impl Fit {{{
+impl Fit
+{
+  pub fn as_str(&self) -> &'static str
+  {
+    match self
+    {
+      Fit::Crop    => "crop",
+      Fit::Pad     => "pad",
+      Fit::Stretch => "stretch",
+      Fit::Keep    => "keep",
+    } // match
+  } // as_str
+
+} // impl Fit }}}
+
+// impl FromStr for Fit {{{
+impl std::str::FromStr for Fit
+{
+  type Err = anyhow::Error;
+
+  fn from_str(src : &str) -> anyhow::Result<Fit>
+  {
+    match src.to_lowercase().as_str()
+    {
+      "crop"    => Ok(Fit::Crop),
+      "pad"     => Ok(Fit::Pad),
+      "stretch" => Ok(Fit::Stretch),
+      "keep"    => Ok(Fit::Keep),
+      _         => Err(ah!("Unknown fit '{}'", src)),
+    } // match
+  } // from_str
+} // impl FromStr for Fit }}}
+
+// pub enum Artwork {{{
+// Roles an image can have in a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Artwork
+{
+  // Launcher background, also the image installed as the project icon
+  Cover,
+  // Wide image used in game lists
+  Banner,
+  // Wide image used behind lists and menus
+  Background,
+  // Transparent title drawn over other images
+  Logo,
+  // Square image used for desktop integration
+  Icon,
+} // }}}
+
+// impl Artwork {{{
+impl Artwork
+{
+  pub fn all() -> [Artwork; 5]
+  {
+    [Artwork::Cover, Artwork::Banner, Artwork::Background, Artwork::Logo, Artwork::Icon]
+  } // all
+
+  pub fn as_str(&self) -> &'static str
+  {
+    match self
+    {
+      Artwork::Cover      => "cover",
+      Artwork::Banner     => "banner",
+      Artwork::Background => "background",
+      Artwork::Logo       => "logo",
+      Artwork::Icon       => "icon",
+    } // match
+  } // as_str
+
+  // Name of the file in the project icon directory
+  pub fn file_name(&self) -> String
+  {
+    format!("{}.png", self.as_str())
+  } // file_name
+
+  // Roles to try in order when this one is not set
+  pub fn fallbacks(&self) -> &'static [Artwork]
+  {
+    match self
+    {
+      Artwork::Cover      => &[Artwork::Background, Artwork::Banner],
+      Artwork::Banner     => &[Artwork::Cover],
+      Artwork::Background => &[Artwork::Cover, Artwork::Banner],
+      Artwork::Logo       => &[],
+      Artwork::Icon       => &[Artwork::Cover],
+    } // match
+  } // fallbacks
+
+  // Default processing for the role
+  pub fn pipeline(&self) -> Pipeline
+  {
+    let transparent = [0, 0, 0, 0];
+    match self
+    {
+      Artwork::Cover      => Pipeline::default(),
+      Artwork::Banner     => Pipeline{ aspect: (460, 215), ..Pipeline::default() },
+      Artwork::Background => Pipeline{ aspect: (16, 9), ..Pipeline::default() },
+      Artwork::Logo       => Pipeline{ fit: Fit::Keep, color: transparent, ..Pipeline::default() },
+      Artwork::Icon       => Pipeline{ fit: Fit::Pad, aspect: (1, 1), color: transparent, ..Pipeline::default() },
+    } // match
+  } // pipeline
+} // impl Artwork }}}
+
+// impl FromStr for Artwork {{{
+impl std::str::FromStr for Artwork
+{
+  type Err = anyhow::Error;
+
+  fn from_str(src : &str) -> anyhow::Result<Artwork>
+  {
+    Artwork::all().into_iter()
+      .find(|e| e.as_str() == src.to_lowercase())
+      .ok_or(ah!("Unknown artwork '{}'", src))
+  } // from_str
+} // impl FromStr for Artwork }}}
+
+// pub struct Pipeline {{{
+#[derive(Debug, Clone, Copy)]
+pub struct Pipeline
+{
+  // How to reach the target aspect ratio
+  pub fit    : Fit,
+  // Target aspect ratio as width:height
+  pub aspect : (u32, u32),
+  // Position of the crop window along the axis that is cut, 0.0 is top/left and 1.0 is bottom/right
+  pub offset : f32,
+  // Rgba color of the letterbox bars
+  pub color  : [u8; 4],
+} // struct Pipeline }}}
+
+impl Default for Pipeline
+{
+  fn default() -> Self
+  {
+    Pipeline
+    {
+      fit    : Fit::Crop,
+      aspect : (dimm::width_launcher() as u32, dimm::height_launcher() as u32),
+      offset : 0.5,
+      color  : [0, 0, 0, 255],
+    }
+  } // default
+} // impl Default
+
+impl Pipeline
+{
+
+// pub fn apply() {{{
+pub fn apply(&self, img : image::DynamicImage) -> image::DynamicImage
+{
+  match self.fit
+  {
+    Fit::Crop    => crop(img, self.aspect, self.offset),
+    Fit::Pad     => pad(img, self.aspect, self.color),
+    Fit::Stretch =>
+    {
+      let (w, h) = (img.width(), img.height());
+      let (width, height) = if w * self.aspect.1 > h * self.aspect.0
+      {
+        (w, (w as u64 * self.aspect.1 as u64 / self.aspect.0.max(1) as u64) as u32)
+      }
+      else
+      {
+        ((h as u64 * self.aspect.0 as u64 / self.aspect.1.max(1) as u64) as u32, h)
+      }; // else
+      img.resize_exact(width.max(1), height.max(1), image::imageops::FilterType::CatmullRom)
+    },
+    Fit::Keep => img,
+  } // match
+} // fn apply() }}}
+
+// pub fn run() {{{
+pub fn run(&self, path_out : PathBuf, path_in : PathBuf) -> anyhow::Result<()>
+{
+  save(&self.apply(open(&path_in)?), &path_out)
+} // fn run() }}}
+
+// pub fn preview() {{{
+pub fn preview(&self, path_out : PathBuf, path_in : PathBuf, width : u32, height : u32) -> anyhow::Result<()>
+{
+  let img = self.apply(open(&path_in)?);
+  save(&img.resize(width, height, image::imageops::FilterType::Triangle), &path_out)
+} // fn preview() }}}
+
+} // impl Pipeline
+
+// pub fn open() {{{
+pub fn open(path : &PathBuf) -> anyhow::Result<image::DynamicImage>
+{
+  // Svg files are rasterized by fltk
+  if Format::from_path(path).ok() == Some(Format::Svg)
+  {
+    return rasterize(path, 0, 0);
+  } // if
+  Ok(image::io::Reader::open(path)?.with_guessed_format()?.decode()?)
+} // fn open() }}}
+
+// pub fn rasterize() {{{
+// Renders a svg file, width or height as zero keeps the size declared in the file
+pub fn rasterize(path : &PathBuf, width : u32, height : u32) -> anyhow::Result<image::DynamicImage>
+{
+  let mut svg = fltk::image::SvgImage::load(path)?;
+  svg.normalize();
+  if width > 0 && height > 0
+  {
+    svg = svg.copy_sized(width as i32, height as i32);
+  } // if
+  from_fltk(&svg).ok_or(ah!("Could not rasterize svg '{}'", path.display()))
+} // fn rasterize() }}}
+
+// fn from_fltk() {{{
+// Copies the pixels of a fltk image
+fn from_fltk<I: ImageExt>(img : &I) -> Option<image::DynamicImage>
+{
+  let (w, h) = (img.data_w() as u32, img.data_h() as u32);
+  let data = img.to_rgb_data();
+  match img.depth()
+  {
+    fltk::enums::ColorDepth::Rgba8 => image::RgbaImage::from_raw(w, h, data).map(image::DynamicImage::ImageRgba8),
+    fltk::enums::ColorDepth::Rgb8  => image::RgbImage::from_raw(w, h, data).map(image::DynamicImage::ImageRgb8),
+    fltk::enums::ColorDepth::La8   => image::GrayAlphaImage::from_raw(w, h, data).map(image::DynamicImage::ImageLumaA8),
+    fltk::enums::ColorDepth::L8    => image::GrayImage::from_raw(w, h, data).map(image::DynamicImage::ImageLuma8),
+  } // match
+} // fn from_fltk() }}}
+
+// pub fn placeholder() {{{
+// Renders a cover with the title in the center and a badge in the bottom right corner, requires
+// the fltk display to be open
+pub fn placeholder(path_out : &PathBuf, title : &str, badge : &str, width : i32, height : i32) -> anyhow::Result<()>
+{
+  use fltk::{draw, enums::{Align,Color,Font}, surface::ImageSurface};
+  let border = dimm::border();
+  let surface = ImageSurface::new(width, height, false);
+  ImageSurface::push_current(&surface);
+  // Background
+  draw::draw_rect_fill(0, 0, width, height, Color::from_rgb(32, 32, 32));
+  // Title
+  draw::set_draw_color(Color::White);
+  draw::set_font(Font::HelveticaBold, (height / 12).max(dimm::height_text()));
+  draw::draw_text2(title, border, 0, width - border*2, height, Align::Center | Align::Wrap);
+  // Badge
+  draw::set_font(Font::Helvetica, dimm::height_text());
+  let (w_badge, h_badge) = draw::measure(badge, false);
+  let (w_badge, h_badge) = (w_badge + border*2, h_badge + border);
+  let (x_badge, y_badge) = (width - w_badge - border, height - h_badge - border);
+  draw::draw_rbox(x_badge, y_badge, w_badge, h_badge, border / 2, true, Color::Blue);
+  draw::set_draw_color(Color::White);
+  draw::draw_text2(badge, x_badge, y_badge, w_badge, h_badge, Align::Center);
+  let opt_img = surface.image();
+  ImageSurface::pop_current();
+  let img = opt_img.and_then(|e| from_fltk(&e)).ok_or(ah!("Could not render placeholder for '{}'", title))?;
+  save(&img, path_out)
+} // fn placeholder() }}}
+
+// pub fn save() {{{
+pub fn save(img : &image::DynamicImage, path_out : &PathBuf) -> anyhow::Result<()>
+{
+  let format = Format::from_path(path_out)?;
+  let image_format = format.image_format().ok_or(ah!("Cannot write raster image as '{}'", format.as_str()))?;
+  match format
+  {
+    // Jpeg has no alpha channel
+    Format::Jpg => image::DynamicImage::ImageRgb8(img.to_rgb8()).save_with_format(path_out, image_format)?,
+    // Ico entries are limited to 256x256
+    Format::Ico if img.width() > 256 || img.height() > 256 =>
+    {
+      img.resize(256, 256, image::imageops::FilterType::CatmullRom).save_with_format(path_out, image_format)?
+    },
+    _ => img.save_with_format(path_out, image_format)?,
+  } // match
+  Ok(())
+} // fn save() }}}
+
+// pub fn convert() {{{
+pub fn convert(path_out : PathBuf, path_in : PathBuf) -> anyhow::Result<()>
+{
+  match (Format::from_path(&path_in)?, Format::from_path(&path_out)?)
+  {
+    (Format::Svg, Format::Svg) => { std::fs::copy(&path_in, &path_out)?; Ok(()) },
+    (_, Format::Svg) => Err(ah!("Cannot convert raster image '{}' to svg", path_in.display())),
+    _ => save(&open(&path_in)?, &path_out),
+  } // match
+} // fn convert() }}}
+
+// pub fn crop() {{{
+pub fn crop(img : image::DynamicImage, aspect : (u32, u32), offset : f32) -> image::DynamicImage
+{
+  let (w, h) = (img.width() as u64, img.height() as u64);
+  let (aw, ah) = (aspect.0.max(1) as u64, aspect.1.max(1) as u64);
+  let offset = offset.clamp(0.0, 1.0);
+  // Image is wider than the target, cut the sides
+  if w * ah > h * aw
+  {
+    let width = (h * aw / ah).max(1);
+    let x = ((w - width) as f32 * offset) as u32;
+    img.crop_imm(x, 0, width as u32, h as u32)
+  } // if
+  // Image is taller than the target, cut top and bottom
+  else
+  {
+    let height = (w * ah / aw).max(1);
+    let y = ((h - height) as f32 * offset) as u32;
+    img.crop_imm(0, y, w as u32, height as u32)
+  } // else
+} // fn crop() }}}
+
+// pub fn pad() {{{
+pub fn pad(img : image::DynamicImage, aspect : (u32, u32), color : [u8; 4]) -> image::DynamicImage
+{
+  let (w, h) = (img.width() as u64, img.height() as u64);
+  let (aw, ah) = (aspect.0.max(1) as u64, aspect.1.max(1) as u64);
+  let (width, height) = if w * ah > h * aw { (w, w * ah / aw) } else { (h * aw / ah, h) };
+  let mut canvas = image::RgbaImage::from_pixel(width as u32, height as u32, image::Rgba(color));
+  image::imageops::overlay(&mut canvas, &img.to_rgba8(), ((width - w) / 2) as i64, ((height - h) / 2) as i64);
+  image::DynamicImage::ImageRgba8(canvas)
+} // fn pad() }}}
+
+// pub fn grayscale() {{{
+pub fn grayscale(path_out : PathBuf, path_in : PathBuf) -> anyhow::Result<()>
+{
+  save(&open(&path_in)?.grayscale(), &path_out)
+} // fn grayscale() }}}
+
+// Sizes of the hicolor icon theme, in pixels
+pub const SIZES_HICOLOR : [u32; 9] = [16, 22, 24, 32, 48, 64, 128, 256, 512];
+
+// pub fn hicolor() {{{
+// Creates the hicolor layout '<size>x<size>/apps/<name>.png' in path_dir_out, svg inputs are
+// rasterized for each size and also copied to 'scalable/apps/<name>.svg'
+pub fn hicolor(path_dir_out : PathBuf, path_in : PathBuf, name : &str) -> anyhow::Result<Vec<PathBuf>>
+{
+  let is_svg = Format::from_path(&path_in)? == Format::Svg;
+  // Icons are square, keep transparency around non-square images
+  let img = if is_svg { None } else { Some(pad(open(&path_in)?, (1, 1), [0, 0, 0, 0])) };
+  let mut vec_path_icons = Vec::<PathBuf>::new();
+  for size in SIZES_HICOLOR
+  {
+    let path_dir_size = path_dir_out.join(format!("{}x{}", size, size)).join("apps");
+    std::fs::create_dir_all(&path_dir_size)?;
+    let path_icon = path_dir_size.join(format!("{}.png", name));
+    let img_sized = match &img
+    {
+      Some(img) => img.resize_exact(size, size, image::imageops::FilterType::Lanczos3),
+      None => pad(rasterize(&path_in, size, size)?, (1, 1), [0, 0, 0, 0]),
+    }; // match
+    save(&img_sized, &path_icon)?;
+    vec_path_icons.push(path_icon);
+  } // for
+  if is_svg
+  {
+    let path_dir_scalable = path_dir_out.join("scalable").join("apps");
+    std::fs::create_dir_all(&path_dir_scalable)?;
+    let path_icon = path_dir_scalable.join(format!("{}.svg", name));
+    std::fs::copy(&path_in, &path_icon)?;
+    vec_path_icons.push(path_icon);
+  } // if
+  Ok(vec_path_icons)
+} // fn hicolor() }}}
+
 // pub fn resize() {{{
 pub fn resize(path_out : std::path::PathBuf, path_in : std::path::PathBuf, width : u32, height : u32) -> anyhow::Result<()>
 {
-  let mut img = image::io::Reader::open(path_in)?.decode()?;
+  let mut img = open(&path_in)?;
   img = img.resize(width, height, image::imageops::FilterType::CatmullRom);
   Ok(img.save(path_out)?)
-} // }}}
+} // fn resize() }}}
 
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
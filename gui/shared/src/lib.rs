@@ -5,5 +5,6 @@ pub mod svg;
 pub mod std;
 pub mod image;
 pub mod db;
+pub mod wine;
 
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
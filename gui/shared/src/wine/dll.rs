@@ -0,0 +1,240 @@
+use std::
+{
+  collections::BTreeMap,
+  fs,
+  io::Read,
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+use crate::db::kv;
+use crate::wine::registry;
+
+// Key of the overrides in 'user.reg'
+pub const KEY_OVERRIDES : &str = "Software\\Wine\\DllOverrides";
+// Variable read by wine
+pub const ENV_OVERRIDES : &str = "WINEDLLOVERRIDES";
+
+// pub enum Mode {{{
+// How wine loads a library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode
+{
+  NativeBuiltin,
+  BuiltinNative,
+  Native,
+  Builtin,
+  Disabled,
+} // enum Mode }}}
+
+impl Mode
+{
+
+pub const ALL : [Mode; 5] = [Mode::NativeBuiltin, Mode::BuiltinNative, Mode::Native, Mode::Builtin, Mode::Disabled];
+
+// pub fn as_str() {{{
+// Data of the registry value
+pub fn as_str(&self) -> &'static str
+{
+  match self
+  {
+    Mode::NativeBuiltin => "native,builtin",
+    Mode::BuiltinNative => "builtin,native",
+    Mode::Native        => "native",
+    Mode::Builtin       => "builtin",
+    Mode::Disabled      => "",
+  } // match
+} // fn as_str() }}}
+
+// pub fn as_env() {{{
+// Mode in the syntax of the environment variable
+pub fn as_env(&self) -> &'static str
+{
+  match self
+  {
+    Mode::NativeBuiltin => "n,b",
+    Mode::BuiltinNative => "b,n",
+    Mode::Native        => "n",
+    Mode::Builtin       => "b",
+    Mode::Disabled      => "",
+  } // match
+} // fn as_env() }}}
+
+// pub fn label() {{{
+pub fn label(&self) -> &'static str
+{
+  match self
+  {
+    Mode::NativeBuiltin => "Native, then builtin",
+    Mode::BuiltinNative => "Builtin, then native",
+    Mode::Native        => "Native",
+    Mode::Builtin       => "Builtin",
+    Mode::Disabled      => "Disabled",
+  } // match
+} // fn label() }}}
+
+} // impl Mode
+
+impl std::str::FromStr for Mode
+{
+
+type Err = anyhow::Error;
+
+// fn from_str() {{{
+// Accepts both the registry and the environment syntax
+fn from_str(text : &str) -> anyhow::Result<Mode>
+{
+  let modes : Vec<&str> = text.split(',')
+    .map(|e| e.trim())
+    .filter(|e| ! e.is_empty())
+    .collect();
+  let f_is = |e : &str, name : &str| e.eq_ignore_ascii_case(name) || e.eq_ignore_ascii_case(&name[..1]);
+  match modes.as_slice()
+  {
+    [] => Ok(Mode::Disabled),
+    [a] if f_is(a, "native") => Ok(Mode::Native),
+    [a] if f_is(a, "builtin") => Ok(Mode::Builtin),
+    [a, b] if f_is(a, "native") && f_is(b, "builtin") => Ok(Mode::NativeBuiltin),
+    [a, b] if f_is(a, "builtin") && f_is(b, "native") => Ok(Mode::BuiltinNative),
+    _ => Err(ah!("Unknown override mode '{}'", text)),
+  } // match
+} // fn from_str() }}}
+
+} // impl FromStr for Mode
+
+pub type Overrides = BTreeMap<String, Mode>;
+
+// fn name() {{{
+// Overrides are keyed by the library name in lowercase without the extension
+fn name(text : &str) -> String
+{
+  let text = text.trim().to_lowercase();
+  text.strip_suffix(".dll").map(|e| e.to_string()).unwrap_or(text)
+} // fn name() }}}
+
+// pub fn parse_env() {{{
+// Parses the variable, e.g., 'd3d9,d3d11=n,b;dinput8=n;mscoree='
+pub fn parse_env(text : &str) -> Overrides
+{
+  let mut overrides = Overrides::new();
+  for entry in text.split(';')
+  {
+    let Some((names, mode)) = entry.split_once('=') else { continue; };
+    let Ok(mode) = mode.parse::<Mode>() else { continue; };
+    names.split(',').map(name).filter(|e| ! e.is_empty()).for_each(|e| { overrides.insert(e, mode); });
+  } // for
+  overrides
+} // fn parse_env() }}}
+
+// pub fn to_env() {{{
+// Libraries with the same mode are grouped
+pub fn to_env(overrides : &Overrides) -> String
+{
+  Mode::ALL.iter()
+    .filter_map(|mode|
+    {
+      let names : Vec<&str> = overrides.iter().filter(|(_,m)| *m == mode).map(|(k,_)| k.as_str()).collect();
+      (! names.is_empty()).then(|| format!("{}={}", names.join(","), mode.as_env()))
+    })
+    .collect::<Vec<String>>()
+    .join(";")
+} // fn to_env() }}}
+
+// pub fn read_registry() {{{
+// Overrides of the prefix
+pub fn read_registry(path_dir_prefix : &PathBuf) -> anyhow::Result<Overrides>
+{
+  let registry = registry::Registry::open(&path_dir_prefix.join("user.reg"))?;
+  Ok(registry.key(KEY_OVERRIDES)
+    .map(|key| key.values.iter()
+      .filter_map(|(k,v)| Some((name(k), registry::unescape(v.trim_matches('"')).parse::<Mode>().ok()?)))
+      .collect())
+    .unwrap_or_default())
+} // fn read_registry() }}}
+
+// pub fn write_registry() {{{
+// Replaces the overrides of the prefix, wine must not be running
+pub fn write_registry(path_dir_prefix : &PathBuf, overrides : &Overrides) -> anyhow::Result<()>
+{
+  let path_file_reg = path_dir_prefix.join("user.reg");
+  let mut registry = registry::Registry::open(&path_file_reg)?;
+  let key = registry.key_mut(KEY_OVERRIDES);
+  key.values.clear();
+  overrides.iter().for_each(|(k,v)| key.set(k, &registry::string(v.as_str())));
+  registry.save(&path_file_reg)
+} // fn write_registry() }}}
+
+// pub fn read() {{{
+// Overrides of the prefix registry and of the environment database, the environment takes precedence
+pub fn read(path_file_env : &PathBuf, path_dir_prefix : &PathBuf) -> Overrides
+{
+  let mut overrides = read_registry(path_dir_prefix).unwrap_or_default();
+  if let Ok(env) = kv::read(path_file_env)
+  && let Some(value) = env.get(ENV_OVERRIDES)
+  {
+    overrides.extend(parse_env(value));
+  } // if
+  overrides
+} // fn read() }}}
+
+// pub fn write() {{{
+// Writes the overrides to the environment database and to the prefix registry
+pub fn write(path_file_env : &PathBuf, path_dir_prefix : &PathBuf, overrides : &Overrides) -> anyhow::Result<()>
+{
+  if overrides.is_empty()
+  {
+    let _ = kv::erase(path_file_env, ENV_OVERRIDES.to_string());
+  } // if
+  else
+  {
+    kv::write(path_file_env, &ENV_OVERRIDES.to_string(), &to_env(overrides))?;
+  } // else
+  write_registry(path_dir_prefix, overrides)
+} // fn write() }}}
+
+// fn is_builtin() {{{
+// Libraries created by wine have a signature after the dos header
+fn is_builtin(path_file : &PathBuf) -> bool
+{
+  let mut data = [0u8; 0x60];
+  fs::File::open(path_file).and_then(|mut e| e.read_exact(&mut data)).is_ok()
+    && data[0x40..].starts_with(b"Wine builtin DLL")
+} // fn is_builtin() }}}
+
+// pub fn list() {{{
+// Libraries that can be overridden, with where they were found. Those in the game directory
+// and the native ones installed in the prefix, e.g., by winetricks.
+pub fn list(path_dir_game : Option<&PathBuf>, path_dir_prefix : &PathBuf) -> BTreeMap<String, String>
+{
+  let mut libraries = BTreeMap::new();
+  let f_read_dir = |path_dir : &PathBuf| -> Vec<PathBuf>
+  {
+    fs::read_dir(path_dir).map(|e| e
+      .filter_map(|e| e.ok())
+      .map(|e| e.path())
+      .filter(|e| e.is_file() && e.extension().is_some_and(|e| e.eq_ignore_ascii_case("dll")))
+      .collect()
+    ).unwrap_or_default()
+  };
+  for dir in ["system32", "syswow64"]
+  {
+    for path_file in f_read_dir(&path_dir_prefix.join("drive_c").join("windows").join(dir))
+    {
+      if is_builtin(&path_file) { continue; } // if
+      let name_file = path_file.file_name().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+      libraries.insert(name(&name_file), format!("prefix ({})", dir));
+    } // for
+  } // for
+  if let Some(path_dir_game) = path_dir_game
+  {
+    for path_file in f_read_dir(path_dir_game)
+    {
+      let name_file = path_file.file_name().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+      libraries.insert(name(&name_file), "game directory".to_string());
+    } // for
+  } // if
+  libraries
+} // fn list() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,168 @@
+use std::
+{
+  fs,
+  path::PathBuf,
+};
+
+use crate::db::kv;
+
+// Overlay of dxvk, as label and value of 'DXVK_HUD'
+pub const HUDS : [(&str, &str); 5] =
+[
+  ("Disabled", ""),
+  ("FPS", "fps"),
+  ("FPS and frame times", "fps,frametimes"),
+  ("FPS, frame times and GPU load", "fps,frametimes,gpuload"),
+  ("Full", "full"),
+];
+// Frame rate limits, zero is unlimited
+pub const FRAME_RATES : [u32; 8] = [0, 30, 60, 75, 120, 144, 165, 240];
+// Direct3D 12 feature levels reported by vkd3d, empty is the default
+pub const FEATURE_LEVELS : [&str; 5] = ["", "11_0", "12_0", "12_1", "12_2"];
+
+// pub struct Settings {{{
+// Graphics and synchronization options of a wine project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings
+{
+  pub hud                    : String,
+  pub frame_rate             : u32,
+  pub is_async               : bool,
+  pub is_state_cache         : bool,
+  pub feature_level          : String,
+  pub is_esync               : bool,
+  pub is_fsync               : bool,
+  pub is_large_address_aware : bool,
+} // struct Settings }}}
+
+impl Default for Settings
+{
+  fn default() -> Settings
+  {
+    Settings
+    {
+      hud: String::new(),
+      frame_rate: 0,
+      is_async: false,
+      is_state_cache: true,
+      feature_level: String::new(),
+      is_esync: false,
+      is_fsync: false,
+      is_large_address_aware: false,
+    }
+  }
+}
+
+// fn conf_read() {{{
+// Options of a 'dxvk.conf' file, as 'key = value' lines
+fn conf_read(path_file_conf : &PathBuf) -> Vec<(String, String)>
+{
+  fs::read_to_string(path_file_conf)
+    .unwrap_or_default()
+    .lines()
+    .filter(|e| ! e.trim_start().starts_with('#'))
+    .filter_map(|e| e.split_once('='))
+    .map(|(k,v)| (k.trim().to_string(), v.trim().to_string()))
+    .collect()
+} // fn conf_read() }}}
+
+// fn conf_write() {{{
+// Sets or erases options of a 'dxvk.conf' file, other lines are kept
+fn conf_write(path_file_conf : &PathBuf, options : &[(&str, Option<String>)]) -> anyhow::Result<()>
+{
+  let text = fs::read_to_string(path_file_conf).unwrap_or_default();
+  let mut lines : Vec<String> = text.lines()
+    .filter(|line| ! options.iter().any(|(key,_)|
+      line.split_once('=').is_some_and(|(k,_)| k.trim() == *key)
+    ))
+    .map(|e| e.to_string())
+    .collect();
+  options.iter()
+    .filter_map(|(key, opt_value)| opt_value.as_ref().map(|value| format!("{} = {}", key, value)))
+    .for_each(|e| lines.push(e));
+  // Do not leave an empty file behind
+  if lines.iter().all(|e| e.trim().is_empty())
+  {
+    if path_file_conf.exists() { fs::remove_file(path_file_conf)?; } // if
+    return Ok(());
+  } // if
+  fs::write(path_file_conf, lines.join("\n") + "\n")?;
+  Ok(())
+} // fn conf_write() }}}
+
+impl Settings
+{
+
+// pub fn read() {{{
+// Reads the settings from the environment database and from 'dxvk.conf'
+pub fn read(path_file_env : &PathBuf, opt_path_file_conf : Option<&PathBuf>) -> Settings
+{
+  let env = kv::read(path_file_env).unwrap_or_default();
+  let conf = opt_path_file_conf.map(conf_read).unwrap_or_default();
+  let f_env = |key : &str| env.get(key).cloned().unwrap_or_default();
+  let f_conf = |key : &str| conf.iter().find(|(k,_)| k == key).map(|(_,v)| v.clone());
+  let frame_rate = f_conf("dxgi.maxFrameRate")
+    .or(env.get("DXVK_FRAME_RATE").cloned())
+    .and_then(|e| e.parse().ok())
+    .unwrap_or(0);
+  Settings
+  {
+    hud: f_env("DXVK_HUD"),
+    frame_rate,
+    is_async: f_env("DXVK_ASYNC") == "1" || f_conf("dxvk.enableAsync").is_some_and(|e| e.eq_ignore_ascii_case("true")),
+    is_state_cache: f_env("DXVK_STATE_CACHE") != "0",
+    feature_level: f_env("VKD3D_FEATURE_LEVEL"),
+    is_esync: f_env("WINEESYNC") == "1",
+    is_fsync: f_env("WINEFSYNC") == "1",
+    is_large_address_aware: f_env("WINE_LARGE_ADDRESS_AWARE") == "1",
+  }
+} // fn read() }}}
+
+// pub fn write() {{{
+// Writes the variables to the environment database and the frame rate and async compilation to
+// 'dxvk.conf', which dxvk reads from the directory of the game. Without a 'dxvk.conf' the frame
+// rate goes to the environment.
+pub fn write(&self, path_file_env : &PathBuf, opt_path_file_conf : Option<&PathBuf>) -> anyhow::Result<()>
+{
+  let f_flag = |value : bool| value.then(|| "1".to_string());
+  let f_text = |value : &String| (! value.is_empty()).then(|| value.clone());
+  let frame_rate = (self.frame_rate > 0).then(|| self.frame_rate.to_string());
+  let mut env : Vec<(&str, Option<String>)> = vec![
+    ("DXVK_HUD", f_text(&self.hud)),
+    ("DXVK_ASYNC", f_flag(self.is_async)),
+    ("DXVK_STATE_CACHE", (! self.is_state_cache).then(|| "0".to_string())),
+    ("VKD3D_FEATURE_LEVEL", f_text(&self.feature_level)),
+    ("WINEESYNC", f_flag(self.is_esync)),
+    ("WINEFSYNC", f_flag(self.is_fsync)),
+    ("WINE_LARGE_ADDRESS_AWARE", f_flag(self.is_large_address_aware)),
+  ];
+  match opt_path_file_conf
+  {
+    Some(path_file_conf) =>
+    {
+      env.push(("DXVK_FRAME_RATE", None));
+      conf_write(path_file_conf, &[
+        ("dxgi.maxFrameRate", frame_rate.clone()),
+        ("d3d9.maxFrameRate", frame_rate),
+        ("dxvk.enableAsync", self.is_async.then(|| "True".to_string())),
+      ])?;
+    },
+    None => env.push(("DXVK_FRAME_RATE", frame_rate)),
+  } // match
+  for (key, opt_value) in env
+  {
+    match opt_value
+    {
+      Some(value) => kv::write(path_file_env, &key.to_string(), &value)?,
+      None => if kv::read(path_file_env).is_ok_and(|e| e.contains_key(key))
+      {
+        kv::erase(path_file_env, key.to_string())?;
+      }, // if
+    } // match
+  } // for
+  Ok(())
+} // fn write() }}}
+
+} // impl Settings
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,7 @@
+pub mod registry;
+pub mod dll;
+pub mod tweak;
+pub mod graphics;
+pub mod server;
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,444 @@
+use std::
+{
+  fs,
+  path::PathBuf,
+  time::{SystemTime,UNIX_EPOCH},
+};
+
+use anyhow::anyhow as ah;
+
+// pub struct Key {{{
+// A key of a registry file, values keep the text of their data as written by wine, e.g.,
+// '"native,builtin"' or 'dword:00000001'
+#[derive(Debug, Clone, Default)]
+pub struct Key
+{
+  // Path relative to the root of the file, without escapes
+  pub path   : String,
+  // Text after the closing bracket, the modification time
+  pub header : String,
+  // Lines that start with '#', e.g., '#time=' and '#class='
+  pub meta   : Vec<String>,
+  // Name without escapes, empty for the default value, and data
+  pub values : Vec<(String, String)>,
+} // struct Key }}}
+
+impl Key
+{
+
+// pub fn get() {{{
+pub fn get(&self, name : &str) -> Option<&String>
+{
+  self.values.iter().find(|(k,_)| k.eq_ignore_ascii_case(name)).map(|(_,v)| v)
+} // fn get() }}}
+
+// pub fn set() {{{
+pub fn set(&mut self, name : &str, data : &str)
+{
+  match self.values.iter_mut().find(|(k,_)| k.eq_ignore_ascii_case(name))
+  {
+    Some(value) => value.1 = data.to_string(),
+    None => self.values.push((name.to_string(), data.to_string())),
+  } // match
+} // fn set() }}}
+
+// pub fn erase() {{{
+pub fn erase(&mut self, name : &str)
+{
+  self.values.retain(|(k,_)| ! k.eq_ignore_ascii_case(name));
+} // fn erase() }}}
+
+} // impl Key
+
+// pub struct Registry {{{
+// A wine registry file, e.g., 'system.reg', 'user.reg' or 'userdef.reg'
+#[derive(Debug, Clone, Default)]
+pub struct Registry
+{
+  // Lines before the first key, the version, the root of the keys and the architecture
+  pub preamble : Vec<String>,
+  pub keys     : Vec<Key>,
+} // struct Registry }}}
+
+// pub fn unescape() {{{
+// Reverts the escapes of key paths, value names and strings like the parser of wine, escapes
+// are UTF-16 code units in hexadecimal or octal, other escaped characters are kept as is
+pub fn unescape(text : &str) -> String
+{
+  let mut units : Vec<u16> = Vec::new();
+  let mut buffer = [0u16; 2];
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next()
+  {
+    if c != '\\' { units.extend_from_slice(c.encode_utf16(&mut buffer)); continue; } // if
+    let Some(c) = chars.next() else { break; };
+    match c
+    {
+      'a' => units.push(0x07),
+      'b' => units.push(0x08),
+      'e' => units.push(0x1b),
+      'f' => units.push(0x0c),
+      'n' => units.push(0x0a),
+      'r' => units.push(0x0d),
+      't' => units.push(0x09),
+      'v' => units.push(0x0b),
+      'x' =>
+      {
+        let mut opt_unit : Option<u16> = None;
+        for _ in 0..4
+        {
+          let Some(digit) = chars.peek().and_then(|e| e.to_digit(16)) else { break; };
+          opt_unit = Some(opt_unit.unwrap_or(0) * 16 + digit as u16);
+          chars.next();
+        } // for
+        // Without digits the escape is the letter itself
+        units.push(opt_unit.unwrap_or('x' as u16));
+      },
+      '0'..='7' =>
+      {
+        let mut unit = c as u16 - '0' as u16;
+        for _ in 0..2
+        {
+          let Some(digit) = chars.peek().and_then(|e| e.to_digit(8)) else { break; };
+          unit = unit * 8 + digit as u16;
+          chars.next();
+        } // for
+        units.push(unit);
+      },
+      c => units.extend_from_slice(c.encode_utf16(&mut buffer)),
+    } // match
+  } // while
+  String::from_utf16_lossy(&units)
+} // fn unescape() }}}
+
+// fn escape_with() {{{
+// Escapes the text like wine does, non-ASCII UTF-16 code units are written in hexadecimal and
+// control characters as C escapes or in octal. Escapes are padded when the next character would
+// be read as one of their digits. 'special' has the delimiters, e.g., the brackets of key paths.
+fn escape_with(text : &str, special : &str) -> String
+{
+  const ESCAPES : &[u8; 32] = b".......abtnvfr.............e....";
+  let units : Vec<u16> = text.encode_utf16().collect();
+  let mut result = String::new();
+  for (index, unit) in units.iter().copied().enumerate()
+  {
+    let next = units.get(index+1).copied().filter(|e| *e < 128).map(|e| e as u8 as char);
+    match unit
+    {
+      128.. if next.is_some_and(|e| e.is_ascii_hexdigit()) => result.push_str(&format!("\\x{:04x}", unit)),
+      128.. => result.push_str(&format!("\\x{:x}", unit)),
+      0..32 if ESCAPES[unit as usize] != b'.' =>
+      {
+        result.push('\\');
+        result.push(ESCAPES[unit as usize] as char);
+      },
+      0..32 if next.is_some_and(|e| ('0'..='7').contains(&e)) => result.push_str(&format!("\\{:03o}", unit)),
+      0..32 => result.push_str(&format!("\\{:o}", unit)),
+      _ =>
+      {
+        let c = unit as u8 as char;
+        if c == '\\' || special.contains(c) { result.push('\\'); } // if
+        result.push(c);
+      },
+    } // match
+  } // for
+  result
+} // fn escape_with() }}}
+
+// pub fn escape() {{{
+// Escapes value names and strings, which are delimited by quotes
+pub fn escape(text : &str) -> String
+{
+  escape_with(text, "\"")
+} // fn escape() }}}
+
+// fn split_value() {{{
+// Splits '"name"=data' or '@=data' in the unescaped name and the data
+fn split_value(line : &str) -> Option<(String, String)>
+{
+  if let Some(data) = line.strip_prefix("@=") { return Some((String::new(), data.to_string())); } // if
+  let line = line.strip_prefix('"')?;
+  // Find the closing quote that is not escaped
+  let mut is_escaped = false;
+  for (i, c) in line.char_indices()
+  {
+    if is_escaped { is_escaped = false; continue; } // if
+    match c
+    {
+      '\\' => is_escaped = true,
+      '"' => return Some((unescape(&line[..i]), line[i+1..].strip_prefix('=')?.to_string())),
+      _ => (),
+    } // match
+  } // for
+  None
+} // fn split_value() }}}
+
+impl Registry
+{
+
+// pub fn parse() {{{
+pub fn parse(text : &str) -> Registry
+{
+  let mut registry = Registry::default();
+  let mut lines = text.lines();
+  while let Some(line) = lines.next()
+  {
+    if let Some(rest) = line.strip_prefix('[')
+    {
+      // The path ends at the last closing bracket, escaped brackets are part of the path
+      let index = rest.rfind(']').unwrap_or(rest.len());
+      registry.keys.push(Key
+      {
+        path: unescape(&rest[..index]),
+        header: rest.get(index+1..).unwrap_or_default().to_string(),
+        ..Default::default()
+      });
+      continue;
+    } // if
+    let Some(key) = registry.keys.last_mut() else { registry.preamble.push(line.to_string()); continue; };
+    if line.starts_with('#') { key.meta.push(line.to_string()); continue; } // if
+    let Some((name, mut data)) = split_value(line) else { continue; };
+    // Binary data continues in the next lines
+    while data.ends_with('\\') && let Some(line) = lines.next()
+    {
+      data.push('\n');
+      data.push_str(line);
+    } // while
+    key.values.push((name, data));
+  } // while
+  registry
+} // fn parse() }}}
+
+// pub fn open() {{{
+pub fn open(path_file : &PathBuf) -> anyhow::Result<Registry>
+{
+  Ok(Registry::parse(&fs::read_to_string(path_file)?))
+} // fn open() }}}
+
+// pub fn text() {{{
+pub fn text(&self) -> String
+{
+  let mut text = String::new();
+  self.preamble.iter().for_each(|e| { text.push_str(e); text.push('\n'); });
+  for key in &self.keys
+  {
+    text.push_str(&format!("[{}]{}\n", escape_with(&key.path, "[]"), key.header));
+    key.meta.iter().for_each(|e| { text.push_str(e); text.push('\n'); });
+    for (name, data) in &key.values
+    {
+      if name.is_empty() { text.push_str(&format!("@={}\n", data)); } // if
+      else { text.push_str(&format!("\"{}\"={}\n", escape(name), data)); } // else
+    } // for
+    text.push('\n');
+  } // for
+  text
+} // fn text() }}}
+
+// pub fn save() {{{
+// Writes to a temporary file first, so the registry is never left truncated. Wine keeps the
+// registry in memory and writes it back on exit, so the files of a running prefix are not changed.
+pub fn save(&self, path_file : &PathBuf) -> anyhow::Result<()>
+{
+  if self.preamble.is_empty()
+  {
+    return Err(ah!("Refusing to write '{}' without the registry header", path_file.display()));
+  } // if
+  if let Some(path_dir_prefix) = path_file.parent() && crate::wine::server::is_running(&path_dir_prefix.to_path_buf())
+  {
+    return Err(ah!("Wine is running in the prefix, close it before changing the registry"));
+  } // if
+  let path_file_tmp = path_file.with_extension("reg.tmp");
+  fs::write(&path_file_tmp, self.text())?;
+  fs::rename(&path_file_tmp, path_file)?;
+  Ok(())
+} // fn save() }}}
+
+// pub fn key() {{{
+pub fn key(&self, path : &str) -> Option<&Key>
+{
+  self.keys.iter().find(|e| e.path.eq_ignore_ascii_case(path))
+} // fn key() }}}
+
+// pub fn key_mut() {{{
+// Finds or creates the key
+pub fn key_mut(&mut self, path : &str) -> &mut Key
+{
+  if let Some(index) = self.keys.iter().position(|e| e.path.eq_ignore_ascii_case(path))
+  {
+    return &mut self.keys[index];
+  } // if
+  let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|e| e.as_secs()).unwrap_or(0);
+  self.keys.push(Key{ path: path.to_string(), header: format!(" {}", time), ..Default::default() });
+  self.keys.last_mut().unwrap()
+} // fn key_mut() }}}
+
+} // impl Registry
+
+// pub fn string() {{{
+// Data of a string value
+pub fn string(text : &str) -> String
+{
+  format!("\"{}\"", escape(text))
+} // fn string() }}}
+
+// pub enum Data {{{
+// Decoded data of a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Data
+{
+  String(String),
+  ExpandString(String),
+  Dword(u32),
+  // Binary and multi string data, as written by wine
+  Other(String),
+} // enum Data }}}
+
+impl Data
+{
+
+// pub fn decode() {{{
+pub fn decode(raw : &str) -> Data
+{
+  if let Some(text) = raw.strip_prefix('"').and_then(|e| e.strip_suffix('"'))
+  {
+    return Data::String(unescape(text));
+  } // if
+  if let Some(text) = raw.strip_prefix("str(2):\"").and_then(|e| e.strip_suffix('"'))
+  {
+    return Data::ExpandString(unescape(text));
+  } // if
+  if let Some(value) = raw.strip_prefix("dword:").and_then(|e| u32::from_str_radix(e.trim(), 16).ok())
+  {
+    return Data::Dword(value);
+  } // if
+  Data::Other(raw.to_string())
+} // fn decode() }}}
+
+// pub fn encode() {{{
+pub fn encode(&self) -> String
+{
+  match self
+  {
+    Data::String(text) => string(text),
+    Data::ExpandString(text) => format!("str(2):\"{}\"", escape(text)),
+    Data::Dword(value) => format!("dword:{:08x}", value),
+    Data::Other(raw) => raw.clone(),
+  } // match
+} // fn encode() }}}
+
+// pub fn kind() {{{
+pub fn kind(&self) -> &'static str
+{
+  match self
+  {
+    Data::String(_) => "REG_SZ",
+    Data::ExpandString(_) => "REG_EXPAND_SZ",
+    Data::Dword(_) => "REG_DWORD",
+    Data::Other(_) => "other",
+  } // match
+} // fn kind() }}}
+
+// pub fn text() {{{
+// Data as displayed and edited
+pub fn text(&self) -> String
+{
+  match self
+  {
+    Data::String(text) | Data::ExpandString(text) => text.clone(),
+    Data::Dword(value) => format!("{:#010x} ({})", value, value),
+    Data::Other(raw) => raw.replace("\\\n", "").split_whitespace().collect(),
+  } // match
+} // fn text() }}}
+
+// pub fn with_text() {{{
+// Data of the same kind from an edited text, numbers are decimal or hexadecimal with '0x'
+pub fn with_text(&self, text : &str) -> anyhow::Result<Data>
+{
+  let text = text.trim_end_matches('\n');
+  Ok(match self
+  {
+    Data::String(_) => Data::String(text.to_string()),
+    Data::ExpandString(_) => Data::ExpandString(text.to_string()),
+    Data::Dword(_) =>
+    {
+      let text = text.split_whitespace().next().unwrap_or_default();
+      Data::Dword(match text.strip_prefix("0x")
+      {
+        Some(hex) => u32::from_str_radix(hex, 16)?,
+        None => text.parse()?,
+      }) // match
+    },
+    Data::Other(_) => Data::Other(text.to_string()),
+  })
+} // fn with_text() }}}
+
+} // impl Data
+
+#[cfg(test)]
+mod tests
+{
+
+use super::{escape, unescape, Data, Registry};
+
+// fn escape_wine() {{{
+// Escapes as written by wine
+#[test]
+fn escape_wine()
+{
+  assert_eq!(escape("C:\\\"x\""), "C:\\\\\\\"x\\\"");
+  assert_eq!(escape("caf\u{e9}"), "caf\\xe9");
+  assert_eq!(escape("\u{e9}1"), "\\x00e91");
+  assert_eq!(escape("\u{5fae}\u{8f6f}"), "\\x5fae\\x8f6f");
+  assert_eq!(escape("\u{1f600}"), "\\xd83d\\xde00");
+  assert_eq!(escape("\u{7}\u{8}\t\n\u{b}\u{c}\r\u{1b}"), "\\a\\b\\t\\n\\v\\f\\r\\e");
+  assert_eq!(escape("\u{1}x\u{1}7\0"), "\\1x\\0017\\0");
+} // fn escape_wine() }}}
+
+// fn unescape_wine() {{{
+// Escapes as read by wine
+#[test]
+fn unescape_wine()
+{
+  assert_eq!(unescape("\\x00e91\\xe9"), "\u{e9}1\u{e9}");
+  assert_eq!(unescape("\\xd83d\\xde00"), "\u{1f600}");
+  assert_eq!(unescape("\\a\\b\\e\\f\\n\\r\\t\\v"), "\u{7}\u{8}\u{1b}\u{c}\n\r\t\u{b}");
+  assert_eq!(unescape("\\0017\\1x\\101"), "\u{1}7\u{1}xA");
+  assert_eq!(unescape("\\xz\\q\\\\\\\""), "xzq\\\"");
+  let text = "a\u{1}\u{7f}\u{e9}\u{5fae}9\u{1f600}\"\\[]";
+  assert_eq!(unescape(&escape(text)), text);
+} // fn unescape_wine() }}}
+
+// fn round_trip() {{{
+// A registry is written back as it was read
+#[test]
+fn round_trip()
+{
+  let text = "WINE REGISTRY Version 2\n\
+    ;; All keys relative to \\\\Machine\n\
+    #arch=win64\n\
+    \n\
+    [Software\\\\Microsoft\\\\Windows NT\\\\CurrentVersion\\\\Fonts] 1700000000\n\
+    #time=1da1b2c3d4e5f60\n\
+    \"\\x5fae\\x8f6f\\x96c5\\x9ed1 (TrueType)\"=\"msyh.ttc\"\n\
+    \"Caf\\xe9 \\\"Bold\\\"\"=\"caf\\xe9\\tb.ttf\"\n\
+    @=\"default\"\n\
+    \n\
+    [Software\\\\Wine\\\\Key \\[1\\]] 1700000001\n\
+    \"Binary\"=hex:00,01,02,\\\n  03,04\n\
+    \"Dword\"=dword:00000001\n\
+    \n";
+  let mut registry = Registry::parse(text);
+  assert_eq!(registry.text(), text);
+  let key = registry.key("Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts").unwrap();
+  assert_eq!(key.get("\u{5fae}\u{8f6f}\u{96c5}\u{9ed1} (TrueType)"), Some(&"\"msyh.ttc\"".to_string()));
+  assert_eq!(Data::decode(key.get("Caf\u{e9} \"Bold\"").unwrap()), Data::String("caf\u{e9}\tb.ttf".into()));
+  assert!(registry.key("Software\\Wine\\Key [1]").is_some());
+  // Edited values are written with the escapes of wine
+  let key = registry.key_mut("Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts");
+  key.set("\u{e9}", &Data::String("\u{e9}1".into()).encode());
+  assert!(registry.text().contains("\"\\xe9\"=\"\\x00e91\"\n"));
+} // fn round_trip() }}}
+
+} // mod tests
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,21 @@
+use std::
+{
+  fs,
+  os::unix::fs::MetadataExt,
+  path::PathBuf,
+};
+
+// pub fn is_running() {{{
+// The wine server of a prefix listens on a socket in '/tmp/.wine-<uid>/server-<dev>-<inode>', named
+// after the device and the inode of the prefix directory. The socket is removed when it exits.
+pub fn is_running(path_dir_prefix : &PathBuf) -> bool
+{
+  let Ok(metadata) = fs::metadata(path_dir_prefix) else { return false; };
+  let Ok(uid) = fs::metadata("/proc/self").map(|e| e.uid()) else { return false; };
+  PathBuf::from(format!("/tmp/.wine-{}", uid))
+    .join(format!("server-{:x}-{:x}", metadata.dev(), metadata.ino()))
+    .join("socket")
+    .exists()
+} // fn is_running() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use crate::wine::registry::Registry;
+
+// pub struct Choice {{{
+// Values written for an option of a tweak, as key, name and data. Values without data are erased.
+pub struct Choice
+{
+  pub label  : &'static str,
+  pub values : &'static [(&'static str, &'static str, Option<&'static str>)],
+} // struct Choice }}}
+
+// pub struct Tweak {{{
+// A common change to the registry of the user, 'user.reg'
+pub struct Tweak
+{
+  pub label   : &'static str,
+  pub choices : &'static [Choice],
+} // struct Tweak }}}
+
+const KEY_WINE      : &str = "Software\\Wine";
+const KEY_EXPLORER  : &str = "Software\\Wine\\Explorer";
+const KEY_DESKTOPS  : &str = "Software\\Wine\\Explorer\\Desktops";
+const KEY_INPUT     : &str = "Software\\Wine\\DirectInput";
+const KEY_DIRECT3D  : &str = "Software\\Wine\\Direct3D";
+const KEY_DRIVERS   : &str = "Software\\Wine\\Drivers";
+
+// Tweaks of the wine configuration
+pub static TWEAKS : [Tweak; 5] =
+[
+  Tweak{ label: "Windows version", choices: &[
+    Choice{ label: "Default", values: &[(KEY_WINE, "Version", None)] },
+    Choice{ label: "Windows 11", values: &[(KEY_WINE, "Version", Some("\"win11\""))] },
+    Choice{ label: "Windows 10", values: &[(KEY_WINE, "Version", Some("\"win10\""))] },
+    Choice{ label: "Windows 8.1", values: &[(KEY_WINE, "Version", Some("\"win81\""))] },
+    Choice{ label: "Windows 7", values: &[(KEY_WINE, "Version", Some("\"win7\""))] },
+    Choice{ label: "Windows Vista", values: &[(KEY_WINE, "Version", Some("\"vista\""))] },
+    Choice{ label: "Windows XP", values: &[(KEY_WINE, "Version", Some("\"winxp\""))] },
+    Choice{ label: "Windows 98", values: &[(KEY_WINE, "Version", Some("\"win98\""))] },
+  ]},
+  Tweak{ label: "Virtual desktop", choices: &[
+    Choice{ label: "Disabled", values: &[(KEY_EXPLORER, "Desktop", None)] },
+    Choice{ label: "1280x720", values: &[(KEY_EXPLORER, "Desktop", Some("\"Default\"")), (KEY_DESKTOPS, "Default", Some("\"1280x720\""))] },
+    Choice{ label: "1600x900", values: &[(KEY_EXPLORER, "Desktop", Some("\"Default\"")), (KEY_DESKTOPS, "Default", Some("\"1600x900\""))] },
+    Choice{ label: "1920x1080", values: &[(KEY_EXPLORER, "Desktop", Some("\"Default\"")), (KEY_DESKTOPS, "Default", Some("\"1920x1080\""))] },
+    Choice{ label: "2560x1440", values: &[(KEY_EXPLORER, "Desktop", Some("\"Default\"")), (KEY_DESKTOPS, "Default", Some("\"2560x1440\""))] },
+  ]},
+  Tweak{ label: "Mouse warp", choices: &[
+    Choice{ label: "Default", values: &[(KEY_INPUT, "MouseWarpOverride", None)] },
+    Choice{ label: "Enable", values: &[(KEY_INPUT, "MouseWarpOverride", Some("\"enable\""))] },
+    Choice{ label: "Disable", values: &[(KEY_INPUT, "MouseWarpOverride", Some("\"disable\""))] },
+    Choice{ label: "Force", values: &[(KEY_INPUT, "MouseWarpOverride", Some("\"force\""))] },
+  ]},
+  Tweak{ label: "Direct3D renderer", choices: &[
+    Choice{ label: "Default", values: &[(KEY_DIRECT3D, "renderer", None)] },
+    Choice{ label: "OpenGL", values: &[(KEY_DIRECT3D, "renderer", Some("\"gl\""))] },
+    Choice{ label: "Vulkan", values: &[(KEY_DIRECT3D, "renderer", Some("\"vulkan\""))] },
+    Choice{ label: "No 3D", values: &[(KEY_DIRECT3D, "renderer", Some("\"no3d\""))] },
+  ]},
+  Tweak{ label: "Audio driver", choices: &[
+    Choice{ label: "Default", values: &[(KEY_DRIVERS, "Audio", None)] },
+    Choice{ label: "PulseAudio", values: &[(KEY_DRIVERS, "Audio", Some("\"pulse\""))] },
+    Choice{ label: "ALSA", values: &[(KEY_DRIVERS, "Audio", Some("\"alsa\""))] },
+    Choice{ label: "Disabled", values: &[(KEY_DRIVERS, "Audio", Some("\"\""))] },
+  ]},
+];
+
+impl Tweak
+{
+
+// pub fn current() {{{
+// Option whose values are all in the registry
+pub fn current(&self, registry : &Registry) -> Option<&'static Choice>
+{
+  self.choices.iter().find(|choice| choice.values.iter().all(|(key, name, opt_data)|
+  {
+    let data = registry.key(key).and_then(|e| e.get(name));
+    match (opt_data, data)
+    {
+      (Some(expected), Some(data)) => expected.eq_ignore_ascii_case(data),
+      (None, None) => true,
+      _ => false,
+    } // match
+  }))
+} // fn current() }}}
+
+} // impl Tweak
+
+impl Choice
+{
+
+// pub fn apply() {{{
+pub fn apply(&self, registry : &mut Registry)
+{
+  for (key, name, opt_data) in self.values
+  {
+    match opt_data
+    {
+      Some(data) => registry.key_mut(key).set(name, data),
+      None => if let Some(index) = registry.keys.iter().position(|e| e.path.eq_ignore_ascii_case(key))
+      {
+        registry.keys[index].erase(name);
+      }, // if
+    } // match
+  } // for
+} // fn apply() }}}
+
+} // impl Choice
+
+// pub fn apply() {{{
+// Applies the option to the registry of the user in the prefix, wine must not be running
+pub fn apply(path_dir_prefix : &PathBuf, choice : &Choice) -> anyhow::Result<()>
+{
+  let path_file_reg = path_dir_prefix.join("user.reg");
+  let mut registry = Registry::open(&path_file_reg)?;
+  choice.apply(&mut registry);
+  registry.save(&path_file_reg)
+} // fn apply() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
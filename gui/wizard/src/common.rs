@@ -1,6 +1,5 @@
 use std::
 {
-  sync::OnceLock,
   env,
   path,
 };
@@ -66,7 +65,11 @@ pub enum Msg
   DrawWineConfigure,
   DrawWineTricks,
   DrawWineEnvironment,
+  DrawWineDllOverrides,
+  DrawWineFonts,
+  DrawWineGraphics,
   DrawWineRom,
+  DrawWineSlim,
   DrawWineCompress,
 
   DrawRetroarchName,
@@ -101,61 +104,70 @@ pub enum Msg
 } // }}}
 
 // impl_log() {{{
-pub fn impl_log(value : &str)
+pub fn impl_log(level : crate::lib::log::Level, target : &str, value : &str)
 {
-  static TX: OnceLock<std::sync::mpsc::Sender<String>> = OnceLock::new();
+  crate::lib::log::log(level, target, value);
+} // impl_log() }}}
 
-  // Initialize the logging channel and logger thread the first time impl_log is called
-  let sender = TX.get_or_init(||
+// macro_rules log_target! {{{
+#[macro_export]
+macro_rules! log_target
+{
+  () =>
   {
-    // Create a channel for log messages
-    let (tx, rx) = std::sync::mpsc::channel::<String>();
-
-    // Spawn a singleton logger thread that will consume messages and append them to the terminal
-    std::thread::spawn(move ||
-    {
-      // Fetch terminal
-      let mut term: Option<fltk::text::SimpleTerminal>;
-      loop
-      {
-        let widget: Option<fltk::text::SimpleTerminal> = fltk::app::widget_from_id("term_log");
-        if widget.is_some() { term = widget; break; }
-      }
-
-      while let Ok(mut log_message) = rx.recv()
-      {
-        if ! log_message.ends_with("\n") { log_message = format!("{}\n", log_message); }
-        term.as_mut().unwrap().append(&log_message);
-      }
-    }); // std::thread
-
-    // Return the sender for future calls to impl_log
-    tx
-  });
-
-  // Send the log message to the logger thread
-  if let Err(e) = sender.send(value.to_string()) {
-    println!("Failed to send log message: {}", e);
+    format!("{}:{}"
+      , std::path::PathBuf::from(file!()).file_name().unwrap_or_default().to_str().unwrap_or("Unknown file")
+      , line!())
   }
-} // impl_log() }}}
+}
+// }}}
 
-// macro_rules log! {{{
+// macro_rules log_level! {{{
 #[macro_export]
-macro_rules! log
+macro_rules! log_level
 {
-  ($($arg:tt)*) =>
+  ($level:expr, $($arg:tt)*) =>
   {
     {
-      let output = format!("{}:{}: "
-        , std::path::PathBuf::from(file!()).file_name().unwrap_or_default().to_str().unwrap_or("Unknown file")
-        , line!()) + &format!($($arg)*);
-      common::impl_log(output.as_str());
-      eprintln!("{}", output);
+      let output = format!($($arg)*);
+      $crate::common::impl_log($level, &$crate::log_target!(), output.as_str());
     }
   }
 }
 // }}}
 
+// macro_rules log! {{{
+#[macro_export]
+macro_rules! log
+{
+  ($($arg:tt)*) => { $crate::log_level!($crate::lib::log::Level::Info, $($arg)*) }
+}
+// }}}
+
+// macro_rules log_debug! {{{
+#[macro_export]
+macro_rules! log_debug
+{
+  ($($arg:tt)*) => { $crate::log_level!($crate::lib::log::Level::Debug, $($arg)*) }
+}
+// }}}
+
+// macro_rules log_warn! {{{
+#[macro_export]
+macro_rules! log_warn
+{
+  ($($arg:tt)*) => { $crate::log_level!($crate::lib::log::Level::Warn, $($arg)*) }
+}
+// }}}
+
+// macro_rules log_error! {{{
+#[macro_export]
+macro_rules! log_error
+{
+  ($($arg:tt)*) => { $crate::log_level!($crate::lib::log::Level::Error, $($arg)*) }
+}
+// }}}
+
 // macro_rules log_alert! {{{
 #[macro_export]
 macro_rules! log_alert
@@ -164,9 +176,8 @@ macro_rules! log_alert
   {
     {
       let output = format!($($arg)*);
-      common::impl_log(output.as_str());
+      $crate::common::impl_log($crate::lib::log::Level::Warn, &$crate::log_target!(), output.as_str());
       fltk::dialog::alert_default(output.as_str());
-      eprintln!("{}", output);
     }
   }
 }
@@ -181,7 +192,7 @@ macro_rules! log_err
     match $result
     {
       Ok(()) => (),
-      Err(e) => log!("{:?}", e),
+      Err(e) => $crate::log_error!("{:?}", e),
     }
   }
 }
@@ -195,10 +206,9 @@ macro_rules! log_status
   {
     {
       let output = format!($($arg)*);
-      common::impl_log(output.as_str());
+      $crate::common::impl_log($crate::lib::log::Level::Info, &$crate::log_target!(), output.as_str());
       let mut status: fltk::output::Output = fltk::app::widget_from_id("footer_status").unwrap();
       status.set_value(&output);
-      eprintln!("{}", output);
     }
   }
 }
@@ -213,10 +223,9 @@ macro_rules! log_err_status
     if let Err(e) = $result
     {
       let err = e.to_string();
-      common::impl_log(err.as_str());
+      $crate::common::impl_log($crate::lib::log::Level::Error, &$crate::log_target!(), err.as_str());
       let mut status: fltk::output::Output = fltk::app::widget_from_id("footer_status").unwrap();
       status.set_value(&err);
-      eprintln!("{}", err);
     }
   }
 }
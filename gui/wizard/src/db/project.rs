@@ -3,11 +3,15 @@ use std::fs::File;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
+use shared::image::Artwork;
+
 use crate::db::global;
-use crate::common;
 use shared::std::PathBufExt;
 use crate::log;
 
+// Images by role name in the project directory, read by the launcher
+const FILE_ARTWORK : &str = "gameimage.artwork.json";
+
 #[allow(dead_code)]
 pub enum EntryName
 {
@@ -92,6 +96,30 @@ pub fn get_path_relative(&self, entry: EntryName) -> anyhow::Result<PathBuf>
   Ok(some_path_project_relative.ok_or(ah!("Could not read relative path"))?)
 } // get_dir_relative
 
+// Absolute path to the image of the role, the cover is the project icon
+pub fn get_path_artwork(&self, artwork: Artwork) -> anyhow::Result<PathBuf>
+{
+  if artwork == Artwork::Cover
+  {
+    return self.get_path_absolute(EntryName::PathFileIcon);
+  } // if
+  let path_dir_self = self.get_dir_self()?;
+  let path_file_artwork = shared::db::kv::read(&path_dir_self.join(FILE_ARTWORK))
+    .unwrap_or_default()
+    .remove(artwork.as_str())
+    .ok_or(ah!("No {} image in project '{}'", artwork.as_str(), self.project))?;
+  Ok(path_dir_self.join(path_file_artwork))
+} // get_path_artwork
+
+// Absolute path to the image of the role or to the first available fallback
+pub fn get_path_artwork_or_fallback(&self, artwork: Artwork) -> anyhow::Result<PathBuf>
+{
+  std::iter::once(&artwork)
+    .chain(artwork.fallbacks())
+    .find_map(|e| self.get_path_artwork(*e).ok().filter(|e| e.exists()))
+    .ok_or(ah!("No image available for {} in project '{}'", artwork.as_str(), self.project))
+} // get_path_artwork_or_fallback
+
 }
 // struct Entry }}}
 
@@ -151,4 +179,15 @@ pub fn current() -> anyhow::Result<Entry>
   Ok(serde_json::from_reader(file)?)
 } // current() }}}
 
+// pub fn set_artwork() {{{
+
+// Sets the path of an image role in the current project, the path is relative to the project
+// directory. Roles are kept out of 'gameimage.json', which the backend rewrites with its own keys
+pub fn set_artwork(artwork: Artwork, path_file_relative: &PathBuf) -> anyhow::Result<()>
+{
+  let global = global::read()?;
+  let path_file_db = global.get_project_dir(&global.project)?.join(FILE_ARTWORK);
+  shared::db::kv::write(&path_file_db, &artwork.as_str().to_string(), &path_file_relative.string())
+} // set_artwork() }}}
+
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
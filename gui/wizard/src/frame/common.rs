@@ -4,13 +4,16 @@ use fltk::{
   output::Output,
   frame::Frame,
   enums::{Align,Color},
+  window::Window,
 };
 
 use shared::fltk::WidgetExtExtra;
+use shared::svg;
 use shared::{tabs,hover_blink,hseparator_fixed,column,row,add,fixed};
 
 use crate::dimm;
 use crate::frame;
+use crate::log_status;
 
 // pub fn layout() {{{
 pub fn layout()
@@ -52,6 +55,8 @@ pub fn layout()
       column!(col_content_term,
         col_content_term.set_frame(fltk::enums::FrameType::FlatBox);
         col_content_term.set_color(Color::BackGround);
+        let log_viewer = frame::term::log_viewer();
+        col_content_term.fixed(&log_viewer, dimm::height_button_wide());
         let term = frame::term::Term::default();
         term.term.clone().set_id("term_log");
       );
@@ -87,4 +92,45 @@ pub fn layout()
 
 } // }}}
 
+// pub fn window() {{{
+// Window of the size of the wizard with the gameimage icon, opened on top of the wizard
+pub fn window(label : &str) -> Window
+{
+  let mut wind = Window::default()
+    .with_label(label)
+    .with_size(dimm::width_wizard(), dimm::height_wizard())
+    .center_screen();
+  // Window should be de-attached from other windows
+  if let Some(mut parent) = wind.parent()
+  {
+    parent.remove(&wind);
+  } // if
+  if let Some(image) = fltk::image::SvgImage::from_data(svg::ICON_GAMEIMAGE).ok()
+  {
+    wind.set_icon(Some(image.clone()));
+  } // if
+  wind
+} // fn window() }}}
+
+// pub fn background() {{{
+// Runs operations of a window in a thread while the window is deactivated, the window contents are
+// refreshed after each operation
+pub fn background<F>(wind : Window, f_refresh : F) -> impl Fn(Box<dyn FnOnce() -> anyhow::Result<()> + Send>) + Clone
+  where F : Fn() + Clone + Send + 'static
+{
+  move |f_operation : Box<dyn FnOnce() -> anyhow::Result<()> + Send>|
+  {
+    let mut wind = wind.clone();
+    let f_refresh = f_refresh.clone();
+    wind.deactivate();
+    std::thread::spawn(move ||
+    {
+      if let Err(e) = f_operation() { log_status!("{}", e); } // if
+      f_refresh();
+      wind.activate();
+      fltk::app::awake();
+    });
+  }
+} // fn background() }}}
+
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
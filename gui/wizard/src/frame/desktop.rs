@@ -18,16 +18,88 @@ use anyhow::anyhow as ah;
 use shared::fltk::SenderExt;
 use shared::fltk::WidgetExtExtra;
 use shared::std::PathBufExt;
+use shared::image::Artwork;
 
 use crate::common;
+use crate::db;
 use crate::dimm;
 use crate::log;
 use crate::log_err;
 use crate::log_status;
 use crate::gameimage;
 use crate::frame;
+use crate::lib;
 use clown::clown;
 
+// Icon sizes displayed in the small size preview
+const SIZES_PREVIEW : [u32; 4] = [16, 24, 32, 48];
+
+// fn hicolor_generate() {{{
+// Creates the hicolor icon set of the selected icon in the build directory, the set is kept while
+// the same icon file is selected
+fn hicolor_generate(path_file_icon : &PathBuf) -> anyhow::Result<PathBuf>
+{
+  let path_dir_hicolor = db::global::read()?.path_dir_build.join("desktop").join("hicolor");
+  let path_file_source = path_dir_hicolor.join("source");
+  let modified = std::fs::metadata(path_file_icon)?.modified()?;
+  let source = format!("{}\n{:?}", path_file_icon.string(), modified);
+  if std::fs::read_to_string(&path_file_source).is_ok_and(|e| e == source)
+  {
+    return Ok(path_dir_hicolor);
+  } // if
+  // Remove icons from a previous selection
+  if path_dir_hicolor.exists()
+  {
+    std::fs::remove_dir_all(&path_dir_hicolor)?;
+  } // if
+  let vec_path_icons = shared::image::hicolor(path_dir_hicolor.clone(), path_file_icon.clone(), "icon")?;
+  std::fs::write(&path_file_source, source)?;
+  log!("Generated {} icons in '{}'", vec_path_icons.len(), path_dir_hicolor.string());
+  Ok(path_dir_hicolor)
+} // fn hicolor_generate() }}}
+
+// fn hicolor_preview() {{{
+// Draws the icons of the hicolor set at their native size
+fn hicolor_preview(vec_frames : Vec<Frame>, path_dir_hicolor : &PathBuf) -> anyhow::Result<()>
+{
+  for (mut frame, size) in vec_frames.into_iter().zip(SIZES_PREVIEW)
+  {
+    let path_icon = path_dir_hicolor.join(format!("{}x{}", size, size)).join("apps").join("icon.png");
+    let png = fltk::image::PngImage::load(&path_icon)
+      .map_err(|e| ah!("Could not load icon '{}': {}", path_icon.string(), e))?;
+    frame.set_image(Some(png));
+    frame.redraw();
+  } // for
+  fltk::app::redraw();
+  fltk::app::awake();
+  Ok(())
+} // fn hicolor_preview() }}}
+
+// fn hicolor_preview_async() {{{
+// Generates the icon set in the background and draws the small size preview
+fn hicolor_preview_async(vec_frames : Vec<Frame>, path_file_icon : PathBuf)
+{
+  std::thread::spawn(move ||
+  {
+    match hicolor_generate(&path_file_icon).and_then(|e| hicolor_preview(vec_frames, &e))
+    {
+      Ok(_) => log_status!("Set small size preview"),
+      Err(e) => log_status!("Failed to generate small size preview: {}", e),
+    } // match
+  });
+} // fn hicolor_preview_async() }}}
+
+// fn icon_default() {{{
+// Desktop icon of the first selected project with an image for it
+fn icon_default() -> Option<PathBuf>
+{
+  let projects = frame::creator::PROJECTS.lock().unwrap().clone();
+  db::project::list().ok()?
+    .into_iter()
+    .filter(|e| projects.contains(&e.get_project()))
+    .find_map(|e| e.get_path_artwork_or_fallback(Artwork::Icon).ok())
+} // fn icon_default() }}}
+
 // fn desktop_next() {{{
 fn desktop_next(tx: Sender<common::Msg>
   , str_name: String
@@ -42,8 +114,11 @@ fn desktop_next(tx: Sender<common::Msg>
     .map_err(|_| ah!("Could not lock path_file_icon"))
     .map(|mut e| e.take())?
     .ok_or(ah!("No icon selected"))?;
+  // Wine projects are packaged with the distribution chosen for them
+  let projects = frame::creator::PROJECTS.lock().unwrap().clone();
+  lib::distribution::activate_projects(&projects)?;
   // Wait for message & check return value
-  if let Err(e) = gameimage::package::package(&str_name, frame::creator::PROJECTS.lock().unwrap().clone())
+  if let Err(e) = gameimage::package::package(&str_name, projects)
   {
     return Err(ah!("Could not include projects into the image: {}", e));
   } // match
@@ -61,7 +136,13 @@ fn desktop_next(tx: Sender<common::Msg>
   let integration_items = vec_integration_items.join(",");
   if ! integration_items.is_empty()
   {
-    match gameimage::desktop::icon(&path_file_icon)
+    // Generate icons for each size, the backend falls back to the single icon without them
+    let path_dir_hicolor = match hicolor_generate(&path_file_icon)
+    {
+      Ok(path_dir_hicolor) => Some(path_dir_hicolor),
+      Err(e) => { log!("Could not generate icon set: {}", e); None },
+    }; // match
+    match gameimage::desktop::icon(&path_file_icon, path_dir_hicolor.as_ref())
     {
       Ok(()) => log!("Finished icon configuration"),
       Err(e) => { tx.send_awake(common::Msg::WindActivate); return Err(ah!("{}", e)) }
@@ -135,6 +216,22 @@ pub fn desktop(tx: Sender<common::Msg>, title: &str)
   row.end();
   col.fixed(&row, 225);
 
+  // Preview of the icon at small sizes, as shown in menus and file managers
+  let mut row = Flex::default().row();
+  row.set_spacing(dimm::border());
+  row.fixed(&Frame::default().with_label("Small sizes").with_align(Align::Inside | Align::Left), 150);
+  let mut vec_frames_preview = Vec::<Frame>::new();
+  for size in SIZES_PREVIEW
+  {
+    let frame = Frame::default()
+      .with_align(Align::Inside | Align::Center);
+    row.fixed(&frame, size as i32 + dimm::border());
+    vec_frames_preview.push(frame);
+  } // for
+  row.add(&Frame::default());
+  row.end();
+  col.fixed(&row, *SIZES_PREVIEW.iter().max().unwrap_or(&48) as i32 + dimm::border());
+
   // Spacer
   col.add(&Frame::default());
 
@@ -150,18 +247,25 @@ pub fn desktop(tx: Sender<common::Msg>, title: &str)
   row.end();
   col.fixed(&row, dimm::height_button_wide() + dimm::border()/2);
 
+  // Default to the desktop icon of the selected projects
+  if OPTION_PATH_FILE_ICON.lock().unwrap().is_none()
+  {
+    *OPTION_PATH_FILE_ICON.lock().unwrap() = icon_default();
+  } // if
+
   // Check path cache
   if let Some(path_file_icon) = OPTION_PATH_FILE_ICON.lock().unwrap().clone()
   {
     input_icon.set_value(&path_file_icon.string());
     log_err!(crate::frame::icon::resize_draw_image(frame_icon.clone(), path_file_icon.clone()));
+    hicolor_preview_async(vec_frames_preview.clone(), path_file_icon.clone());
   } // if
 
   // // Set input_icon callback
   let mut clone_input_icon = input_icon.clone();
   btn_search.set_callback(move |_|
   {
-    let str_choice = match file_chooser("Select the icon", "*.{jpg,png}", ".", false)
+    let str_choice = match file_chooser("Select the icon", shared::image::Format::filter(), ".", false)
     {
       Some(str_choice) => str_choice,
       None => { log_status!("No file selected"); return; }
@@ -171,11 +275,13 @@ pub fn desktop(tx: Sender<common::Msg>, title: &str)
     // Show file path on selector
     clone_input_icon.set_value(str_choice.as_str());
     // Set preview image
-    match crate::frame::icon::resize_draw_image(frame_icon.clone(), str_choice.into())
+    match crate::frame::icon::resize_draw_image(frame_icon.clone(), str_choice.clone().into())
     {
       Ok(_) => log_status!("Set preview image"),
       Err(_) => log_status!("Failed to load icon image into preview"),
     } // match
+    // Set small size previews
+    hicolor_preview_async(vec_frames_preview.clone(), PathBuf::from(&str_choice));
   });
 
   // Callback to install projects and configure desktop integration
@@ -0,0 +1,194 @@
+use std::sync::{Arc,Mutex};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  dialog,
+  enums::{Color,Font,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row,fixed,add};
+
+use crate::dimm;
+use crate::frame;
+use crate::lib;
+use crate::gameimage;
+use crate::log;
+use crate::log_status;
+
+use lib::distribution::Distribution;
+
+// fn text() {{{
+// Comparison of the distributions and the runs of the selected one
+fn text(distributions : &[Distribution], opt_selected : Option<&Distribution>) -> String
+{
+  let runs = lib::history::list().unwrap_or_default();
+  let mut text = lib::distribution::compare(distributions, &runs);
+  if let Some(selected) = opt_selected
+  {
+    text.push_str(&format!("\nRuns with {}:\n", selected.name));
+    let runs : Vec<&lib::history::Run> = runs.iter().filter(|e| e.dist_wine.as_ref() == Some(&selected.name)).collect();
+    if runs.is_empty() { text.push_str("  None\n"); } // if
+    runs.iter().for_each(|e| text.push_str(&format!("  {}\n", e.summary())));
+  } // if
+  text
+} // fn text() }}}
+
+// fn fill() {{{
+fn fill(browser : &mut HoldBrowser, display : &mut TextDisplay, distributions : &Arc<Mutex<Vec<Distribution>>>)
+{
+  let mut distributions = distributions.lock().unwrap();
+  browser.clear();
+  match lib::distribution::list()
+  {
+    Ok(list) => *distributions = list,
+    Err(e) => { distributions.clear(); log_status!("Could not read the wine distributions: {}", e); },
+  } // match
+  distributions.iter().for_each(|e| browser.add(&e.summary()));
+  if let Some(mut buffer) = display.buffer() { buffer.set_text(&text(&distributions, None)); } // if
+} // fn fill() }}}
+
+// fn selected() {{{
+fn selected(browser : &HoldBrowser, distributions : &Arc<Mutex<Vec<Distribution>>>) -> Option<Distribution>
+{
+  // Lines start at one
+  let index = browser.value() as usize;
+  if index == 0 { return None; } // if
+  distributions.lock().unwrap().get(index-1).cloned()
+} // fn selected() }}}
+
+// pub fn distribution() {{{
+// Opens a window to fetch several wine distributions, test the current project with each of them,
+// compare their test runs and choose the one the project is packaged with
+pub fn distribution()
+{
+  let distributions = Arc::new(Mutex::new(Vec::<Distribution>::new()));
+  let mut wind = frame::common::window("Wine distributions");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() / 4);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    fixed!(col, prog, shared::fltk::progress::progress(), dimm::height_button_wide());
+    row!(row_buttons,
+      add!(row_buttons, btn_fetch, shared::fltk::button::wide::default().with_label("Fetch"));
+      add!(row_buttons, btn_test, shared::fltk::button::wide::default().with_label("Test"));
+      add!(row_buttons, btn_project, shared::fltk::button::wide::default().with_label("Use for project"));
+      add!(row_buttons, btn_close, shared::fltk::button::wide::default().with_label("Close"));
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  let prog = prog.clone()
+    .with_color(Color::BackGround)
+    .with_color_selected(Color::Blue);
+  // Distributions
+  browser.set_text_size(dimm::height_text());
+  display.wrap_mode(WrapMode::None, 0);
+  display.set_text_font(Font::Courier);
+  display.set_buffer(TextBuffer::default());
+  fill(&mut browser, &mut display, &distributions);
+  browser.set_callback({
+    let distributions = distributions.clone();
+    let display = display.clone();
+    move |e|
+    {
+      let opt_selected = selected(e, &distributions);
+      let text = text(&distributions.lock().unwrap(), opt_selected.as_ref());
+      if let Some(mut buffer) = display.buffer() { buffer.set_text(&text); } // if
+    }
+  });
+  // Runs the operation in the background and lists the distributions again
+  let f_background = frame::common::background(wind.clone(), {
+    let browser = browser.clone();
+    let display = display.clone();
+    let distributions = distributions.clone();
+    move || fill(&mut browser.clone(), &mut display.clone(), &distributions)
+  });
+  btn_fetch.clone().with_color(Color::Blue).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let distributions = distributions.clone();
+    let prog = prog.clone();
+    move |_|
+    {
+      let Some(distribution) = selected(&browser, &distributions) else { return; };
+      let mut prog = prog.clone();
+      prog.set_value(0.0);
+      f_background(Box::new(move ||
+      {
+        log_status!("Fetching wine distribution '{}'", distribution.name);
+        lib::distribution::fetch(&distribution.name, move |rx|
+        {
+          while let Ok(msg) = rx.recv()
+          {
+            match msg.parse::<f64>()
+            {
+              Ok(progress) => { prog.set_value(progress); fltk::app::awake(); },
+              Err(e) => { log!("Could not convert progress to float: {}", e); return; },
+            } // match
+          } // while
+        })?;
+        log_status!("Fetched wine distribution '{}'", distribution.name);
+        Ok(())
+      }));
+    }
+  });
+  btn_test.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let distributions = distributions.clone();
+    move |_|
+    {
+      let Some(distribution) = selected(&browser, &distributions) else { return; };
+      if ! distribution.is_fetched { log_status!("Fetch '{}' before testing it", distribution.name); return; } // if
+      f_background(Box::new(move ||
+      {
+        lib::distribution::activate(&distribution.name)?;
+        log_status!("Testing with wine distribution '{}'", distribution.name);
+        let code = gameimage::test::test();
+        log_status!("Test with wine distribution '{}' exited with code {}", distribution.name, code);
+        // The project keeps the distribution it was configured with
+        lib::distribution::activate_project()
+      }));
+    }
+  });
+  btn_project.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let distributions = distributions.clone();
+    move |_|
+    {
+      let Some(distribution) = selected(&browser, &distributions) else { return; };
+      if ! distribution.is_fetched { log_status!("Fetch '{}' before using it", distribution.name); return; } // if
+      if dialog::choice2_default(&format!("Package the project with '{}'?", distribution.name), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      f_background(Box::new(move ||
+      {
+        lib::distribution::set_project(&distribution.name)?;
+        log_status!("The project is packaged with wine distribution '{}'", distribution.name);
+        Ok(())
+      }));
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn distribution() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
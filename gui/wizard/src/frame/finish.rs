@@ -1,3 +1,10 @@
+use std::
+{
+  path::PathBuf,
+  sync::Mutex,
+  time::SystemTime,
+};
+
 // Gui
 use fltk::prelude::*;
 use fltk::{
@@ -11,7 +18,10 @@ use anyhow::anyhow as ah;
 use shared::fltk::WidgetExtExtra;
 
 use crate::db;
+use crate::lib;
 use crate::dimm;
+use crate::frame;
+use crate::log;
 use crate::log_status;
 use crate::common;
 use shared::std::OsStrExt;
@@ -25,6 +35,44 @@ fn finish_file_location(output: &mut fltk::output::Output) -> anyhow::Result<Str
   Ok(format!(".{}.config",  &db_global.path_file_output.file_name().ok_or(ah!("Could not get file stem"))?.string()))
 } // fn: finish_file_location() }}}
 
+// Image the last report is from, by path, size and modification time
+type KeyReport = (PathBuf, u64, SystemTime);
+
+// Report of the last verified image, hashing the image again takes long
+static REPORT : Mutex<Option<(KeyReport, lib::verify::Report)>> = Mutex::new(None);
+
+// fn: verify() {{{
+// Checks the produced image and writes the report next to it, an unchanged image is not verified again
+fn verify() -> anyhow::Result<lib::verify::Report>
+{
+  let db_global = db::global::read()?;
+  // A missing image is reported by the verification
+  let opt_key = std::fs::metadata(&db_global.path_file_output)
+    .and_then(|e| Ok((db_global.path_file_output.clone(), e.len(), e.modified()?)))
+    .ok();
+  if let Some((_, report)) = REPORT.lock().unwrap().as_ref().filter(|(k, _)| Some(k) == opt_key.as_ref())
+  {
+    log!("Using the verification report of '{}'", db_global.path_file_output.string());
+    return Ok(report.clone());
+  } // if
+  let projects = frame::creator::PROJECTS.lock().unwrap().clone()
+    .into_iter()
+    .filter_map(|name|
+    {
+      let details = db_global.projects.get(&name)?;
+      Some((lib::verify::Game{ name, platform: details.platform.clone() }, details.path_dir_project.clone()))
+    })
+    .collect();
+  let report = lib::verify::verify(&db_global.path_file_output, projects);
+  match report.write()
+  {
+    Ok((path_file_json, path_file_md)) => log!("Wrote reports '{}' and '{}'", path_file_json.string(), path_file_md.string()),
+    Err(e) => log!("Could not write verification report: {}", e),
+  } // match
+  if let Some(key) = opt_key { *REPORT.lock().unwrap() = Some((key, report.clone())); } // if
+  Ok(report)
+} // fn: verify() }}}
+
 // pub fn finish() {{{
 pub fn finish(tx: Sender<common::Msg>, title: &str)
 {
@@ -57,6 +105,34 @@ pub fn finish(tx: Sender<common::Msg>, title: &str)
   col.fixed(&output_saved_location, dimm::height_button_wide());
   // Retrieve output image file location
   let str_package_basename = finish_file_location(&mut output_saved_location).unwrap_or_default();
+  // Verification report
+  let mut display_report = text::TextDisplay::default()
+    .with_color(fltk::enums::Color::BackGround)
+    .with_frame(fltk::enums::FrameType::BorderBox);
+  display_report.wrap_mode(text::WrapMode::AtBounds, 0);
+  display_report.set_buffer(text::TextBuffer::default());
+  display_report.insert("Verifying the package...");
+  col.fixed(&display_report, dimm::height_text()*10);
+  ui.btn_next.deactivate();
+  std::thread::spawn({
+    let mut btn_next = ui.btn_next.clone();
+    move ||
+    {
+      let text = match verify()
+      {
+        Ok(report) =>
+        {
+          if report.is_ok() { log_status!("The package was verified successfully"); }
+          else { log_status!("The package verification found {} problem(s)", report.errors.len()); }
+          report.markdown()
+        },
+        Err(e) => format!("Could not verify the package: {}", e),
+      }; // match
+      if let Some(mut buffer) = display_report.buffer() { buffer.set_text(&text); } // if
+      btn_next.activate();
+      fltk::app::awake();
+    }
+  });
   let mut output_info = text::TextDisplay::default()
     .with_color(fltk::enums::Color::BackGround)
     .with_frame(fltk::enums::FrameType::NoBox);
@@ -0,0 +1,60 @@
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  enums::{Color,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row,fixed};
+
+use crate::dimm;
+use crate::frame;
+use crate::lib;
+
+// pub fn history() {{{
+// Opens a window with the test runs of the current project, selecting a run shows its
+// configuration, what changed since the run before it and its log
+pub fn history()
+{
+  let runs = lib::history::list().unwrap_or_default();
+  let mut wind = frame::common::window("Test history");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() * 2 / 5);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    fixed!(col, btn_close, shared::fltk::button::wide::default().with_label("Close"), dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Runs
+  browser.set_text_size(dimm::height_text());
+  runs.iter().for_each(|run| browser.add(&run.summary()));
+  display.wrap_mode(WrapMode::None, 0);
+  display.set_buffer(TextBuffer::default());
+  display.insert(if runs.is_empty() { "No test runs recorded for this project" } else { "Select a run" });
+  browser.set_callback(move |e|
+  {
+    // Lines start at one, runs are newest first
+    let index = e.value() as usize;
+    if index == 0 || index > runs.len() { return; } // if
+    let text = runs[index-1].details(runs.get(index));
+    if let Some(mut buffer) = display.buffer() { buffer.set_text(&text); } // if
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn history() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
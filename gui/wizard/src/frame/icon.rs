@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 use std::sync::{Arc,Mutex};
+use std::collections::HashMap;
 
 // Gui
 use fltk::prelude::*;
@@ -8,7 +9,7 @@ use fltk::{
   input::FileInput,
   frame::Frame,
   dialog::file_chooser,
-  enums::Color,
+  enums::{Color,CallbackTrigger},
 };
 
 use shared::fltk::WidgetExtExtra;
@@ -18,6 +19,7 @@ use anyhow::anyhow as ah;
 use shared::hover_blink;
 use shared::fltk::SenderExt;
 use shared::std::PathBufExt;
+use shared::image::{Pipeline,Fit,Artwork};
 
 use crate::dimm;
 use crate::db;
@@ -28,8 +30,8 @@ use crate::log_return_void;
 use crate::log_err;
 use crate::gameimage;
 
-// resize_draw_image() {{{
-pub fn resize_draw_image(mut frame : Frame, path_file_icon : PathBuf) -> anyhow::Result<()>
+// path_dir_icon() {{{
+fn path_dir_icon() -> anyhow::Result<PathBuf>
 {
   // Get path to project directory
   let db_global = match db::global::read()
@@ -42,42 +44,99 @@ pub fn resize_draw_image(mut frame : Frame, path_file_icon : PathBuf) -> anyhow:
     Ok(path_dir_project) => path_dir_project,
     Err(e) => { return Err(ah!("Error to open project directory: {}", e)); },
   }; // match
-  // Create path to resized icon
-  let path_icon_resized_parent = path_dir_project.join("icon");
-  let path_icon_resized = path_icon_resized_parent.join("icon.wizard.resized.png");
-  match std::fs::create_dir_all(&path_icon_resized_parent)
+  // Create icon directory
+  let path_dir_icon = path_dir_project.join("icon");
+  match std::fs::create_dir_all(&path_dir_icon)
   {
-    Ok(()) => log!("Created directory {}", path_icon_resized_parent.string()),
+    Ok(()) => log!("Created directory {}", path_dir_icon.string()),
     Err(e) => { return Err(ah!("Failure to create directories: {}", e));  },
   }; // match
-  // Resize icon
-  match shared::image::resize(path_icon_resized.clone(), path_file_icon, frame.w() as u32, frame.h() as u32)
-  {
-    Ok(()) => log!("Resized image '{}'", path_icon_resized.string()),
-    Err(e) => return Err(ah!("Failed to resize image to '{}', with err '{}'", path_icon_resized.string(), e)),
-  }; // if
-  // Load image
-  match fltk::image::PngImage::load(path_icon_resized)
+  Ok(path_dir_icon)
+} // path_dir_icon() }}}
+
+// draw_image() {{{
+fn draw_image(mut frame : Frame, path_file_image : PathBuf) -> anyhow::Result<()>
+{
+  match fltk::image::PngImage::load(path_file_image)
   {
     Ok(png_image) =>
     {
       frame.set_image_scaled(Some(png_image));
+      frame.redraw();
       fltk::app::redraw();
       fltk::app::awake();
     },
     Err(e) => return Err(ah!("Could not load png icon: {}", e)),
   } // if
-
   Ok(())
+} // draw_image() }}}
+
+// resize_draw_image() {{{
+pub fn resize_draw_image(frame : Frame, path_file_icon : PathBuf) -> anyhow::Result<()>
+{
+  // Create path to resized icon
+  let path_icon_resized = path_dir_icon()?.join("icon.wizard.resized.png");
+  // Resize icon
+  match shared::image::resize(path_icon_resized.clone(), path_file_icon, frame.w() as u32, frame.h() as u32)
+  {
+    Ok(()) => log!("Resized image '{}'", path_icon_resized.string()),
+    Err(e) => return Err(ah!("Failed to resize image to '{}', with err '{}'", path_icon_resized.string(), e)),
+  }; // if
+  // Load image
+  draw_image(frame, path_icon_resized)
 } // resize_draw_image() }}}
 
+// pipeline_draw_image() {{{
+pub fn pipeline_draw_image(frame : Frame, path_file_icon : PathBuf, pipeline : Pipeline) -> anyhow::Result<()>
+{
+  // Create path to preview icon
+  let path_icon_preview = path_dir_icon()?.join("icon.wizard.resized.png");
+  // Crop or pad icon to the launcher aspect ratio
+  match pipeline.preview(path_icon_preview.clone(), path_file_icon, frame.w() as u32, frame.h() as u32)
+  {
+    Ok(()) => log!("Created preview image '{}'", path_icon_preview.string()),
+    Err(e) => return Err(ah!("Failed to create preview '{}', with err '{}'", path_icon_preview.string(), e)),
+  }; // if
+  // Load image
+  draw_image(frame, path_icon_preview)
+} // pipeline_draw_image() }}}
+
+// pipeline_process() {{{
+// Writes the processed image to the project icon directory and returns its path
+pub fn pipeline_process(path_file_icon : PathBuf, pipeline : Pipeline, name_file : &str) -> anyhow::Result<PathBuf>
+{
+  let path_icon_processed = path_dir_icon()?.join(name_file);
+  pipeline.run(path_icon_processed.clone(), path_file_icon)?;
+  Ok(path_icon_processed)
+} // pipeline_process() }}}
+
+// pipeline_artwork() {{{
+// Processes an optional image role and registers it in the project
+pub fn pipeline_artwork(artwork : Artwork, selection : Selection) -> anyhow::Result<()>
+{
+  let path_file_image = selection.path_file_image.ok_or(ah!("No image selected for {}", artwork.as_str()))?;
+  let path_file_processed = pipeline_process(path_file_image, selection.pipeline, &artwork.file_name())?;
+  let path_dir_project = db::global::get_current_project()?.path_dir_project;
+  let path_file_relative = path_file_processed.strip_prefix(&path_dir_project)?.to_path_buf();
+  db::project::set_artwork(artwork, &path_file_relative)
+} // pipeline_artwork() }}}
+
+// pub struct Selection {{{
+// Image and processing options selected for a role
+#[derive(Clone)]
+pub struct Selection
+{
+  pub path_file_image : Option<PathBuf>,
+  pub pipeline        : Pipeline,
+} // Selection }}}
+
 // pub struct Icon {{{
 #[derive(Clone)]
 pub struct Icon
 {
-  pub arc_path_file_icon : Arc<Mutex<Option<PathBuf>>>,
-  pub opt_frame_icon     : Option<Frame>,
-  pub opt_input_icon     : Option<FileInput>
+  pub arc_selections : Arc<Mutex<HashMap<Artwork, Selection>>>,
+  pub opt_frame_icon : Option<Frame>,
+  pub opt_input_icon : Option<FileInput>
 } // Icon }}}
 
 // pub fn icon() {{{
@@ -86,18 +145,39 @@ pub fn icon(tx: Sender<common::Msg>
   , msg_prev : common::Msg
   , _msg_curr : common::Msg) -> (crate::Ui, Icon)
 {
-  // Save previously selected icon path
-  static OPTION_PATH_FILE_ICON : once_cell::sync::Lazy<Arc<Mutex<Option<PathBuf>>>> = once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
+  // Save previously selected images and crop and padding options for each role
+  static SELECTIONS : once_cell::sync::Lazy<Arc<Mutex<HashMap<Artwork, Selection>>>> = once_cell::sync::Lazy::new(||
+  {
+    Arc::new(Mutex::new(Artwork::all()
+      .into_iter()
+      .map(|e| (e, Selection{ path_file_image: None, pipeline: e.pipeline() }))
+      .collect()
+    ))
+  });
+  // Role currently being edited
+  static ARTWORK : once_cell::sync::Lazy<Mutex<Artwork>> = once_cell::sync::Lazy::new(|| Mutex::new(Artwork::Cover));
 
   let ui = crate::GUI.lock().unwrap().ui.clone()(title);
 
   let mut ret = Icon
   {
-      arc_path_file_icon: OPTION_PATH_FILE_ICON.clone()
+      arc_selections: SELECTIONS.clone()
     , opt_frame_icon: None
     , opt_input_icon: None
   };
 
+  // Access the selection of the current role
+  let f_selection = || -> Selection
+  {
+    let artwork = *ARTWORK.lock().unwrap();
+    SELECTIONS.lock().unwrap().get(&artwork).cloned().unwrap_or(Selection{ path_file_image: None, pipeline: artwork.pipeline() })
+  };
+  let f_update = |f : &dyn Fn(&mut Selection)|
+  {
+    let artwork = *ARTWORK.lock().unwrap();
+    if let Some(selection) = SELECTIONS.lock().unwrap().get_mut(&artwork) { f(selection); }
+  };
+
   let mut col = fltk::group::Flex::default()
     .column()
     .with_size_of(&ui.group)
@@ -123,6 +203,24 @@ pub fn icon(tx: Sender<common::Msg>
   // Spacer
   col.add(&Frame::default());
 
+  // Crop and padding options
+  let mut row = fltk::group::Flex::default().row();
+  row.set_spacing(dimm::border_half());
+  let mut menu_artwork = fltk::menu::MenuButton::default()
+    .with_frame(fltk::enums::FrameType::FlatBox)
+    .with_color(Color::BackGround.lighter());
+  row.fixed(&menu_artwork, dimm::width_button_wide());
+  let mut menu_fit = fltk::menu::MenuButton::default()
+    .with_frame(fltk::enums::FrameType::FlatBox)
+    .with_color(Color::BackGround.lighter());
+  row.fixed(&menu_fit, dimm::width_button_wide());
+  let mut slider_offset = fltk::valuator::HorNiceSlider::default();
+  row.add(&slider_offset);
+  let mut btn_color = shared::fltk::button::rect::configure();
+  row.fixed(&btn_color, dimm::width_button_rec());
+  row.end();
+  col.fixed(&row, dimm::height_button_wide());
+
   // Icon
   let mut row = fltk::group::Flex::default().row();
   let mut input_icon = FileInput::default();
@@ -137,28 +235,128 @@ pub fn icon(tx: Sender<common::Msg>
   row.end();
   col.fixed(&row, dimm::height_button_wide() + dimm::border()/2);
 
-  // Check path cache
-  if let Some(path_file_icon) = OPTION_PATH_FILE_ICON.lock().unwrap().clone()
+  // Re-draws the preview with the current options
+  let f_preview =
   {
-    input_icon.set_value(&path_file_icon.string());
-    log_err!(resize_draw_image(frame_icon.clone(), path_file_icon.clone()));
-  } // if
+    let frame_icon = frame_icon.clone();
+    move ||
+    {
+      let mut frame_icon = frame_icon.clone();
+      let selection = f_selection();
+      if let Some(path_file_icon) = selection.path_file_image
+      {
+        log_err!(pipeline_draw_image(frame_icon.clone(), path_file_icon, selection.pipeline));
+      } // if
+      else
+      {
+        frame_icon.set_image(None::<fltk::image::PngImage>);
+        frame_icon.redraw();
+      } // else
+    }
+  };
+
+  // Updates the widgets with the options of the current role
+  let f_refresh =
+  {
+    let f_preview = f_preview.clone();
+    let (menu_fit, slider_offset, btn_color, input_icon)
+      = (menu_fit.clone(), slider_offset.clone(), btn_color.clone(), input_icon.clone());
+    move ||
+    {
+      let (mut menu_fit, mut slider_offset, mut btn_color, mut input_icon)
+        = (menu_fit.clone(), slider_offset.clone(), btn_color.clone(), input_icon.clone());
+      let selection = f_selection();
+      menu_fit.set_label(selection.pipeline.fit.as_str());
+      slider_offset.set_value(selection.pipeline.offset as f64);
+      if selection.pipeline.fit == Fit::Crop { slider_offset.activate(); } else { slider_offset.deactivate(); }
+      let [r, g, b, _] = selection.pipeline.color;
+      btn_color.set_color(Color::from_rgb(r, g, b));
+      input_icon.set_value(&selection.path_file_image.map(|e| e.string()).unwrap_or_default());
+      f_preview();
+    }
+  };
+
+  // Image role, only the cover is required
+  menu_artwork.add_choice(&Artwork::all().map(|e| e.as_str()).join("|"));
+  menu_artwork.set_label(ARTWORK.lock().unwrap().as_str());
+  menu_artwork.set_tooltip("Select the image role, only the cover is required");
+  menu_artwork.set_callback({
+    let f_refresh = f_refresh.clone();
+    move |e|
+    {
+      let artwork = match e.choice().and_then(|e| e.parse::<Artwork>().ok()) { Some(artwork) => artwork, None => return };
+      *ARTWORK.lock().unwrap() = artwork;
+      e.set_label(artwork.as_str());
+      f_refresh();
+    }
+  });
+
+  // Fit mode
+  menu_fit.add_choice(&[Fit::Crop, Fit::Pad, Fit::Stretch, Fit::Keep].map(|e| e.as_str()).join("|"));
+  menu_fit.set_callback({
+    let f_preview = f_preview.clone();
+    let mut slider_offset = slider_offset.clone();
+    move |e|
+    {
+      let fit = match e.choice().and_then(|e| e.parse::<Fit>().ok()) { Some(fit) => fit, None => return };
+      f_update(&|selection : &mut Selection| selection.pipeline.fit = fit);
+      e.set_label(fit.as_str());
+      if fit == Fit::Crop { slider_offset.activate(); } else { slider_offset.deactivate(); }
+      f_preview();
+    }
+  });
+
+  // Crop offset
+  slider_offset.set_bounds(0.0, 1.0);
+  slider_offset.set_trigger(CallbackTrigger::Release);
+  slider_offset.set_color(Color::BackGround.lighter());
+  slider_offset.set_selection_color(Color::Blue);
+  slider_offset.set_callback({
+    let f_preview = f_preview.clone();
+    move |e|
+    {
+      let offset = e.value() as f32;
+      f_update(&|selection : &mut Selection| selection.pipeline.offset = offset);
+      f_preview();
+    }
+  });
+
+  // Padding color
+  btn_color.set_tooltip("Select the padding color");
+  hover_blink!(btn_color);
+  btn_color.set_callback({
+    let f_preview = f_preview.clone();
+    move |e|
+    {
+      let (r, g, b) = match fltk::dialog::color_chooser("Select the padding color", fltk::dialog::ColorMode::Byte)
+      {
+        Some(rgb) => rgb,
+        None => { log_status!("No color selected"); return; }
+      }; // match
+      f_update(&|selection : &mut Selection| selection.pipeline.color = [r, g, b, 255]);
+      e.set_color(Color::from_rgb(r, g, b));
+      f_preview();
+    }
+  });
+
+  // Restore widgets from the previous selection
+  f_refresh();
 
   // // Set input_icon callback
   let mut clone_input_icon = input_icon.clone();
   btn_search.set_callback(move |_|
   {
-    let str_choice = match file_chooser("Select the icon", "*.{jpg,png}", ".", false)
+    let str_choice = match file_chooser("Select the image", shared::image::Format::filter(), ".", false)
     {
       Some(str_choice) => str_choice,
       None => { log_status!("No file selected"); return; }
     }; // match
-    // Update static icon
-    *OPTION_PATH_FILE_ICON.lock().unwrap() = Some(PathBuf::from(&str_choice));
+    // Update selection of the current role
+    f_update(&|selection : &mut Selection| selection.path_file_image = Some(PathBuf::from(&str_choice)));
     // Show file path on selector
     clone_input_icon.set_value(str_choice.as_str());
     // Set preview image
-    match resize_draw_image(frame_icon.clone(), str_choice.into())
+    match pipeline_draw_image(frame_icon.clone(), str_choice.into(), f_selection().pipeline)
     {
       Ok(_) => log_status!("Set preview image"),
       Err(_) => log_status!("Failed to load icon image into preview"),
@@ -180,29 +378,38 @@ pub fn project(tx: Sender<common::Msg>
   let (ui,ret) = icon(tx, title, msg_prev, msg_curr);
   let mut btn_next = ui.btn_next.clone();
 
-  // Callback to install the selected icon with the backend
+  // Callback to install the selected images with the backend
   let clone_tx = tx.clone();
   btn_next.set_callback(move |_|
   {
-    let arc_path_file_icon = ret.arc_path_file_icon.clone();
+    let selections = ret.arc_selections.lock().unwrap().clone();
 
-    // Check if an icon was selected
-    let path_file_icon = if let Some(path_file_icon) = arc_path_file_icon.lock().unwrap().as_ref()
+    // Check if a cover was selected
+    let selection_cover = match selections.get(&Artwork::Cover).cloned()
     {
-      path_file_icon.clone()
-    }
-    else
-    {
-      log_status!("No icon selected");
-      clone_tx.send_activate(msg_curr);
-      return;
-    };
+      Some(selection) if selection.path_file_image.is_some() => selection,
+      _ =>
+      {
+        log_status!("No cover image selected");
+        clone_tx.send_activate(msg_curr);
+        return;
+      },
+    }; // match
 
     // Set selected icon as icon
     clone_tx.send_awake(common::Msg::WindDeactivate);
     let clone_tx = clone_tx.clone();
     std::thread::spawn(move ||
     {
+      // Crop or pad the cover to the launcher aspect ratio
+      let path_file_icon = match pipeline_process(selection_cover.path_file_image.unwrap_or_default()
+        , selection_cover.pipeline
+        , "icon.wizard.processed.png")
+      {
+        Ok(path_file_icon) => path_file_icon,
+        Err(e) => { clone_tx.send_activate(msg_curr); log_return_void!("Could not process icon with error: {}", e); },
+      }; // match
+
       // Try to install icon
       log_status!("Installing icon...");
 
@@ -212,6 +419,17 @@ pub fn project(tx: Sender<common::Msg>
         Err(e) => { clone_tx.send_activate(msg_curr); log_return_void!("Could not install icon with error: {}", e); },
       } // match
 
+      // Optional roles, a failure does not prevent the project from working
+      for (artwork, selection) in selections.into_iter()
+        .filter(|(k,v)| *k != Artwork::Cover && v.path_file_image.is_some())
+      {
+        match pipeline_artwork(artwork, selection)
+        {
+          Ok(()) => log_status!("Installed {} image", artwork.as_str()),
+          Err(e) => log_status!("Could not install {} image: {}", artwork.as_str(), e),
+        } // match
+      } // for
+
       clone_tx.send_activate(msg_next);
     });
   });
@@ -6,3 +6,12 @@ pub mod creator;
 pub mod desktop;
 pub mod finish;
 pub mod icon;
+pub mod history;
+pub mod snapshot;
+pub mod registry;
+pub mod shortcut;
+pub mod uninstall;
+pub mod usage;
+pub mod distribution;
+pub mod winetricks;
+pub mod template;
@@ -16,6 +16,7 @@ use shared::fltk::SenderExt;
 use shared::{hover_blink,column,row,add,fixed};
 
 use crate::db;
+use crate::lib;
 use crate::dimm;
 use crate::common;
 use crate::log;
@@ -174,6 +175,21 @@ fn platform_add_wine(tx: Sender<common::Msg>
       }
       else
       {
+        // Distributions fetched before are kept in the build directory
+        let dist_wine = DIST_WINE.lock().unwrap().clone();
+        if lib::distribution::is_fetched(&dist_wine)
+        {
+          if let Err(e) = lib::distribution::activate(&dist_wine)
+          {
+            log!("Could not activate wine distribution: {}", e);
+          } // if
+          tx.send_awake(common::Msg::DrawPlatform);
+          return;
+        } // if
+        if let Err(e) = lib::distribution::stash()
+        {
+          log!("Could not keep the current wine distribution: {}", e);
+        } // if
         if let Err(e) = db::global::update(#[clown] |mut db|{ db.dist_wine = DIST_WINE.lock().unwrap().clone(); db })
         {
           log!("Could not update wine distribution: {}", e);
@@ -0,0 +1,356 @@
+use std::
+{
+  path::PathBuf,
+  sync::{Arc,Mutex},
+};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  button::Button,
+  dialog,
+  enums::{Align,CallbackTrigger,Color},
+  frame::Frame,
+  input::Input,
+  menu::MenuButton,
+  tree::{Tree,TreeItem},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::wine::registry::{Data,Registry};
+use shared::wine::tweak;
+use shared::{column,row,fixed,add};
+
+use crate::dimm;
+use crate::frame;
+use crate::db;
+use crate::log;
+use crate::log_status;
+
+// Registry files of a prefix
+const FILES : [&str; 3] = ["user.reg", "system.reg", "userdef.reg"];
+
+// struct State {{{
+// Registry being edited and the selected key
+struct State
+{
+  path_file   : PathBuf,
+  registry    : Registry,
+  // The file could not be read, saving would replace it
+  is_readonly : bool,
+  query       : String,
+  key         : Option<String>,
+} // struct State }}}
+
+// fn path_tree() {{{
+// Tree items are separated by slashes, which are escaped in key names
+fn path_tree(path_key : &str) -> String
+{
+  path_key.split('\\').map(|e| e.replace('/', "\\/")).collect::<Vec<String>>().join("/")
+} // fn path_tree() }}}
+
+// fn path_key() {{{
+// Path of the key of a tree item, from the labels of the item and of its parents
+fn path_key(item : &TreeItem) -> String
+{
+  let mut labels = vec![];
+  let mut opt_item = Some(item.clone());
+  while let Some(item) = opt_item && ! item.is_root()
+  {
+    labels.push(item.label().unwrap_or_default());
+    opt_item = item.parent();
+  } // while
+  labels.reverse();
+  labels.join("\\")
+} // fn path_key() }}}
+
+// fn is_match() {{{
+// Keys that contain the query in the path, in a value name or in a value
+fn is_match(key : &shared::wine::registry::Key, query : &str) -> bool
+{
+  query.is_empty()
+    || key.path.to_lowercase().contains(query)
+    || key.values.iter().any(|(k,v)| k.to_lowercase().contains(query) || v.to_lowercase().contains(query))
+} // fn is_match() }}}
+
+// fn fill_tree() {{{
+fn fill_tree(tree : &mut Tree, state : &State)
+{
+  tree.clear();
+  for key in state.registry.keys.iter().filter(|e| is_match(e, &state.query))
+  {
+    let _ = tree.add(&path_tree(&key.path));
+  } // for
+  // Show only the top level keys when not searching
+  if state.query.is_empty() && let Some(items) = tree.get_items()
+  {
+    items.into_iter().filter(|e| e.depth() > 0).for_each(|mut e| e.close());
+  } // if
+  tree.redraw();
+} // fn fill_tree() }}}
+
+// fn fill_values() {{{
+fn fill_values(browser : &mut HoldBrowser, state : &State)
+{
+  browser.clear();
+  let Some(key) = state.key.as_ref().and_then(|e| state.registry.key(e)) else { return; };
+  for (name, raw) in &key.values
+  {
+    let data = Data::decode(raw);
+    browser.add(&format!("{}\t{}\t{}"
+      , if name.is_empty() { "(default)" } else { name.as_str() }
+      , data.kind()
+      , data.text()
+    ));
+  } // for
+} // fn fill_values() }}}
+
+// fn selected() {{{
+// Name and data of the selected value
+fn selected(browser : &HoldBrowser, state : &State) -> Option<(String, Data)>
+{
+  // Lines start at one
+  let index = (browser.value() as usize).checked_sub(1)?;
+  let key = state.registry.key(state.key.as_ref()?)?;
+  key.values.get(index).map(|(k,v)| (k.clone(), Data::decode(v)))
+} // fn selected() }}}
+
+// fn save() {{{
+fn save(state : &State)
+{
+  if state.is_readonly { log_status!("Registry '{}' could not be read, it is not saved", state.path_file.display()); return; } // if
+  match state.registry.save(&state.path_file)
+  {
+    Ok(()) => log!("Saved registry '{}'", state.path_file.display()),
+    Err(e) => log_status!("Could not save registry: {}", e),
+  } // match
+} // fn save() }}}
+
+// fn open() {{{
+fn open(path_file : PathBuf) -> State
+{
+  let (registry, is_readonly) = match Registry::open(&path_file)
+  {
+    Ok(registry) => (registry, false),
+    Err(e) =>
+    {
+      log_status!("Could not read registry '{}', editing is disabled: {}", path_file.display(), e);
+      (Registry::default(), true)
+    },
+  }; // match
+  State{ path_file, registry, is_readonly, query: String::new(), key: None }
+} // fn open() }}}
+
+// fn set_editable() {{{
+// Editing is disabled for registries that could not be read
+fn set_editable(buttons : &mut [Button], state : &State)
+{
+  buttons.iter_mut().for_each(|e| if state.is_readonly { e.deactivate() } else { e.activate() });
+} // fn set_editable() }}}
+
+// pub fn registry() {{{
+// Opens a window to browse, search and edit the registry of the wine prefix of the project, with
+// shortcuts for common changes
+pub fn registry()
+{
+  let path_dir_wine = match db::project::current().and_then(|e| e.get_dir_self())
+  {
+    Ok(path_dir_project) => path_dir_project.join("wine"),
+    Err(e) => { log_status!("Could not read current project: {}", e); return; },
+  }; // match
+  let state = Arc::new(Mutex::new(open(path_dir_wine.join(FILES[0]))));
+  let mut wind = frame::common::window("Wine registry");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row_search,
+      fixed!(row_search, menu_file, MenuButton::default(), dimm::width_button_wide() * 2);
+      add!(row_search, input_query, Input::default());
+    );
+    col.fixed(&row_search, dimm::height_button_wide());
+    row!(row,
+      let mut tree = Tree::default();
+      row.fixed(&tree, dimm::width_wizard() * 2 / 5);
+      column!(col_values,
+        add!(col_values, browser, HoldBrowser::default());
+        row!(row_buttons,
+          let btn_add = shared::fltk::button::wide::default().with_label("Add");
+          let btn_edit = shared::fltk::button::wide::default().with_label("Edit");
+          let btn_erase = shared::fltk::button::wide::default().with_label("Erase");
+        );
+        col_values.fixed(&row_buttons, dimm::height_button_wide());
+      );
+      row.add(&col_values);
+    );
+    col.add(&row);
+    col.fixed(&Frame::default().with_align(Align::Inside | Align::Left).with_label("Shortcuts"), dimm::height_text());
+    row!(row_tweaks,
+      let menus_tweak : Vec<MenuButton> = tweak::TWEAKS.iter().map(|_| MenuButton::default()).collect();
+    );
+    col.fixed(&row_tweaks, dimm::height_button_wide());
+    fixed!(col, btn_close, shared::fltk::button::wide::default().with_label("Close"), dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Registry file
+  let mut menu_file = menu_file.clone();
+  FILES.iter().for_each(|e| { menu_file.add_choice(e); });
+  menu_file.set_label(FILES[0]);
+  // Keys and values
+  tree.set_show_root(false);
+  let mut browser = browser.clone();
+  browser.set_text_size(dimm::height_text());
+  browser.set_column_char('\t');
+  browser.set_column_widths(&[dimm::width_wizard() / 5, dimm::width_wizard() / 8]);
+  fill_tree(&mut tree, &state.lock().unwrap());
+  let buttons_edit = vec![btn_add.clone(), btn_edit.clone(), btn_erase.clone()];
+  set_editable(&mut buttons_edit.clone(), &state.lock().unwrap());
+  menu_file.set_callback({
+    let state = state.clone();
+    let path_dir_wine = path_dir_wine.clone();
+    let mut tree = tree.clone();
+    let mut browser = browser.clone();
+    let mut buttons_edit = buttons_edit.clone();
+    move |e|
+    {
+      let Some(choice) = e.choice() else { return; };
+      e.set_label(&choice);
+      let mut state = state.lock().unwrap();
+      *state = open(path_dir_wine.join(choice));
+      set_editable(&mut buttons_edit, &state);
+      fill_tree(&mut tree, &state);
+      fill_values(&mut browser, &state);
+    }
+  });
+  tree.set_callback({
+    let state = state.clone();
+    let mut browser = browser.clone();
+    move |e|
+    {
+      let Some(item) = e.first_selected_item() else { return; };
+      let mut state = state.lock().unwrap();
+      state.key = Some(path_key(&item));
+      fill_values(&mut browser, &state);
+    }
+  });
+  // Search keys, value names and values
+  let mut input_query = input_query.clone();
+  input_query.set_trigger(CallbackTrigger::EnterKeyAlways);
+  input_query.set_callback({
+    let state = state.clone();
+    let mut tree = tree.clone();
+    move |e|
+    {
+      let mut state = state.lock().unwrap();
+      state.query = e.value().to_lowercase();
+      fill_tree(&mut tree, &state);
+    }
+  });
+  input_query.set_tooltip("Input a search term and press enter");
+  // Add a value to the selected key
+  btn_add.clone().with_color(Color::Green).set_callback({
+    let state = state.clone();
+    let mut browser = browser.clone();
+    move |_|
+    {
+      // Dialogs run the event loop, the state is not locked while they are shown
+      let Some(path_key) = state.lock().unwrap().key.clone() else { log_status!("No key was selected"); return; };
+      let Some(name) = dialog::input_default("Enter the name of the value", "") else { return; };
+      let data = match dialog::choice2_default("Select the type of the value", "String", "Number", "Cancel")
+      {
+        Some(0) => Data::String(String::new()),
+        Some(1) => Data::Dword(0),
+        _ => return,
+      }; // match
+      let Some(text) = dialog::input_default(&format!("Enter the data of '{}'", name), &data.text()) else { return; };
+      let mut state = state.lock().unwrap();
+      match data.with_text(&text)
+      {
+        Ok(data) => state.registry.key_mut(&path_key).set(&name, &data.encode()),
+        Err(e) => { log_status!("Invalid data: {}", e); return; },
+      } // match
+      save(&state);
+      fill_values(&mut browser, &state);
+    }
+  });
+  // Edit the selected value
+  btn_edit.clone().with_color(Color::Blue).set_callback({
+    let state = state.clone();
+    let mut browser = browser.clone();
+    move |_|
+    {
+      let Some((name, data)) = selected(&browser, &state.lock().unwrap()) else { log_status!("No value was selected"); return; };
+      let Some(text) = dialog::input_default(&format!("Enter the data of '{}' ({})", name, data.kind()), &data.text()) else { return; };
+      let mut state = state.lock().unwrap();
+      let Some(path_key) = state.key.clone() else { return; };
+      match data.with_text(&text)
+      {
+        Ok(data) => state.registry.key_mut(&path_key).set(&name, &data.encode()),
+        Err(e) => { log_status!("Invalid data: {}", e); return; },
+      } // match
+      save(&state);
+      fill_values(&mut browser, &state);
+    }
+  });
+  // Erase the selected value
+  btn_erase.clone().with_color(Color::Red).set_callback({
+    let state = state.clone();
+    let mut browser = browser.clone();
+    move |_|
+    {
+      let Some((name, _)) = selected(&browser, &state.lock().unwrap()) else { log_status!("No value was selected"); return; };
+      if dialog::choice2_default(&format!("Erase the value '{}'?", name), "No", "Yes", "") != Some(1) { return; } // if
+      let mut state = state.lock().unwrap();
+      let Some(path_key) = state.key.clone() else { return; };
+      state.registry.key_mut(&path_key).erase(&name);
+      save(&state);
+      fill_values(&mut browser, &state);
+    }
+  });
+  // Shortcuts for common changes, written to the registry of the user
+  let registry_user = Registry::open(&path_dir_wine.join("user.reg")).unwrap_or_default();
+  for (item, mut menu) in tweak::TWEAKS.iter().zip(menus_tweak)
+  {
+    item.choices.iter().for_each(|e| { menu.add_choice(e.label); });
+    menu.set_label(&format!("{}: {}", item.label, item.current(&registry_user).map(|e| e.label).unwrap_or("Custom")));
+    menu.set_callback({
+      let state = state.clone();
+      let path_dir_wine = path_dir_wine.clone();
+      let mut tree = tree.clone();
+      let mut browser = browser.clone();
+      let buttons_edit = buttons_edit.clone();
+      move |e|
+      {
+        let Some(choice) = item.choices.get(e.value().max(0) as usize) else { return; };
+        if let Err(e) = tweak::apply(&path_dir_wine, choice)
+        {
+          log_status!("Could not apply '{}': {}", item.label, e);
+          return;
+        } // if
+        log_status!("Set '{}' to '{}'", item.label, choice.label);
+        e.set_label(&format!("{}: {}", item.label, choice.label));
+        // Reload the registry of the user if it is open
+        let mut state = state.lock().unwrap();
+        if state.path_file == path_dir_wine.join("user.reg")
+        {
+          let key = state.key.clone();
+          let query = state.query.clone();
+          *state = open(path_dir_wine.join("user.reg"));
+          state.key = key;
+          state.query = query;
+          set_editable(&mut buttons_edit.clone(), &state);
+          fill_tree(&mut tree, &state);
+          fill_values(&mut browser, &state);
+        } // if
+      }
+    });
+  } // for
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn registry() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,75 @@
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  enums::{Color,FrameType},
+  frame::Frame,
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row,fixed};
+
+use crate::dimm;
+use crate::frame;
+use crate::lib;
+
+// pub fn shortcut() {{{
+// Opens a window with the start menu and desktop shortcuts of the wine prefix, the selected one is
+// passed to 'f_use' to become a launch profile
+pub fn shortcut(shortcuts : Vec<lib::lnk::Shortcut>, f_use : impl Fn(&lib::lnk::Shortcut) + 'static)
+{
+  let mut wind = frame::common::window("Shortcuts of the wine prefix");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() * 2 / 5);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    row!(row_buttons,
+      row_buttons.add(&Frame::default());
+      fixed!(row_buttons, btn_use, shared::fltk::button::wide::default().with_label("Use"), dimm::width_button_wide());
+      fixed!(row_buttons, btn_close, shared::fltk::button::wide::default().with_label("Close"), dimm::width_button_wide());
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Shortcuts
+  browser.set_text_size(dimm::height_text());
+  shortcuts.iter().for_each(|shortcut| browser.add(&shortcut.summary()));
+  display.wrap_mode(WrapMode::AtBounds, 0);
+  display.set_buffer(TextBuffer::default());
+  display.insert(if shortcuts.is_empty() { "No shortcuts to executables found in the wine prefix" } else { "Select a shortcut" });
+  let f_selected = {
+    let browser = browser.clone();
+    let shortcuts = shortcuts.clone();
+    // Lines start at one
+    move || (browser.value() > 0).then(|| shortcuts.get(browser.value() as usize - 1).cloned()).flatten()
+  };
+  browser.set_callback({
+    let f_selected = f_selected.clone();
+    move |_|
+    {
+      let Some(shortcut) = f_selected() else { return; };
+      if let Some(mut buffer) = display.buffer() { buffer.set_text(&shortcut.details()); } // if
+    }
+  });
+  btn_use.clone().with_color(Color::Green).set_callback(move |_|
+  {
+    if let Some(shortcut) = f_selected() { f_use(&shortcut); } // if
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn shortcut() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
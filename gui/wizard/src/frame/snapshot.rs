@@ -0,0 +1,147 @@
+use std::sync::{Arc,Mutex};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  dialog,
+  enums::{Color,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row};
+
+use crate::dimm;
+use crate::frame;
+use crate::lib;
+use crate::log_status;
+
+// fn fill() {{{
+// Lists the snapshots of the current project
+fn fill(browser : &mut HoldBrowser, display : &mut TextDisplay, snapshots : &Arc<Mutex<Vec<lib::snapshot::Snapshot>>>)
+{
+  let mut snapshots = snapshots.lock().unwrap();
+  *snapshots = lib::snapshot::list().unwrap_or_default();
+  browser.clear();
+  snapshots.iter().for_each(|snapshot| browser.add(&snapshot.summary()));
+  if let Some(mut buffer) = display.buffer()
+  {
+    buffer.set_text(if snapshots.is_empty() { "No snapshots of the wine prefix" } else { "Select a snapshot" });
+  } // if
+} // fn fill() }}}
+
+// fn selected() {{{
+fn selected(browser : &HoldBrowser, snapshots : &Arc<Mutex<Vec<lib::snapshot::Snapshot>>>) -> Option<lib::snapshot::Snapshot>
+{
+  // Lines start at one
+  let index = browser.value() as usize;
+  if index == 0 { return None; } // if
+  snapshots.lock().unwrap().get(index-1).cloned()
+} // fn selected() }}}
+
+// pub fn snapshot() {{{
+// Opens a window with the snapshots of the wine prefix of the current project, which can be
+// created, restored or erased
+pub fn snapshot()
+{
+  let snapshots = Arc::new(Mutex::new(Vec::<lib::snapshot::Snapshot>::new()));
+  let mut wind = frame::common::window("Wine prefix snapshots");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() * 2 / 5);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    row!(row_buttons,
+      let btn_create = shared::fltk::button::wide::default().with_label("Create");
+      let btn_restore = shared::fltk::button::wide::default().with_label("Restore");
+      let btn_erase = shared::fltk::button::wide::default().with_label("Erase");
+      let btn_close = shared::fltk::button::wide::default().with_label("Close");
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Snapshots
+  browser.set_text_size(dimm::height_text());
+  display.wrap_mode(WrapMode::None, 0);
+  display.set_buffer(TextBuffer::default());
+  fill(&mut browser, &mut display, &snapshots);
+  browser.set_callback({
+    let snapshots = snapshots.clone();
+    let display = display.clone();
+    move |e|
+    {
+      let Some(snapshot) = selected(e, &snapshots) else { return; };
+      if let Some(mut buffer) = display.buffer() { buffer.set_text(&snapshot.details()); } // if
+    }
+  });
+  // Runs the operation in the background and lists the snapshots again
+  let f_background = frame::common::background(wind.clone(), {
+    let browser = browser.clone();
+    let display = display.clone();
+    let snapshots = snapshots.clone();
+    move || fill(&mut browser.clone(), &mut display.clone(), &snapshots)
+  });
+  btn_create.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    move |_|
+    {
+      let Some(label) = dialog::input_default("Enter a description for the snapshot", "Manual snapshot") else { return; };
+      f_background(Box::new(move ||
+      {
+        let snapshot = lib::snapshot::create(&label)?;
+        log_status!("Created snapshot '{}'", snapshot.label);
+        Ok(())
+      }));
+    }
+  });
+  btn_restore.clone().with_color(Color::Blue).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let snapshots = snapshots.clone();
+    move |_|
+    {
+      let Some(snapshot) = selected(&browser, &snapshots) else { return; };
+      if dialog::choice2_default(&format!("Replace the wine prefix with '{}'?", snapshot.summary()), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      f_background(Box::new(move ||
+      {
+        lib::snapshot::restore(&snapshot)?;
+        log_status!("Restored snapshot '{}'", snapshot.label);
+        Ok(())
+      }));
+    }
+  });
+  btn_erase.clone().with_color(Color::Red).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let snapshots = snapshots.clone();
+    move |_|
+    {
+      let Some(snapshot) = selected(&browser, &snapshots) else { return; };
+      f_background(Box::new(move ||
+      {
+        lib::snapshot::erase(&snapshot)?;
+        log_status!("Erased snapshot '{}'", snapshot.label);
+        Ok(())
+      }));
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn snapshot() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
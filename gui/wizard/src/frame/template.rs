@@ -0,0 +1,178 @@
+use std::sync::{Arc,Mutex};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  dialog,
+  enums::{Color,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row};
+
+use crate::dimm;
+use crate::frame;
+use crate::db;
+use crate::lib;
+use crate::log_status;
+
+// fn fill() {{{
+// Lists the prefix templates of the build directory
+fn fill(browser : &mut HoldBrowser, display : &mut TextDisplay, templates : &Arc<Mutex<Vec<lib::template::Template>>>)
+{
+  let mut templates = templates.lock().unwrap();
+  *templates = lib::template::list().unwrap_or_default();
+  browser.clear();
+  templates.iter().for_each(|template| browser.add(&template.summary()));
+  if let Some(mut buffer) = display.buffer()
+  {
+    buffer.set_text(if templates.is_empty() { "No prefix templates, save a configured prefix to create one" } else { "Select a template" });
+  } // if
+} // fn fill() }}}
+
+// fn selected() {{{
+fn selected(browser : &HoldBrowser, templates : &Arc<Mutex<Vec<lib::template::Template>>>) -> Option<lib::template::Template>
+{
+  // Lines start at one
+  let index = browser.value() as usize;
+  if index == 0 { return None; } // if
+  templates.lock().unwrap().get(index-1).cloned()
+} // fn selected() }}}
+
+// pub fn template() {{{
+// Opens a window with the wine prefix templates shared by the projects. The prefix of the current
+// project can be saved as a template or initialized from one.
+pub fn template()
+{
+  let templates = Arc::new(Mutex::new(Vec::<lib::template::Template>::new()));
+  let mut wind = frame::common::window("Wine prefix templates");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() * 2 / 5);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    row!(row_buttons,
+      let btn_save = shared::fltk::button::wide::default().with_label("Save prefix");
+      let btn_apply = shared::fltk::button::wide::default().with_label("Initialize prefix");
+      let btn_erase = shared::fltk::button::wide::default().with_label("Erase");
+      let btn_close = shared::fltk::button::wide::default().with_label("Close");
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Templates
+  browser.set_text_size(dimm::height_text());
+  display.wrap_mode(WrapMode::None, 0);
+  display.set_buffer(TextBuffer::default());
+  fill(&mut browser, &mut display, &templates);
+  browser.set_callback({
+    let templates = templates.clone();
+    let display = display.clone();
+    move |e|
+    {
+      let Some(template) = selected(e, &templates) else { return; };
+      if let Some(mut buffer) = display.buffer() { buffer.set_text(&template.details()); } // if
+    }
+  });
+  // Runs the operation in the background and lists the templates again
+  let f_background = {
+    let wind = wind.clone();
+    let browser = browser.clone();
+    let display = display.clone();
+    let templates = templates.clone();
+    move |f_operation : Box<dyn FnOnce() -> anyhow::Result<()> + Send>|
+    {
+      let mut wind = wind.clone();
+      let mut browser = browser.clone();
+      let mut display = display.clone();
+      let templates = templates.clone();
+      wind.deactivate();
+      std::thread::spawn(move ||
+      {
+        if let Err(e) = f_operation() { log_status!("{}", e); } // if
+        fill(&mut browser, &mut display, &templates);
+        wind.activate();
+        fltk::app::awake();
+      });
+    }
+  };
+  btn_save.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let templates = templates.clone();
+    move |_|
+    {
+      // The selected template is replaced by default
+      let name_default = selected(&browser, &templates)
+        .map(|e| e.name)
+        .or(db::global::read().ok().map(|e| e.project))
+        .unwrap_or_default();
+      let Some(name) = dialog::input_default("Enter the name of the template", &name_default) else { return; };
+      f_background(Box::new(move ||
+      {
+        log_status!("Saving the wine prefix as template '{}'...", name.trim());
+        let template = lib::template::save(&name)?;
+        log_status!("Saved template '{}' with {} verbs", template.name, template.verbs.len());
+        Ok(())
+      }));
+    }
+  });
+  btn_apply.clone().with_color(Color::Blue).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let templates = templates.clone();
+    move |_|
+    {
+      let Some(template) = selected(&browser, &templates) else { return; };
+      let is_prefix = db::global::get_current_project().is_ok_and(|e| e.path_dir_project.join("wine").exists());
+      if is_prefix
+      && dialog::choice2_default(&format!("Replace the wine prefix with template '{}'?", template.name), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      f_background(Box::new(move ||
+      {
+        log_status!("Initializing the wine prefix from template '{}'...", template.name);
+        lib::template::apply(&template)?;
+        log_status!("Initialized the wine prefix from template '{}'", template.name);
+        Ok(())
+      }));
+    }
+  });
+  btn_erase.clone().with_color(Color::Red).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let templates = templates.clone();
+    move |_|
+    {
+      let Some(template) = selected(&browser, &templates) else { return; };
+      if dialog::choice2_default(&format!("Erase template '{}'?", template.name), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      f_background(Box::new(move ||
+      {
+        lib::template::erase(&template)?;
+        log_status!("Erased template '{}'", template.name);
+        Ok(())
+      }));
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn template() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
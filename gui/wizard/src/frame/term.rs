@@ -2,8 +2,8 @@ use std::{
   fs,
   path::PathBuf,
   sync::{Arc, Mutex},
-  io::Write,
-  process::{Command, Stdio, Child},
+  io::{Read, Write},
+  process::{Command, Child},
   sync::mpsc,
 };
 
@@ -11,16 +11,18 @@ use fltk::{
   app,
   dialog::file_chooser,
   prelude::*,
-  enums::Color,
+  enums::{Color,Event,FrameType,CallbackTrigger,Key},
   text::SimpleTerminal,
+  menu::MenuButton,
 };
 
 use anyhow::anyhow as ah;
 
+use shared::fltk::WidgetExtExtra;
 use shared::{column,row,add,fixed,hover_blink};
 
 use crate::dimm;
-use crate::common;
+use crate::lib;
 use crate::log;
 use crate::log_err;
 
@@ -34,6 +36,14 @@ pub struct Term
   pub term : SimpleTerminal,
   // Terminal message sender
   pub tx : mpsc::Sender<String>,
+  // Pseudo terminal of the current process
+  arc_pty : Arc<Mutex<Option<lib::pty::Pty>>>,
+  // Emulated screen of the current process
+  arc_screen : Arc<Mutex<lib::vt::Screen>>,
+  // Number of lines at the end of term that show the screen
+  arc_lines_screen : Arc<Mutex<i32>>,
+  // File that receives a copy of the process output
+  arc_file_tee : Arc<Mutex<Option<fs::File>>>,
   // Save button
   #[allow(dead_code)] pub btn_save : fltk::button::Button,
   // Group
@@ -69,6 +79,8 @@ pub fn default() -> Term
   let mut term = term.clone();
   term.set_text_color(Color::from_hex_str("#fffffff").unwrap());
   term.set_text_size(dimm::height_text());
+  // Colors from the emulated screen are sent as ansi escapes
+  term.set_ansi(true);
   term.wrap_mode(fltk::text::WrapMode::None, 0);
   term.set_history_lines(std::i32::MAX);
   term.set_scrollbar_size(dimm::border());
@@ -116,8 +128,47 @@ pub fn default() -> Term
     } // while
   });
 
+  let arc_pty : Arc<Mutex<Option<lib::pty::Pty>>> = Arc::new(Mutex::new(None));
+  let arc_screen = Arc::new(Mutex::new(lib::vt::Screen::new(24, 80)));
+
+  // Forward keys to the running process
+  term.handle({
+    let arc_pty = arc_pty.clone();
+    move |term, event|
+    {
+      let guard = arc_pty.lock().unwrap();
+      let pty = match guard.as_ref() { Some(pty) => pty, None => return false };
+      match event
+      {
+        Event::Push => { let _ = term.take_focus(); false },
+        Event::Focus | Event::Unfocus => true,
+        Event::KeyDown => match key_bytes()
+        {
+          Some(bytes) => { log_err!(pty.write(&bytes)); true },
+          None => false,
+        },
+        _ => false,
+      } // match
+    }
+  });
+
+  // Propagate the size of the widget to the screen and process
+  term.resize_callback({
+    let arc_pty = arc_pty.clone();
+    let arc_screen = arc_screen.clone();
+    move |term,_,_,w,h|
+    {
+      let (rows, cols) = size_chars(term, w, h);
+      arc_screen.lock().unwrap().resize(rows as usize, cols as usize);
+      if let Some(pty) = arc_pty.lock().unwrap().as_ref()
+      {
+        log_err!(pty.resize(rows, cols));
+      } // if
+    }
+  });
+
   // Return new term
-  Term{ term, opt_proc_thread: None, tx, btn_save, group: row }
+  Term{ term, opt_proc_thread: None, tx, btn_save, group: row, arc_pty, arc_screen, arc_lines_screen: Arc::new(Mutex::new(0)), arc_file_tee: Arc::new(Mutex::new(None)) }
 } // new_with_id() }}}
 
 // kill() {{{
@@ -129,10 +180,15 @@ fn kill(&mut self, opt_proc_thread : Option<(Arc<Mutex<std::process::Child>>, Ar
     None => { log!("No process to terminate"); return; },
   }; // match
 
-  // Kill process
+  // Kill process and its children, the process leads its own session
   match proc.lock()
   {
-    Ok(mut guard) => { let _ = guard.kill(); let _ = guard.wait(); },
+    Ok(mut guard) =>
+    {
+      unsafe { libc::kill(-(guard.id() as libc::pid_t), libc::SIGKILL); }
+      let _ = guard.kill();
+      let _ = guard.wait();
+    },
     Err(e) => { log!("Could not lock arc with error: {}", e); return; }
   }; // match
 
@@ -144,48 +200,73 @@ fn kill(&mut self, opt_proc_thread : Option<(Arc<Mutex<std::process::Child>>, Ar
   }; // match
 } // kill() }}}
 
+// pub fn set_tee() {{{
+// Appends the output of the next processes to a file, None stops
+pub fn set_tee(&self, opt_path_file : Option<&PathBuf>) -> anyhow::Result<()>
+{
+  *self.arc_file_tee.lock().unwrap() = match opt_path_file
+  {
+    Some(path_file) => Some(fs::OpenOptions::new().append(true).create(true).open(path_file)?),
+    None => None,
+  }; // match
+  Ok(())
+} // fn set_tee() }}}
+
 // pub fn dispatch() {{{
+// Runs the command in a pseudo terminal, the output is shown on an emulated screen
 pub fn dispatch<F>(&mut self, args : Vec<&str>, mut callback : F) -> anyhow::Result<Arc<Mutex<Child>>>
   where F : FnMut(i32) + Send + 'static
 {
   let (cmd_base, cmd_args) = args.split_first().ok_or(ah!("No command to execute"))?;
 
-  let mut reader_cmd = Command::new(cmd_base)
-    .env_remove("LD_PRELOAD")
+  let mut cmd = Command::new(cmd_base);
+  cmd.env_remove("LD_PRELOAD")
     .env("FIM_FIFO", "0")
-    .args(cmd_args)
-    .stdin(Stdio::piped())
-    .stderr(Stdio::piped())
-    .stdout(Stdio::piped())
-    .spawn()?;
-
-  // Create arc reader for stdout
-  let arc_stdout = Arc::new(Mutex::new(reader_cmd.stdout.take()));
-  let arc_stderr = Arc::new(Mutex::new(reader_cmd.stderr.take()));
-
-  // Put child in arc
-  let arc_reader = Arc::new(Mutex::new(reader_cmd));
+    .args(cmd_args);
 
   // Kill existing process if any
   self.kill(self.opt_proc_thread.clone());
 
+  // Start with a clean screen of the current size
+  let (rows, cols) = size_chars(&self.term, self.term.w(), self.term.h());
+  *self.arc_screen.lock().unwrap() = lib::vt::Screen::new(rows as usize, cols as usize);
+  *self.arc_lines_screen.lock().unwrap() = 0;
+
+  let (pty, child) = lib::pty::Pty::spawn(cmd, rows, cols)?;
+  let mut reader = pty.reader()?;
+  *self.arc_pty.lock().unwrap() = Some(pty);
+
+  // Put child in arc
+  let arc_reader = Arc::new(Mutex::new(child));
+
   // Setup callback
-  let clone_arc_stdout = arc_stdout.clone();
-  let clone_arc_stderr = arc_stderr.clone();
   let clone_arc_reader = arc_reader.clone();
-  let clone_tx = self.tx.clone();
+  let clone_arc_pty = self.arc_pty.clone();
+  let clone_arc_screen = self.arc_screen.clone();
+  let clone_arc_lines_screen = self.arc_lines_screen.clone();
+  let clone_arc_file_tee = self.arc_file_tee.clone();
+  let mut clone_term = self.term.clone();
   let handle = std::thread::spawn(move ||
   {
-    let (tx_log, rx_log) = mpsc::channel::<String>();
-    let f_callback = move |tx : mpsc::Sender<String>, msg : String| { log_err!(tx.send(msg)); };
-    let handle_stdout = std::thread::spawn(common::log_fd(clone_arc_stdout.lock().unwrap().take().unwrap(), tx_log.clone(), f_callback.clone()));
-    let handle_stderr = std::thread::spawn(common::log_fd(clone_arc_stderr.lock().unwrap().take().unwrap(), tx_log, f_callback));
-    while let Ok(msg) = rx_log.recv()
+    // Reads fail once every process attached to the terminal exits
+    let mut buf = vec![0; 4096];
+    loop
     {
-      let _ = clone_tx.send(msg);
-    } // while
-    log_err!(handle_stdout.join());
-    log_err!(handle_stderr.join());
+      let bytes_read = match reader.read(&mut buf)
+      {
+        Ok(0) | Err(_) => break,
+        Ok(bytes_read) => bytes_read,
+      }; // match
+      if let Some(file_tee) = clone_arc_file_tee.lock().unwrap().as_mut()
+      {
+        log_err!(file_tee.write_all(&buf[..bytes_read]));
+      } // if
+      let mut screen = clone_arc_screen.lock().unwrap();
+      screen.feed(&buf[..bytes_read]);
+      render(&mut clone_term, &mut screen, &mut clone_arc_lines_screen.lock().unwrap());
+      app::awake();
+    } // loop
+    *clone_arc_pty.lock().unwrap() = None;
 
     // Make callback
     let code_return : i32 = if let Ok(mut lock) = clone_arc_reader.lock()
@@ -201,6 +282,13 @@ pub fn dispatch<F>(&mut self, args : Vec<&str>, mut callback : F) -> anyhow::Res
   Ok(arc_reader.clone())
 } // dispatch() }}}
 
+// pub fn write() {{{
+// Sends input to the running process, as typed in the terminal
+pub fn write(&self, value: &str) -> anyhow::Result<()>
+{
+  self.arc_pty.lock().unwrap().as_ref().ok_or(ah!("No process running"))?.write(value.as_bytes())
+} // fn: write }}}
+
 // pub fn append() {{{
 #[allow(dead_code)] pub fn append(&self, value: &str)
 {
@@ -209,4 +297,139 @@ pub fn dispatch<F>(&mut self, args : Vec<&str>, mut callback : F) -> anyhow::Res
 
 } // impl
 
+// fn size_chars() {{{
+// Rows and columns of text that fit in the terminal widget
+fn size_chars(term : &SimpleTerminal, w : i32, h : i32) -> (u16, u16)
+{
+  fltk::draw::set_font(term.text_font(), term.text_size());
+  let width_char = fltk::draw::width("M").max(1.0);
+  let height_char = fltk::draw::height().max(1);
+  let cols = ((w - term.scrollbar_size()) as f64 / width_char) as i32;
+  let rows = (h - term.scrollbar_size()) / height_char;
+  (rows.clamp(1, u16::MAX as i32) as u16, cols.clamp(1, u16::MAX as i32) as u16)
+} // fn size_chars() }}}
+
+// fn render() {{{
+// Appends the lines that left the screen and replaces the screen shown at the end of term
+fn render(term : &mut SimpleTerminal, screen : &mut lib::vt::Screen, lines_screen : &mut i32)
+{
+  let length = term.buffer().map(|e| e.length()).unwrap_or(0);
+  let lines_total = term.count_lines(0, length, true);
+  if *lines_screen > 0
+  {
+    term.remove_lines((lines_total - *lines_screen).max(0), *lines_screen);
+  } // if
+  let mut text = String::new();
+  for line in screen.take_scrolled() { text.push_str(&line); text.push('\n'); } // for
+  let lines = screen.render();
+  for line in &lines { text.push_str(line); text.push('\n'); } // for
+  *lines_screen = lines.len() as i32;
+  term.append(&text);
+} // fn render() }}}
+
+// fn key_bytes() {{{
+// Input sequence of the last key event, as sent by a vt100 terminal
+fn key_bytes() -> Option<Vec<u8>>
+{
+  let seq = match app::event_key()
+  {
+    Key::Enter | Key::KPEnter => "\r",
+    Key::BackSpace => "\x7f",
+    Key::Tab => "\t",
+    Key::Escape => "\x1b",
+    Key::Up => "\x1b[A",
+    Key::Down => "\x1b[B",
+    Key::Right => "\x1b[C",
+    Key::Left => "\x1b[D",
+    Key::Home => "\x1b[H",
+    Key::End => "\x1b[F",
+    Key::Insert => "\x1b[2~",
+    Key::Delete => "\x1b[3~",
+    Key::PageUp => "\x1b[5~",
+    Key::PageDown => "\x1b[6~",
+    // Printable keys and control combinations, e.g., ctrl+c is \x03
+    _ =>
+    {
+      let text = app::event_text();
+      return if text.is_empty() { None } else { Some(text.into_bytes()) };
+    },
+  }; // match
+  Some(seq.as_bytes().to_vec())
+} // fn key_bytes() }}}
+
+// pub fn log_viewer() {{{
+// Filters by level, text and backend job for the session log shown in the 'term_log' terminal
+pub fn log_viewer() -> fltk::group::Flex
+{
+  row!(row,
+    row.set_spacing(dimm::border_half());
+    fixed!(row, menu_level, MenuButton::default()
+      .with_frame(FrameType::FlatBox)
+      .with_color(Color::BackGround.lighter()), dimm::width_button_wide());
+    add!(row, input_text, fltk::input::Input::default());
+    fixed!(row, menu_job, MenuButton::default()
+      .with_frame(FrameType::FlatBox)
+      .with_color(Color::BackGround.lighter()), dimm::width_button_wide()*2);
+  );
+
+  // Re-draws the terminal with the records that match the filter
+  let f_apply = |f : &dyn Fn(&mut lib::log::Filter)|
+  {
+    let mut filter = lib::log::filter();
+    f(&mut filter);
+    let text = lib::log::set_filter(filter);
+    if let Some(mut term) = app::widget_from_id::<SimpleTerminal>("term_log")
+    {
+      term.clear();
+      term.append(&text);
+    } // if
+  };
+
+  // Minimum level
+  let mut menu_level = menu_level.clone();
+  menu_level.add_choice(&lib::log::Level::all().map(|e| e.as_str()).join("|"));
+  menu_level.set_label(lib::log::filter().level.as_str());
+  menu_level.set_tooltip("Minimum level of the displayed messages");
+  menu_level.set_callback(move |e|
+  {
+    let level = match e.choice().and_then(|e| lib::log::Level::from_str(&e)) { Some(level) => level, None => return };
+    e.set_label(level.as_str());
+    f_apply(&|filter : &mut lib::log::Filter| filter.level = level);
+  });
+
+  // Text contained in the message
+  let mut input_text = input_text.clone();
+  input_text.set_tooltip("Show messages that contain this text");
+  input_text.set_trigger(CallbackTrigger::Changed);
+  input_text.set_callback(move |e|
+  {
+    let text = e.value();
+    f_apply(&|filter : &mut lib::log::Filter| filter.text = text.clone());
+  });
+
+  // Backend job, the list is refreshed when the menu is opened
+  let mut menu_job = menu_job.clone();
+  menu_job.set_label(&lib::log::filter().job.unwrap_or("all jobs".into()));
+  menu_job.set_tooltip("Show messages of a backend job");
+  menu_job.handle(|e, event|
+  {
+    if event == Event::Push
+    {
+      e.clear();
+      e.add_choice("all jobs");
+      for job in lib::log::jobs() { e.add_choice(&job); } // for
+    } // if
+    false
+  });
+  menu_job.set_callback(move |e|
+  {
+    let job = match e.choice() { Some(job) => job, None => return };
+    e.set_label(&job);
+    let job = if job == "all jobs" { None } else { Some(job) };
+    f_apply(&|filter : &mut lib::log::Filter| filter.job = job.clone());
+  });
+
+  row
+} // fn: log_viewer }}}
+
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
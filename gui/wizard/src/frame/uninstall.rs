@@ -0,0 +1,170 @@
+use std::sync::{Arc,Mutex};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::HoldBrowser,
+  dialog,
+  enums::{Color,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
+};
+
+use anyhow::anyhow as ah;
+
+use shared::fltk::WidgetExtExtra;
+use shared::std::PathBufExt;
+use shared::{column,row};
+
+use crate::dimm;
+use crate::frame;
+use crate::db;
+use crate::lib;
+use crate::gameimage;
+use crate::log_status;
+
+// fn fill() {{{
+// Lists the programs installed into the prefix of the current project
+fn fill(browser : &mut HoldBrowser, display : &mut TextDisplay, programs : &Arc<Mutex<Vec<lib::uninstall::Program>>>)
+{
+  let mut programs = programs.lock().unwrap();
+  let result = db::global::get_current_project().and_then(|e| lib::uninstall::list(&e.path_dir_project));
+  browser.clear();
+  let text = match result
+  {
+    Ok(list) =>
+    {
+      *programs = list;
+      programs.iter().for_each(|program| browser.add(&program.summary()));
+      if programs.is_empty() { "No programs are registered in the wine prefix".to_string() } else { "Select a program".to_string() }
+    },
+    Err(e) => { programs.clear(); format!("Could not read the installed programs: {}", e) },
+  }; // match
+  if let Some(mut buffer) = display.buffer() { buffer.set_text(&text); } // if
+} // fn fill() }}}
+
+// fn selected() {{{
+fn selected(browser : &HoldBrowser, programs : &Arc<Mutex<Vec<lib::uninstall::Program>>>) -> Option<lib::uninstall::Program>
+{
+  // Lines start at one
+  let index = browser.value() as usize;
+  if index == 0 { return None; } // if
+  programs.lock().unwrap().get(index-1).cloned()
+} // fn selected() }}}
+
+// pub fn uninstall() {{{
+// Opens a window with the programs registered in the uninstall keys of the prefix registry, which
+// can be opened in the file manager, made the default executable or uninstalled
+pub fn uninstall()
+{
+  let programs = Arc::new(Mutex::new(Vec::<lib::uninstall::Program>::new()));
+  let mut wind = frame::common::window("Installed programs");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row,
+      let mut browser = HoldBrowser::default();
+      row.fixed(&browser, dimm::width_wizard() * 2 / 5);
+      let mut display = TextDisplay::default()
+        .with_color(Color::BackGround)
+        .with_frame(FrameType::BorderBox);
+      row.add(&display);
+    );
+    col.add(&row);
+    row!(row_buttons,
+      let btn_folder = shared::fltk::button::wide::default().with_label("Open folder");
+      let btn_default = shared::fltk::button::wide::default().with_label("Set default");
+      let btn_uninstall = shared::fltk::button::wide::default().with_label("Uninstall");
+      let btn_close = shared::fltk::button::wide::default().with_label("Close");
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  // Programs
+  browser.set_text_size(dimm::height_text());
+  display.wrap_mode(WrapMode::AtBounds, 0);
+  display.set_buffer(TextBuffer::default());
+  fill(&mut browser, &mut display, &programs);
+  browser.set_callback({
+    let programs = programs.clone();
+    let display = display.clone();
+    move |e|
+    {
+      let Some(program) = selected(e, &programs) else { return; };
+      if let Some(mut buffer) = display.buffer() { buffer.set_text(&program.details()); } // if
+    }
+  });
+  // Runs the operation in the background and lists the programs again
+  let f_background = frame::common::background(wind.clone(), {
+    let browser = browser.clone();
+    let display = display.clone();
+    let programs = programs.clone();
+    move || fill(&mut browser.clone(), &mut display.clone(), &programs)
+  });
+  btn_folder.clone().with_color(Color::Blue).set_callback({
+    let browser = browser.clone();
+    let programs = programs.clone();
+    move |_|
+    {
+      let Some(program) = selected(&browser, &programs) else { return; };
+      let path_dir = db::global::get_current_project().ok().and_then(|e| program.path_dir(&e.path_dir_project));
+      let Some(path_dir) = path_dir else { log_status!("Could not find the folder of '{}'", program.name); return; };
+      log_status!("Open '{}'", path_dir.string());
+      // Open with xdg-open
+      let _ = std::process::Command::new("fim_portal")
+          .stderr(std::process::Stdio::inherit())
+          .stdout(std::process::Stdio::inherit())
+          .arg("xdg-open")
+          .arg(&path_dir.string())
+          .spawn();
+    }
+  });
+  btn_default.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let programs = programs.clone();
+    move |_|
+    {
+      let Some(program) = selected(&browser, &programs) else { return; };
+      f_background(Box::new(move ||
+      {
+        let path_dir_project = db::global::get_current_project()?.path_dir_project;
+        let path_file_executable = program.executable(&path_dir_project)
+          .ok_or(ah!("Could not find the executable of '{}'", program.name))?;
+        gameimage::select::select("rom", &path_file_executable)?;
+        log_status!("Selected '{}' as the default executable", path_file_executable.string());
+        Ok(())
+      }));
+    }
+  });
+  btn_uninstall.clone().with_color(Color::Red).set_callback({
+    let f_background = f_background.clone();
+    let browser = browser.clone();
+    let programs = programs.clone();
+    move |_|
+    {
+      let Some(program) = selected(&browser, &programs) else { return; };
+      if dialog::choice2_default(&format!("Uninstall '{}' from the wine prefix?", program.name), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      f_background(Box::new(move ||
+      {
+        if let Err(e) = lib::snapshot::create(&format!("Before uninstalling {}", program.name))
+        {
+          log_status!("Could not create snapshot of the wine prefix: {}", e);
+        } // if
+        program.uninstall()?;
+        log_status!("Ran the uninstaller of '{}'", program.name);
+        Ok(())
+      }));
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+} // fn uninstall() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
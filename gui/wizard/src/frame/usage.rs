@@ -0,0 +1,293 @@
+use std::
+{
+  path::PathBuf,
+  sync::{Arc,Mutex},
+};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  dialog,
+  enums::{Align,Color},
+  frame::Frame,
+  menu::MenuButton,
+  tree::{Tree,TreeItem,TreeReason},
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::std::PathBufExt;
+use shared::{column,row,fixed,add};
+
+use crate::dimm;
+use crate::frame;
+use crate::db;
+use crate::lib;
+use crate::log_status;
+
+use lib::usage::{Node,Order};
+
+// Label of the children that are not inserted yet
+const LABEL_PENDING : &str = "...";
+// Children inserted when a directory is opened, the smallest ones are summarized
+const COUNT_CHILDREN_MAX : usize = 1000;
+
+// struct State {{{
+struct State
+{
+  path_dir : PathBuf,
+  opt_root : Option<Node>,
+  order    : Order,
+} // struct State }}}
+
+// fn directories() {{{
+// The project and the directories in it, e.g., 'wine', 'linux' or 'rom'
+fn directories() -> Vec<(String, PathBuf)>
+{
+  let Ok(project) = db::global::get_current_project() else { return vec![]; };
+  let mut directories = vec![("Project".to_string(), project.path_dir_project.clone())];
+  if let Ok(entries) = std::fs::read_dir(&project.path_dir_project)
+  {
+    let mut entries : Vec<(String, PathBuf)> = entries
+      .filter_map(|e| e.ok())
+      .filter(|e| e.file_type().is_ok_and(|e| e.is_dir()))
+      .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+      .collect();
+    entries.sort();
+    directories.extend(entries);
+  } // if
+  directories
+} // fn directories() }}}
+
+// fn label() {{{
+// Share of the total, size and name, the name is after the closing bracket
+fn label(node : &Node, size_total : u64) -> String
+{
+  let percentage = if size_total == 0 { 0.0 } else { node.size as f64 * 100.0 / size_total as f64 };
+  let files = if node.is_dir { format!(", {} files", node.count_files) } else { String::new() };
+  format!("[{:.1}%, {}{}] {}", percentage, lib::format::format_size(node.size), files, node.name)
+} // fn label() }}}
+
+// fn names() {{{
+// Names of the item and of its parents up to the root
+fn names(item : &TreeItem) -> Vec<String>
+{
+  let mut names = vec![];
+  let mut opt_item = Some(item.clone());
+  while let Some(item) = opt_item && ! item.is_root()
+  {
+    let label = item.label().unwrap_or_default();
+    names.push(label.split_once("] ").map(|e| e.1.to_string()).unwrap_or(label));
+    opt_item = item.parent();
+  } // while
+  names.reverse();
+  names
+} // fn names() }}}
+
+// fn insert_children() {{{
+// Inserts the children of a node, directories get a pending child so they can be opened
+fn insert_children(tree : &mut Tree, item : &TreeItem, node : &Node, size_total : u64)
+{
+  for child in node.children.iter().take(COUNT_CHILDREN_MAX)
+  {
+    let Some(mut item_child) = tree.insert(item, &label(child, size_total), i32::MAX) else { continue; };
+    if child.is_dir && ! child.children.is_empty()
+    {
+      let _ = tree.insert(&item_child, LABEL_PENDING, 0);
+      item_child.close();
+    } // if
+  } // for
+  if node.children.len() > COUNT_CHILDREN_MAX
+  {
+    let size : u64 = node.children.iter().skip(COUNT_CHILDREN_MAX).map(|e| e.size).sum();
+    let _ = tree.insert(item, &format!("{} more entries with {}", node.children.len() - COUNT_CHILDREN_MAX
+      , lib::format::format_size(size)), i32::MAX);
+  } // if
+} // fn insert_children() }}}
+
+// fn fill() {{{
+fn fill(tree : &mut Tree, frame_total : &mut Frame, state : &State)
+{
+  tree.clear();
+  match &state.opt_root
+  {
+    Some(root) =>
+    {
+      if let Some(item_root) = tree.root() { insert_children(tree, &item_root, root, root.size); } // if
+      frame_total.set_label(&format!("{} in {} files", lib::format::format_size(root.size), root.count_files));
+    },
+    None => frame_total.set_label("Scanning..."),
+  } // match
+  tree.redraw();
+} // fn fill() }}}
+
+// pub fn usage() {{{
+// Opens a window with the disk usage of a directory of the current project, as a tree of sizes
+// and shares of the total. Entries can be opened in the file manager or deleted.
+pub fn usage()
+{
+  let directories = directories();
+  let Some((_, path_dir)) = directories.first().cloned() else { log_status!("Could not read current project"); return; };
+  let state = Arc::new(Mutex::new(State{ path_dir, opt_root: None, order: Order::Size }));
+  let mut wind = frame::common::window("Disk usage");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row_top,
+      fixed!(row_top, menu_dir, MenuButton::default(), dimm::width_button_wide() * 3);
+      fixed!(row_top, menu_order, MenuButton::default(), dimm::width_button_wide() * 2);
+      add!(row_top, frame_total, Frame::default().with_align(Align::Inside | Align::Right));
+    );
+    col.fixed(&row_top, dimm::height_button_wide());
+    let mut tree = Tree::default();
+    col.add(&tree);
+    row!(row_buttons,
+      let btn_refresh = shared::fltk::button::wide::default().with_label("Refresh");
+      let btn_open = shared::fltk::button::wide::default().with_label("Open");
+      let btn_delete = shared::fltk::button::wide::default().with_label("Delete");
+      let btn_close = shared::fltk::button::wide::default().with_label("Close");
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  let frame_total = frame_total.clone();
+  tree.set_show_root(false);
+  // Lists the scanned directory after each operation in the background
+  let f_background = frame::common::background(wind.clone(), {
+    let state = state.clone();
+    let tree = tree.clone();
+    let frame_total = frame_total.clone();
+    move || fill(&mut tree.clone(), &mut frame_total.clone(), &state.lock().unwrap())
+  });
+  // Scans the selected directory in the background
+  let f_scan = {
+    let state = state.clone();
+    let tree = tree.clone();
+    let frame_total = frame_total.clone();
+    move ||
+    {
+      let path_dir = {
+        let mut state = state.lock().unwrap();
+        state.opt_root = None;
+        fill(&mut tree.clone(), &mut frame_total.clone(), &state);
+        state.path_dir.clone()
+      };
+      let state = state.clone();
+      f_background(Box::new(move ||
+      {
+        let mut root = lib::usage::scan(&path_dir)?;
+        let mut state = state.lock().unwrap();
+        root.sort(state.order);
+        state.opt_root = Some(root);
+        Ok(())
+      }));
+    }
+  };
+  // Directory
+  let mut menu_dir = menu_dir.clone();
+  directories.iter().for_each(|(name, _)| { menu_dir.add_choice(&name.replace("/", "\\/")); });
+  menu_dir.set_label(&directories[0].0);
+  menu_dir.set_callback({
+    let state = state.clone();
+    let f_scan = f_scan.clone();
+    move |e|
+    {
+      let Some((name, path_dir)) = directories.get(e.value().max(0) as usize).cloned() else { return; };
+      e.set_label(&name);
+      state.lock().unwrap().path_dir = path_dir;
+      f_scan();
+    }
+  });
+  // Order
+  let mut menu_order = menu_order.clone();
+  Order::ALL.iter().for_each(|e| { menu_order.add_choice(&format!("Sort by {}", e.as_str().to_lowercase())); });
+  menu_order.set_label(&format!("Sort by {}", Order::Size.as_str().to_lowercase()));
+  menu_order.set_callback({
+    let state = state.clone();
+    let mut tree = tree.clone();
+    let mut frame_total = frame_total.clone();
+    move |e|
+    {
+      let Some(order) = Order::ALL.get(e.value().max(0) as usize).copied() else { return; };
+      e.set_label(&format!("Sort by {}", order.as_str().to_lowercase()));
+      let mut state = state.lock().unwrap();
+      state.order = order;
+      if let Some(root) = state.opt_root.as_mut() { root.sort(order); } // if
+      fill(&mut tree, &mut frame_total, &state);
+    }
+  });
+  // Insert the children of directories as they are opened
+  tree.set_callback({
+    let state = state.clone();
+    move |e|
+    {
+      if e.callback_reason() != TreeReason::Opened { return; } // if
+      let Some(mut item) = e.callback_item() else { return; };
+      if item.children() != 1 || item.child(0).and_then(|e| e.label()).as_deref() != Some(LABEL_PENDING) { return; } // if
+      let state = state.lock().unwrap();
+      let Some(root) = state.opt_root.as_ref() else { return; };
+      let Some(node) = root.find(&names(&item)) else { return; };
+      item.clear_children();
+      insert_children(e, &item, node, root.size);
+      e.redraw();
+    }
+  });
+  // Path of the selected entry
+  let f_selected = {
+    let state = state.clone();
+    let tree = tree.clone();
+    move || -> Option<PathBuf>
+    {
+      let item = tree.first_selected_item()?;
+      let names = names(&item);
+      let state = state.lock().unwrap();
+      state.opt_root.as_ref()?.find(&names)?;
+      Some(names.iter().fold(state.path_dir.clone(), |acc, e| acc.join(e)))
+    }
+  };
+  btn_refresh.clone().with_color(Color::Blue).set_callback({
+    let f_scan = f_scan.clone();
+    move |_| f_scan()
+  });
+  btn_open.clone().with_color(Color::Blue).set_callback({
+    let f_selected = f_selected.clone();
+    move |_|
+    {
+      let Some(mut path) = f_selected() else { return; };
+      if ! path.is_dir() { path.pop(); } // if
+      log_status!("Open '{}'", path.string());
+      // Open with xdg-open
+      let _ = std::process::Command::new("fim_portal")
+          .stderr(std::process::Stdio::inherit())
+          .stdout(std::process::Stdio::inherit())
+          .arg("xdg-open")
+          .arg(&path.string())
+          .spawn();
+    }
+  });
+  btn_delete.clone().with_color(Color::Red).set_callback({
+    let f_scan = f_scan.clone();
+    move |_|
+    {
+      let Some(path) = f_selected() else { return; };
+      if dialog::choice2_default(&format!("Delete '{}'?", path.string()), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      match lib::usage::remove(&path)
+      {
+        Ok(()) => log_status!("Deleted '{}'", path.string()),
+        Err(e) => log_status!("Could not delete '{}': {}", path.string(), e),
+      } // match
+      f_scan();
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+  f_scan();
+} // fn usage() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -1,5 +1,6 @@
 use std::env;
 use std::path::PathBuf;
+use std::sync::{LazyLock,Mutex};
 
 // Gui
 use fltk::prelude::*;
@@ -12,9 +13,11 @@ use fltk::{
 };
 
 use shared::fltk::SenderExt;
+use shared::fltk::WidgetExtExtra;
 use anyhow::anyhow as ah;
 
 use crate::db;
+use crate::lib;
 use crate::gameimage;
 use crate::dimm;
 use crate::common;
@@ -44,6 +47,9 @@ fn check_version() -> anyhow::Result<()>
 // pub fn welcome() {{{
 pub fn welcome(tx: Sender<common::Msg>, title: &str)
 {
+  // Report of the last checked directory
+  static REPORT : LazyLock<Mutex<Option<(PathBuf, lib::preflight::Report)>>> = LazyLock::new(|| Mutex::new(None));
+
   let ui = crate::GUI.lock().unwrap().ui.clone()(title);
 
   column!(col,
@@ -60,8 +66,49 @@ pub fn welcome(tx: Sender<common::Msg>, title: &str)
         .with_label("Select The Directory for GameImage's Temporary Files")
       , dimm::height_text());
     fixed!(col, input_dir, FileInput::default(), dimm::height_button_wide() + dimm::border_half());
+    fixed!(col, display_preflight, fltk::text::TextDisplay::default(), dimm::height_text()*8);
   );
 
+  // Preflight report of the selected directory
+  let mut display_preflight = display_preflight.clone()
+    .with_frame(fltk::enums::FrameType::BorderBox)
+    .with_color(fltk::enums::Color::BackGround);
+  display_preflight.wrap_mode(fltk::text::WrapMode::AtBounds, 0);
+  display_preflight.visible_focus(false);
+  display_preflight.set_buffer(fltk::text::TextBuffer::default());
+  // The checks probe the filesystem, which is slow on network mounts, so they run in the background
+  // and the next button waits for them
+  let f_preflight =
+  {
+    let display_preflight = display_preflight.clone();
+    let btn_next = ui.btn_next.clone();
+    move |path_dir_build : &PathBuf|
+    {
+      let mut display_preflight = display_preflight.clone();
+      let mut btn_next = btn_next.clone();
+      let path_dir_build = path_dir_build.clone();
+      btn_next.deactivate();
+      display_preflight.set_text_color(fltk::enums::Color::Foreground);
+      if let Some(mut buffer) = display_preflight.buffer() { buffer.set_text("Checking the directory..."); } // if
+      std::thread::spawn(move ||
+      {
+        let report = lib::preflight::check(&path_dir_build);
+        if let Some(mut buffer) = display_preflight.buffer() { buffer.set_text(&report.format()); } // if
+        if ! report.is_ok() { display_preflight.set_text_color(fltk::enums::Color::Red); }
+        else if ! report.warnings.is_empty() { display_preflight.set_text_color(fltk::enums::Color::Yellow); }
+        else { display_preflight.set_text_color(fltk::enums::Color::Foreground); }
+        display_preflight.redraw();
+        *REPORT.lock().unwrap() = Some((path_dir_build, report));
+        btn_next.activate();
+        fltk::app::awake();
+      });
+    }
+  };
+  if let Ok(path_dir_build) = env::var("GIMG_DIR").map(PathBuf::from)
+  {
+    f_preflight(&path_dir_build);
+  } // if
+
   // Image
   let mut frame_image = frame_image.clone();
   frame_image.set_align(Align::Inside | Align::Bottom);
@@ -72,7 +119,7 @@ pub fn welcome(tx: Sender<common::Msg>, title: &str)
   input_dir.set_pos(dimm::border(), input_dir.y());
   input_dir.set_readonly(true);
   input_dir.set_value(&env::var("GIMG_DIR").unwrap_or_default());
-  input_dir.set_callback(move |e|
+  input_dir.set_callback({ let f_preflight = f_preflight.clone(); move |e|
   {
     let mut path_selected = match dir_chooser("Select the build directory", "", false)
     {
@@ -85,7 +132,9 @@ pub fn welcome(tx: Sender<common::Msg>, title: &str)
     e.set_value(&path_selected.string());
     // Set env var to build dir
     env::set_var("GIMG_DIR", &path_selected.string());
-  });
+    // Check if the directory can hold a build
+    f_preflight(&path_selected);
+  }});
 
   // Set callback for next
   let clone_tx = tx.clone();
@@ -96,12 +145,22 @@ pub fn welcome(tx: Sender<common::Msg>, title: &str)
       Ok(value) => PathBuf::from(value),
       Err(e) => { log_status!("Invalid temporary files directory: {}", e); return; }
     }; // if
-    // Create build directory
-    match std::fs::create_dir_all(&path_dir_build)
+    // Checks of the build directory, they create it
+    let opt_report = REPORT.lock().unwrap().clone()
+      .filter(|(path_dir, _)| *path_dir == path_dir_build)
+      .map(|(_, report)| report);
+    let Some(report) = opt_report else { log_status!("The selected directory is still being checked"); return; };
+    if ! report.is_ok()
     {
-      Ok(()) => (),
-      Err(e) => log_status!("Could not create build directory: {}", e),
-    }
+      log_status!("The selected directory cannot be used, see the blocking problems");
+      return;
+    } // if
+    if ! report.warnings.is_empty()
+    && fltk::dialog::choice2_default("The selected directory has warnings, continue anyway?", "Cancel", "Continue", "") != Some(1)
+    {
+      log_status!("Select another directory");
+      return;
+    } // if
     // Init project build directory
     match gameimage::init::build(path_dir_build)
     {
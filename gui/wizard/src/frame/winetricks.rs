@@ -0,0 +1,237 @@
+use std::
+{
+  collections::HashSet,
+  sync::{Arc,Mutex},
+};
+
+// Gui
+use fltk::prelude::*;
+use fltk::{
+  browser::CheckBrowser,
+  enums::{Align,CallbackTrigger,Color,Font},
+  frame::Frame,
+  input::Input,
+  menu::MenuButton,
+};
+
+use shared::fltk::WidgetExtExtra;
+use shared::{column,row,fixed,add};
+
+use crate::dimm;
+use crate::frame;
+use crate::db;
+use crate::lib;
+use crate::gameimage;
+use crate::log_status;
+
+use lib::winetricks::Verb;
+
+// Label of the entry of the category menu that shows all verbs
+const LABEL_ALL : &str = "All categories";
+
+// struct State {{{
+#[derive(Default)]
+struct State
+{
+  verbs             : Vec<Verb>,
+  installed         : HashSet<String>,
+  selected          : HashSet<String>,
+  query             : String,
+  opt_category      : Option<String>,
+  is_hide_installed : bool,
+  // Indices of the verbs in the browser
+  visible           : Vec<usize>,
+} // struct State }}}
+
+// fn fill() {{{
+// Lists the verbs that match the query and the filters, selections are kept while filtering
+fn fill(browser : &mut CheckBrowser, frame_count : &mut Frame, state : &mut State)
+{
+  browser.clear();
+  state.visible = state.verbs.iter()
+    .enumerate()
+    .filter(|(_, e)| state.opt_category.as_ref().is_none_or(|c| e.category == *c))
+    .filter(|(_, e)| ! (state.is_hide_installed && state.installed.contains(&e.name)))
+    .filter(|(_, e)| e.matches(&state.query))
+    .map(|(i, _)| i)
+    .collect();
+  for verb in state.visible.iter().map(|e| &state.verbs[*e])
+  {
+    let tag = if state.installed.contains(&verb.name) { "[installed] " } else { "" };
+    browser.add(&format!("{:<24} {:<9} {}{}", verb.name, verb.category, tag, verb.description)
+      , state.selected.contains(&verb.name));
+  } // for
+  frame_count.set_label(&format!("{} of {} verbs, {} selected", state.visible.len(), state.verbs.len(), state.selected.len()));
+  browser.redraw();
+} // fn fill() }}}
+
+// pub fn catalog() {{{
+// Opens a window with all the verbs of winetricks, which can be searched, filtered by category and
+// installed together. Verbs in the 'winetricks.log' of the prefix are marked as installed.
+pub fn catalog()
+{
+  let state = Arc::new(Mutex::new(State::default()));
+  let mut wind = frame::common::window("Winetricks catalog");
+  wind.begin();
+  column!(col,
+    col.set_margin(dimm::border());
+    row!(row_search,
+      fixed!(row_search, menu_category, MenuButton::default(), dimm::width_button_wide() * 2);
+      add!(row_search, input_query, Input::default());
+      fixed!(row_search, btn_installed, shared::fltk::button::rect::checkbutton().with_label("Hide installed"), dimm::width_button_wide() * 2);
+    );
+    col.fixed(&row_search, dimm::height_button_wide());
+    add!(col, browser, CheckBrowser::default());
+    fixed!(col, frame_count, Frame::default().with_align(Align::Inside | Align::Left), dimm::height_text());
+    row!(row_buttons,
+      let btn_refresh = shared::fltk::button::wide::default().with_label("Refresh");
+      let btn_install = shared::fltk::button::wide::default().with_label("Install selected");
+      let btn_close = shared::fltk::button::wide::default().with_label("Close");
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  wind.end();
+  wind.make_resizable(true);
+  let mut browser = browser.clone();
+  browser.set_text_size(dimm::height_text());
+  browser.set_text_font(Font::Courier);
+  let frame_count = frame_count.clone();
+  // Filters
+  let mut menu_category = menu_category.clone();
+  menu_category.add_choice(LABEL_ALL);
+  lib::winetricks::CATEGORIES.iter().for_each(|e| { menu_category.add_choice(e); });
+  menu_category.set_label(LABEL_ALL);
+  menu_category.set_callback({
+    let state = state.clone();
+    let browser = browser.clone();
+    let frame_count = frame_count.clone();
+    move |e|
+    {
+      let Some(choice) = e.choice() else { return; };
+      e.set_label(&choice);
+      let mut state = state.lock().unwrap();
+      state.opt_category = (choice != LABEL_ALL).then_some(choice);
+      fill(&mut browser.clone(), &mut frame_count.clone(), &mut state);
+    }
+  });
+  let mut input_query = input_query.clone();
+  input_query.set_trigger(CallbackTrigger::Changed);
+  input_query.set_tooltip("Search the names and descriptions of the verbs");
+  input_query.set_callback({
+    let state = state.clone();
+    let browser = browser.clone();
+    let frame_count = frame_count.clone();
+    move |e|
+    {
+      let mut state = state.lock().unwrap();
+      state.query = e.value();
+      fill(&mut browser.clone(), &mut frame_count.clone(), &mut state);
+    }
+  });
+  btn_installed.clone().set_callback({
+    let state = state.clone();
+    let browser = browser.clone();
+    let frame_count = frame_count.clone();
+    move |e|
+    {
+      let mut state = state.lock().unwrap();
+      state.is_hide_installed = e.is_checked();
+      fill(&mut browser.clone(), &mut frame_count.clone(), &mut state);
+    }
+  });
+  // Keep the checked verbs of the browser
+  browser.set_callback({
+    let state = state.clone();
+    let mut frame_count = frame_count.clone();
+    move |e|
+    {
+      let mut state = state.lock().unwrap();
+      // Lines start at one
+      for (line, index) in state.visible.clone().into_iter().enumerate()
+      {
+        let name = state.verbs[index].name.clone();
+        match e.checked(line as i32 + 1)
+        {
+          true => { state.selected.insert(name); },
+          false => { state.selected.remove(&name); },
+        } // match
+      } // for
+      frame_count.set_label(&format!("{} of {} verbs, {} selected", state.visible.len(), state.verbs.len(), state.selected.len()));
+    }
+  });
+  // Runs the operation in the background and lists the verbs again
+  let f_background = {
+    let f_background = frame::common::background(wind.clone(), {
+      let browser = browser.clone();
+      let frame_count = frame_count.clone();
+      let state = state.clone();
+      move ||
+      {
+        let mut state = state.lock().unwrap();
+        state.installed = db::global::get_current_project()
+          .map(|e| lib::winetricks::installed(&e.path_dir_project))
+          .unwrap_or_default();
+        fill(&mut browser.clone(), &mut frame_count.clone(), &mut state);
+      }
+    });
+    let state = state.clone();
+    move |f_operation : Box<dyn FnOnce(&mut State) -> anyhow::Result<()> + Send>|
+    {
+      let state = state.clone();
+      f_background(Box::new(move || f_operation(&mut state.lock().unwrap())));
+    }
+  };
+  btn_refresh.clone().with_color(Color::Blue).set_callback({
+    let f_background = f_background.clone();
+    move |_|
+    {
+      f_background(Box::new(|state : &mut State|
+      {
+        log_status!("Listing the winetricks verbs...");
+        state.verbs = lib::winetricks::refresh()?;
+        log_status!("Found {} winetricks verbs", state.verbs.len());
+        Ok(())
+      }));
+    }
+  });
+  btn_install.clone().with_color(Color::Green).set_callback({
+    let f_background = f_background.clone();
+    move |_|
+    {
+      f_background(Box::new(|state : &mut State|
+      {
+        let mut verbs : Vec<String> = state.selected.iter().cloned().collect();
+        verbs.sort();
+        if verbs.is_empty() { log_status!("No verb was selected"); return Ok(()); } // if
+        if let Err(e) = lib::snapshot::create(&format!("Before winetricks {}", verbs.join(" ")))
+        {
+          log_status!("Could not create snapshot of the wine prefix: {}", e);
+        } // if
+        // Must install one at the time, winetricks exits if at least one verb fails
+        for verb in &verbs
+        {
+          log_status!("Installing '{}'", verb);
+          match gameimage::install::winetricks(vec!["-f".into(), "-q".into(), verb.clone()])
+          {
+            Ok(()) => { state.selected.remove(verb); },
+            Err(e) => log_status!("Could not install '{}': {}", verb, e),
+          } // match
+        } // for
+        Ok(())
+      }));
+    }
+  });
+  btn_close.clone().with_color(Color::Blue).set_callback({
+    let mut wind = wind.clone();
+    move |_| wind.hide()
+  });
+  wind.show();
+  // Read the cached catalog, winetricks is run when there is no cache
+  f_background(Box::new(|state : &mut State|
+  {
+    state.verbs = lib::winetricks::catalog()?;
+    Ok(())
+  }));
+} // fn catalog() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -23,12 +23,18 @@ pub fn desktop(name: &str, items: &str) -> anyhow::Result<()>
 } // fn: desktop }}}
 
 // pub fn icon() {{{
-pub fn icon(path : &std::path::PathBuf) -> anyhow::Result<()>
+// path_dir_hicolor is an optional directory with the hicolor layout created by
+// shared::image::hicolor, the backend installs it with the desktop integration
+pub fn icon(path : &std::path::PathBuf, path_dir_hicolor : Option<&std::path::PathBuf>) -> anyhow::Result<()>
 {
   let mut json_args = json!({});
   json_args["op"] = "desktop".into();
   json_args["desktop"]["op"] = "icon".into();
   json_args["desktop"]["path_file_icon"] = path.string().into();
+  if let Some(path_dir_hicolor) = path_dir_hicolor
+  {
+    json_args["desktop"]["path_dir_hicolor"] = path_dir_hicolor.string().into();
+  } // if
   // Wait for message & check return value
   match gameimage::gameimage_sync(vec![&json_args.to_string()])
   {
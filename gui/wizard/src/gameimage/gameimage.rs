@@ -1,7 +1,8 @@
 use std::
 {
   env,
-  sync::{Arc,Mutex,mpsc},
+  io::Write,
+  sync::{Arc,Mutex,mpsc,atomic::{AtomicU64,Ordering}},
 };
 
 use crate::lib;
@@ -23,8 +24,37 @@ pub fn dir_build() -> anyhow::Result<()>
   Ok(env::set_current_dir(std::path::PathBuf::from(env::var("GIMG_DIR")?))?)
 } // fn: dir_build }}}
 
+// fn job() {{{
+// Name to tag the log records of a backend call, e.g., 'install.winetricks#3'
+fn job(args : &Vec<&str>) -> String
+{
+  static COUNTER : AtomicU64 = AtomicU64::new(0);
+  let id = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+  let json : serde_json::Value = args.first()
+    .and_then(|e| serde_json::from_str(e).ok())
+    .unwrap_or_default();
+  let name = match json["op"].as_str()
+  {
+    Some(op) => match json[op]["sub_op"].as_str().or(json[op]["op"].as_str())
+    {
+      Some(sub_op) if sub_op != op => format!("{}.{}", op, sub_op),
+      _ => op.to_string(),
+    },
+    None => "backend".to_string(),
+  }; // match
+  format!("{}#{}", name, id)
+} // fn job() }}}
+
 // pub fn gameimage_async() {{{
 pub fn gameimage_async(args : Vec<&str>) -> anyhow::Result<(mpsc::Receiver<String>, mpsc::Receiver<i32>)>
+{
+  gameimage_async_tee(args, None)
+} // fn: gameimage_async }}}
+
+// fn gameimage_async_tee() {{{
+// Same as gameimage_async, the output of the backend is also written to opt_file_tee
+fn gameimage_async_tee(args : Vec<&str>, mut opt_file_tee : Option<std::fs::File>)
+  -> anyhow::Result<(mpsc::Receiver<String>, mpsc::Receiver<i32>)>
 {
   dir_build()?;
 
@@ -36,7 +66,8 @@ pub fn gameimage_async(args : Vec<&str>) -> anyhow::Result<(mpsc::Receiver<Strin
     .args(&args)
     .spawn()?;
 
-  log!("Dispatch command: {:?} : {:?}", path_binary_gameimage, args);
+  let job = job(&args);
+  log!("Dispatch command [{}]: {:?} : {:?}", job, path_binary_gameimage, args);
 
   // Create arc reader for stdout
   let arc_stdout = Arc::new(Mutex::new(handle.stdout.take()));
@@ -49,6 +80,8 @@ pub fn gameimage_async(args : Vec<&str>) -> anyhow::Result<(mpsc::Receiver<Strin
   let (tx_code, rx_code) = mpsc::channel();
   std::thread::spawn(move ||
   {
+    // Tag the backend output with the job
+    crate::lib::log::set_job(Some(job));
     let (tx_log, rx_log) = mpsc::channel();
     let f_callback = |tx : mpsc::Sender<String>, msg| { log_err!(tx.send(msg)); };
     let handle_stdout = std::thread::spawn(common::log_fd(stdout.unwrap(), tx_log.clone(), f_callback));
@@ -58,6 +91,10 @@ pub fn gameimage_async(args : Vec<&str>) -> anyhow::Result<(mpsc::Receiver<Strin
       for line in msg.split('\n')
       {
         log!("{}", line);
+        if let Some(file_tee) = opt_file_tee.as_mut()
+        {
+          log_err!(writeln!(file_tee, "{}", line));
+        } // if
       }
     } // while
 
@@ -101,7 +138,7 @@ pub fn gameimage_async(args : Vec<&str>) -> anyhow::Result<(mpsc::Receiver<Strin
   });
 
   Ok((rx_ipc, rx_code))
-} // fn: gameimage_async }}}
+} // fn: gameimage_async_tee }}}
 
 // pub fn gameimage_sync() {{{
 pub fn gameimage_sync(args : Vec<&str>) -> i32
@@ -115,6 +152,24 @@ pub fn gameimage_sync(args : Vec<&str>) -> i32
   rx_code.recv().unwrap_or(1)
 } // fn: gameimage_sync }}}
 
+// pub fn gameimage_sync_tee() {{{
+// Waits for the backend and writes its output to path_file_log
+pub fn gameimage_sync_tee(args : Vec<&str>, path_file_log : &std::path::PathBuf) -> i32
+{
+  let file_log = match std::fs::OpenOptions::new().append(true).create(true).open(path_file_log)
+  {
+    Ok(file) => file,
+    Err(e) => { log!("Could not open log file: {}", e); return 1; },
+  }; // match
+  let (_, rx_code) = match gameimage_async_tee(args, Some(file_log))
+  {
+    Ok((rx_ipc, rx_code)) => (rx_ipc, rx_code),
+    Err(e) => { log!("Could not start backend: {}", e); return 1; },
+  }; // if
+
+  rx_code.recv().unwrap_or(1)
+} // fn: gameimage_sync_tee }}}
+
 // pub fn gameimage_sync_ipc() {{{
 pub fn gameimage_sync_ipc<F>(args : Vec<&str>, mut f: F) -> i32
   where F: FnMut(mpsc::Receiver<String>) + Send + 'static
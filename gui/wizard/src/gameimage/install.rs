@@ -4,6 +4,7 @@ use serde_json::json;
 
 use anyhow::anyhow as ah;
 
+use crate::lib;
 use crate::gameimage::gameimage;
 
 // pub fn icon() {{{
@@ -86,6 +87,8 @@ pub fn gui() -> anyhow::Result<()>
 // pub fn winetricks() {{{
 pub fn winetricks(vec_path_files : Vec<String>) -> anyhow::Result<()>
 {
+  // Run with the distribution of the project
+  lib::distribution::activate_project()?;
   let mut json_args = json!({});
   json_args["op"] = "install".into();
   json_args["install"]["op"] = "install".into();
@@ -101,6 +104,8 @@ pub fn winetricks(vec_path_files : Vec<String>) -> anyhow::Result<()>
 // pub fn wine() {{{
 pub fn wine(vec_path_files : Vec<String>) -> anyhow::Result<()>
 {
+  // Run with the distribution of the project
+  lib::distribution::activate_project()?;
   let mut json_args = json!({});
   json_args["op"] = "install".into();
   json_args["install"]["op"] = "install".into();
@@ -3,7 +3,6 @@ use anyhow::anyhow as ah;
 use serde_json::json;
 
 use crate::log;
-use crate::common;
 use crate::gameimage;
 
 // search() {{{
@@ -1,19 +1,33 @@
-use anyhow::anyhow as ah;
-
 use serde_json::json;
 
 use crate::log;
-use crate::common;
+use crate::log_err;
+use crate::lib;
 use crate::gameimage::gameimage;
 
 // pub fn test() {{{
-pub fn test() -> anyhow::Result<()>
+// Runs the project and returns the exit code of the application, which is recorded in the project
+// history. Many games exit with a code other than zero, so it is not an error.
+pub fn test() -> i32
 {
   let mut json_args = json!({});
   json_args["op"] = "test".into();
-  match gameimage::gameimage_sync(vec![&json_args.to_string()])
+  let args = json_args.to_string();
+  // Record the run in the project history
+  let opt_recorder = match lib::history::begin()
+  {
+    Ok(recorder) => Some(recorder),
+    Err(e) => { log!("Could not record test run: {}", e); None },
+  }; // match
+  let code = match &opt_recorder
+  {
+    Some(recorder) => gameimage::gameimage_sync_tee(vec![&args], &recorder.path_file_log()),
+    None => gameimage::gameimage_sync(vec![&args]),
+  }; // match
+  if let Some(recorder) = opt_recorder
   {
-    0 => { log!("test returned successfully"); return Ok(()) },
-    ret => return Err(ah!("test returned with error: {}", ret)),
-  } // match
+    log_err!(recorder.finish(code).map(|_| ()));
+  } // if
+  log!("test returned with code {}", code);
+  code
 } // fn: test }}}
@@ -0,0 +1,140 @@
+use std::
+{
+  fs,
+  io::{Read, Seek, SeekFrom, Write},
+  path::PathBuf,
+  process::{Command, Stdio},
+  time::{Duration, Instant},
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+// Bytes read from the project to benchmark each level
+const SIZE_SAMPLE : u64 = 64 << 20;
+// Bytes read from a single file, so large files do not dominate the sample
+const SIZE_SAMPLE_FILE : u64 = 4 << 20;
+// Levels benchmarked by the advisor
+pub const LEVELS_BENCHMARK : [u32; 5] = [1, 3, 5, 7, 9];
+
+// pub struct Estimate {{{
+#[derive(Debug, Clone)]
+pub struct Estimate
+{
+  pub level : u32,
+  // Compressed size / original size of the sample
+  pub ratio : f64,
+  pub size  : u64,
+  pub time  : Duration,
+} // struct Estimate }}}
+
+// pub struct Sample {{{
+// Representative bytes of a project directory
+pub struct Sample
+{
+  pub data       : Vec<u8>,
+  pub size_total : u64,
+  pub count_files : usize,
+} // struct Sample }}}
+
+// fn args_zstd() {{{
+// The image compressor is approximated with zstd, these are the closest settings for each level
+fn args_zstd(level : u32) -> Vec<String>
+{
+  let level_zstd = [1, 1, 3, 5, 9, 12, 15, 19, 19, 22, 22][level.min(10) as usize];
+  let mut args = vec![format!("-{}", level_zstd), "-T0".into(), "-q".into(), "-c".into()];
+  if level_zstd > 19 { args.insert(0, "--ultra".into()); } // if
+  if level >= 8 { args.push("--long".into()); } // if
+  args
+} // fn args_zstd() }}}
+
+// pub fn size() {{{
+// Size of the files in a directory
+pub fn size(path_dir : &PathBuf) -> u64
+{
+  walkdir::WalkDir::new(path_dir)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter_map(|e| e.metadata().ok())
+    .filter(|e| e.is_file())
+    .map(|e| e.len())
+    .sum()
+} // fn size() }}}
+
+// pub fn sample() {{{
+// Reads chunks from the middle of files spread across the size distribution of the project
+pub fn sample(path_dir : &PathBuf) -> anyhow::Result<Sample>
+{
+  let mut files : Vec<(PathBuf, u64)> = walkdir::WalkDir::new(path_dir)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| e.file_type().is_file())
+    .filter_map(|e| Some((e.path().to_path_buf(), e.metadata().ok()?.len())))
+    .filter(|e| e.1 > 0)
+    .collect();
+  if files.is_empty() { return Err(ah!("No files in '{}'", path_dir.string())); } // if
+  let size_total = files.iter().map(|e| e.1).sum();
+  let count_files = files.len();
+  files.sort_by_key(|e| e.1);
+  // Take files at an even stride so small and large files are represented
+  let count_sample = (SIZE_SAMPLE / SIZE_SAMPLE_FILE) as usize * 4;
+  let stride = (files.len() / count_sample).max(1);
+  let mut data = Vec::<u8>::new();
+  for (path_file, size) in files.into_iter().step_by(stride)
+  {
+    if data.len() as u64 >= SIZE_SAMPLE { break; } // if
+    let size_read = size.min(SIZE_SAMPLE_FILE).min(SIZE_SAMPLE - data.len() as u64);
+    let mut file = match fs::File::open(&path_file) { Ok(file) => file, Err(_) => continue };
+    let _ = file.seek(SeekFrom::Start((size - size_read) / 2));
+    let _ = file.take(size_read).read_to_end(&mut data);
+  } // for
+  Ok(Sample{ data, size_total, count_files })
+} // fn sample() }}}
+
+// pub fn benchmark() {{{
+// Compresses the sample with the level and extrapolates to the full project
+pub fn benchmark(sample : &Sample, level : u32) -> anyhow::Result<Estimate>
+{
+  if sample.data.is_empty() { return Err(ah!("Empty sample")); } // if
+  // Level zero does not compress
+  if level == 0
+  {
+    return Ok(Estimate{ level, ratio: 1.0, size: sample.size_total, time: Duration::ZERO });
+  } // if
+  let path_zstd = which::which("zstd").map_err(|_| ah!("The 'zstd' command is required for estimates"))?;
+  let instant = Instant::now();
+  let mut child = Command::new(path_zstd)
+    .args(args_zstd(level))
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .spawn()?;
+  let mut stdin = child.stdin.take().ok_or(ah!("Could not open zstd input"))?;
+  let mut stdout = child.stdout.take().ok_or(ah!("Could not open zstd output"))?;
+  // Count the output while writing the input, the pipe buffers are small
+  let handle = std::thread::spawn(move ||
+  {
+    let mut buf = vec![0u8; 1 << 16];
+    let mut size = 0u64;
+    while let Ok(bytes_read) = stdout.read(&mut buf) && bytes_read > 0 { size += bytes_read as u64; }
+    size
+  });
+  stdin.write_all(&sample.data)?;
+  drop(stdin);
+  let size_compressed = handle.join().map_err(|_| ah!("Could not read zstd output"))?;
+  let status = child.wait()?;
+  if ! status.success() { return Err(ah!("zstd exited with {}", status)); } // if
+  let elapsed = instant.elapsed();
+  let ratio = size_compressed as f64 / sample.data.len() as f64;
+  let factor = sample.size_total as f64 / sample.data.len() as f64;
+  Ok(Estimate
+  {
+    level,
+    ratio,
+    size: (sample.size_total as f64 * ratio) as u64,
+    time: elapsed.mul_f64(factor),
+  })
+} // fn benchmark() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,137 @@
+use std::
+{
+  fs,
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+use crate::lib::pe::Pe;
+
+// Libraries next to the executable that are inspected too
+const COUNT_LIBRARIES_MAX : usize = 256;
+
+// pub struct Recommendation {{{
+#[derive(Debug, Clone)]
+pub struct Recommendation
+{
+  pub verb   : String,
+  pub reason : String,
+} // struct Recommendation }}}
+
+// fn verbs_import() {{{
+// Winetricks verbs that provide an imported library
+fn verbs_import(name : &str, is_64 : bool) -> Vec<&'static str>
+{
+  let f_xact = || if is_64 { vec!["xact", "xact_x64"] } else { vec!["xact"] };
+  match name.trim_end_matches(".dll")
+  {
+    "msvcp60" | "mfc42" | "mfc42u"                           => vec!["vcrun6", "mfc42"],
+    "msvcr71" | "msvcp71" | "mfc71" | "mfc71u"               => vec!["vcrun2003"],
+    "msvcr80" | "msvcp80" | "mfc80" | "mfc80u" | "vcomp"     => vec!["vcrun2005"],
+    "msvcr90" | "msvcp90" | "mfc90" | "mfc90u" | "vcomp90"   => vec!["vcrun2008"],
+    "msvcr100" | "msvcp100" | "mfc100" | "mfc100u" | "vcomp100" => vec!["vcrun2010"],
+    "msvcr110" | "msvcp110" | "mfc110" | "mfc110u" | "vcomp110" => vec!["vcrun2012"],
+    "msvcr120" | "msvcp120" | "mfc120" | "mfc120u" | "vcomp120" => vec!["vcrun2013"],
+    "vcruntime140" | "vcruntime140_1" | "msvcp140" | "msvcp140_1" | "msvcp140_2"
+      | "mfc140" | "mfc140u" | "vcomp140" | "concrt140"      => vec!["vcrun2022"],
+    name if name.starts_with("api-ms-win-crt-") || name == "ucrtbase" => vec!["vcrun2022"],
+    name if name.starts_with("d3dx9_")                       => vec!["d3dx9"],
+    name if name.starts_with("d3dx10")                       => vec!["d3dx10"],
+    name if name.starts_with("d3dx11")                       => vec!["d3dx11_43"],
+    "d3dcompiler_43"                                         => vec!["d3dcompiler_43"],
+    "d3dcompiler_46" | "d3dcompiler_47"                      => vec!["d3dcompiler_47"],
+    name if name.starts_with("xinput1_") || name == "xinput9_1_0" => vec!["xinput"],
+    name if name.starts_with("xactengine") || name.starts_with("x3daudio") => f_xact(),
+    "xaudio2_0" | "xaudio2_1" | "xaudio2_2" | "xaudio2_3" | "xaudio2_4" | "xaudio2_5"
+      | "xaudio2_6" | "xaudio2_7"                            => f_xact(),
+    "binkw32" | "binkw64"                                    => vec!["binkw32"],
+    "msvbvm60"                                               => vec!["vb6run"],
+    "msvbvm50"                                               => vec!["vb5run"],
+    "vb40032"                                                => vec!["vb4run"],
+    "dx8vb"                                                  => vec!["dx8vb"],
+    "openal32" | "wrap_oal"                                  => vec!["openal"],
+    "physxloader" | "physxcore" | "physxcooking"             => vec!["physx"],
+    "gdiplus"                                                => vec!["gdiplus"],
+    "msxml3"                                                 => vec!["msxml3"],
+    "msxml4"                                                 => vec!["msxml4"],
+    "msxml6"                                                 => vec!["msxml6"],
+    _                                                        => vec![],
+  } // match
+} // fn verbs_import() }}}
+
+// fn verbs_clr() {{{
+// Winetricks verbs of a .NET runtime version, e.g., 'v4.0.30319'
+fn verbs_clr(version : &str) -> Vec<&'static str>
+{
+  match version.trim_start_matches('v').split('.').next().unwrap_or_default()
+  {
+    "1" if version.starts_with("v1.0") => vec!["dotnet11"],
+    "1" => vec!["dotnet11", "dotnet11sp1"],
+    "2" => vec!["dotnet35sp1"],
+    "4" => vec!["dotnet48"],
+    _   => vec![],
+  } // match
+} // fn verbs_clr() }}}
+
+// fn push() {{{
+fn push(recommendations : &mut Vec<Recommendation>, verb : &str, reason : String)
+{
+  if ! recommendations.iter().any(|e| e.verb == verb)
+  {
+    recommendations.push(Recommendation{ verb: verb.to_string(), reason });
+  } // if
+} // fn push() }}}
+
+// fn inspect() {{{
+// Adds the verbs for the dependencies of a binary, libraries shipped in the directory are provided
+fn inspect(recommendations : &mut Vec<Recommendation>, path_file : &PathBuf, pe : &Pe, shipped : &Vec<String>)
+{
+  let name_file = path_file.file_name_string();
+  for import in pe.imports.iter().filter(|e| ! shipped.contains(e))
+  {
+    for verb in verbs_import(import, pe.is_64())
+    {
+      push(recommendations, verb, format!("{} imports {}", name_file, import));
+    } // for
+  } // for
+  if let Some(version) = &pe.clr_version
+  {
+    for verb in verbs_clr(version)
+    {
+      push(recommendations, verb, format!("{} is a .NET {} assembly", name_file, version));
+    } // for
+  } // if
+  else if pe.imports.iter().any(|e| e == "mscoree.dll")
+  {
+    push(recommendations, "dotnet48", format!("{} loads the .NET runtime", name_file));
+  } // else if
+} // fn inspect() }}}
+
+// pub fn recommend() {{{
+// Reads the executable and the libraries in its directory to find what they link against
+pub fn recommend(path_file_exe : &PathBuf) -> anyhow::Result<Vec<Recommendation>>
+{
+  let pe_exe = Pe::open(path_file_exe)?;
+  let path_dir = path_file_exe.parent().ok_or(ah!("Executable has no parent directory"))?;
+  // Libraries in the directory of the executable
+  let libraries : Vec<(PathBuf, Pe)> = fs::read_dir(path_dir)?
+    .filter_map(|e| e.ok())
+    .map(|e| e.path())
+    .filter(|e| e.extension().is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case("dll")))
+    .take(COUNT_LIBRARIES_MAX)
+    .filter_map(|e| Some((e.clone(), Pe::open(&e).ok()?)))
+    .collect();
+  let shipped : Vec<String> = libraries.iter().map(|e| e.0.file_name_string().to_lowercase()).collect();
+  let mut recommendations = vec![];
+  inspect(&mut recommendations, path_file_exe, &pe_exe, &shipped);
+  for (path_file, pe) in &libraries
+  {
+    inspect(&mut recommendations, path_file, pe, &shipped);
+  } // for
+  Ok(recommendations)
+} // fn recommend() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
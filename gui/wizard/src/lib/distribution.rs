@@ -0,0 +1,251 @@
+use std::
+{
+  fs,
+  path::PathBuf,
+  sync::mpsc,
+  time::Duration,
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+use crate::db;
+use crate::lib;
+use crate::common;
+use crate::gameimage;
+
+// Key of the project database with the distribution chosen for packaging
+const KEY_DIST : &str = "dist";
+
+// pub struct Distribution {{{
+// A wine distribution of the fetch list. The active one is in the cache, where the backend uses and
+// packages it, the other fetched ones are kept in the build directory.
+#[derive(Debug, Clone)]
+pub struct Distribution
+{
+  pub name       : String,
+  pub is_fetched : bool,
+  pub is_active  : bool,
+  // Chosen for packaging the current project
+  pub is_project : bool,
+} // struct Distribution }}}
+
+impl Distribution
+{
+
+// pub fn summary() {{{
+pub fn summary(&self) -> String
+{
+  let mut tags = vec![];
+  if self.is_project { tags.push("project"); } // if
+  if self.is_active { tags.push("active"); } // if
+  else if self.is_fetched { tags.push("fetched"); } // else if
+  match tags.is_empty()
+  {
+    true => self.name.clone(),
+    false => format!("{} ({})", self.name, tags.join(", ")),
+  } // match
+} // fn summary() }}}
+
+} // impl Distribution
+
+// fn path_file_layer() {{{
+// Layer used by the backend
+fn path_file_layer() -> anyhow::Result<PathBuf>
+{
+  Ok(db::global::read()?.path_dir_cache.join("wine.layer"))
+} // fn path_file_layer() }}}
+
+// fn path_file_stored() {{{
+// Layer of a distribution that is not active, outside of the cache so it is not mounted
+fn path_file_stored(name : &str) -> anyhow::Result<PathBuf>
+{
+  Ok(db::global::read()?.path_dir_build.join("distribution").join("wine").join(format!("{}.layer", name)))
+} // fn path_file_stored() }}}
+
+// fn path_file_db() {{{
+fn path_file_db(path_dir_project : &PathBuf) -> PathBuf
+{
+  path_dir_project.join("gameimage.wine.json")
+} // fn path_file_db() }}}
+
+// fn rename() {{{
+// Moves a layer, copies it when the directories are in different file systems
+fn rename(path_src : &PathBuf, path_dst : &PathBuf) -> anyhow::Result<()>
+{
+  fs::create_dir_all(path_dst.parent().ok_or(ah!("Invalid path '{}'", path_dst.string()))?)?;
+  if fs::rename(path_src, path_dst).is_err()
+  {
+    fs::copy(path_src, path_dst)?;
+    fs::remove_file(path_src)?;
+  } // if
+  Ok(())
+} // fn rename() }}}
+
+// pub fn is_fetched() {{{
+pub fn is_fetched(name : &str) -> bool
+{
+  let is_active = db::global::read().is_ok_and(|e| e.dist_wine == name)
+    && path_file_layer().is_ok_and(|e| e.exists());
+  is_active || path_file_stored(name).is_ok_and(|e| e.exists())
+} // fn is_fetched() }}}
+
+// pub fn stash() {{{
+// Keeps the layer of the active distribution in the build directory, so fetching another one does
+// not overwrite it
+pub fn stash() -> anyhow::Result<()>
+{
+  let path_file_layer = path_file_layer()?;
+  if ! path_file_layer.exists() { return Ok(()); } // if
+  rename(&path_file_layer, &path_file_stored(&db::global::read()?.dist_wine)?)
+} // fn stash() }}}
+
+// pub fn activate() {{{
+// Makes a fetched distribution the one used by the backend for tests and packaging
+pub fn activate(name : &str) -> anyhow::Result<()>
+{
+  if db::global::read()?.dist_wine == name && path_file_layer()?.exists() { return Ok(()); } // if
+  let path_file_stored = path_file_stored(name)?;
+  if ! path_file_stored.exists() { return Err(ah!("Wine distribution '{}' was not fetched", name)); } // if
+  stash()?;
+  rename(&path_file_stored, &path_file_layer()?)?;
+  db::global::update(|mut db| { db.dist_wine = name.to_string(); db })?;
+  Ok(())
+} // fn activate() }}}
+
+// pub fn fetch() {{{
+// Fetches a distribution and makes it active, the previous one is kept
+pub fn fetch<F>(name : &str, f : F) -> anyhow::Result<()>
+  where F: FnMut(mpsc::Receiver<String>) + Send + 'static
+{
+  if is_fetched(name) { return activate(name); } // if
+  stash()?;
+  db::global::update(|mut db| { db.dist_wine = name.to_string(); db })?;
+  gameimage::fetch::fetch(common::Platform::Wine, f)?;
+  Ok(())
+} // fn fetch() }}}
+
+// pub fn project() {{{
+// Distribution chosen for packaging a project
+pub fn project(path_dir_project : &PathBuf) -> Option<String>
+{
+  shared::db::kv::read(&path_file_db(path_dir_project)).ok()?.get(KEY_DIST).cloned()
+} // fn project() }}}
+
+// pub fn set_project() {{{
+// Chooses the distribution to package the current project with and makes it active
+pub fn set_project(name : &str) -> anyhow::Result<()>
+{
+  activate(name)?;
+  let path_dir_project = db::global::get_current_project()?.path_dir_project;
+  shared::db::kv::write(&path_file_db(&path_dir_project), &KEY_DIST.to_string(), &name.to_string())
+} // fn set_project() }}}
+
+// pub fn activate_project() {{{
+// Makes the distribution of the current project active, if it has one
+pub fn activate_project() -> anyhow::Result<()>
+{
+  match project(&db::global::get_current_project()?.path_dir_project)
+  {
+    Some(name) => activate(&name),
+    None => Ok(()),
+  } // match
+} // fn activate_project() }}}
+
+// pub fn activate_projects() {{{
+// Makes the distribution of the wine projects to package active, the image has a single wine layer
+pub fn activate_projects(projects : &[String]) -> anyhow::Result<()>
+{
+  let global = db::global::read()?;
+  let mut names : Vec<String> = global.projects.iter()
+    .filter(|(name, details)| projects.contains(name) && details.platform == "wine")
+    .filter_map(|(_, details)| project(&details.path_dir_project))
+    .collect();
+  names.sort();
+  names.dedup();
+  match names.as_slice()
+  {
+    [] => Ok(()),
+    [name] => activate(name),
+    _ => Err(ah!("The projects are configured with different wine distributions: {}", names.join(", "))),
+  } // match
+} // fn activate_projects() }}}
+
+// pub fn list() {{{
+// Distributions of the fetch list, sorted by name
+pub fn list() -> anyhow::Result<Vec<Distribution>>
+{
+  let global = db::global::read()?;
+  let is_layer = path_file_layer()?.exists();
+  let opt_project = project(&db::global::get_current_project()?.path_dir_project);
+  let mut names : Vec<String> = db::fetch::read()?.wine.layer.into_keys().collect();
+  names.sort();
+  Ok(names.into_iter().map(|name|
+  {
+    let is_active = is_layer && global.dist_wine == name;
+    Distribution
+    {
+      is_fetched: is_active || path_file_stored(&name).is_ok_and(|e| e.exists()),
+      is_project: opt_project.as_ref() == Some(&name),
+      is_active,
+      name,
+    }
+  }).collect())
+} // fn list() }}}
+
+// pub fn compare() {{{
+// Table with a column for each distribution that was fetched or tested, built from the latest
+// test runs with it
+pub fn compare(distributions : &[Distribution], runs : &[lib::history::Run]) -> String
+{
+  let f_runs = |name : &str| -> Vec<&lib::history::Run>
+  {
+    runs.iter().filter(|e| e.dist_wine.as_deref() == Some(name)).collect()
+  };
+  let f_yes = |value : bool| if value { "yes".to_string() } else { "no".to_string() };
+  let mut columns : Vec<Vec<String>> = vec![
+    ["", "Fetched", "Packaged", "Runs", "Successful runs", "Last run", "Last exit code", "Last duration", "Last executable"]
+      .iter()
+      .map(|e| e.to_string())
+      .collect()
+  ];
+  for distribution in distributions
+  {
+    let runs = f_runs(&distribution.name);
+    if ! distribution.is_fetched && runs.is_empty() { continue; } // if
+    // Runs are newest first
+    let opt_last = runs.first();
+    columns.push(vec![
+      distribution.name.clone(),
+      f_yes(distribution.is_fetched),
+      f_yes(distribution.is_project),
+      runs.len().to_string(),
+      runs.iter().filter(|e| e.code == 0).count().to_string(),
+      opt_last.map(|e| e.time_str()).unwrap_or("-".into()),
+      opt_last.map(|e| e.code.to_string()).unwrap_or("-".into()),
+      opt_last.map(|e| lib::format::format_time(Duration::from_secs_f64(e.secs_duration))).unwrap_or("-".into()),
+      opt_last.and_then(|e| e.path_file_rom.as_ref())
+        .and_then(|e| e.file_name())
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or("-".into()),
+    ]);
+  } // for
+  if columns.len() == 1 { return "No wine distribution was fetched or tested yet\n".to_string(); } // if
+  let widths : Vec<usize> = columns.iter()
+    .map(|e| e.iter().map(|e| e.chars().count()).max().unwrap_or(0) + 2)
+    .collect();
+  let mut text = String::new();
+  for row in 0..columns[0].len()
+  {
+    let line : String = columns.iter()
+      .zip(widths.iter())
+      .map(|(column, width)| format!("{:<width$}", column[row], width = width))
+      .collect();
+    text.push_str(line.trim_end());
+    text.push('\n');
+  } // for
+  text
+} // fn compare() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
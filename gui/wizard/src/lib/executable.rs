@@ -0,0 +1,179 @@
+use std::
+{
+  fs::File,
+  io::{Read, Seek, SeekFrom},
+  path::PathBuf,
+};
+
+use shared::std::PathBufExt;
+
+use crate::lib::pe::{Pe, Subsystem};
+
+// Bytes read from the start and the end of the file to search for installer markers
+const SIZE_SCAN_MARKERS : u64 = 512 << 10;
+// Strings embedded by installer builders and self extracting archives
+const MARKERS_INSTALLER : [(&str, &str); 8] =
+[
+  ("Inno Setup", "Inno Setup installer"),
+  ("Nullsoft Install System", "NSIS installer"),
+  ("NullsoftInst", "NSIS installer"),
+  ("InstallShield", "InstallShield installer"),
+  ("WiseMain", "Wise installer"),
+  ("7zS.sfx", "7-Zip self extracting archive"),
+  (";!@Install@!UTF-8!", "7-Zip self extracting archive"),
+  ("Setup Factory", "Setup Factory installer"),
+];
+// Parts of file names of programs that are not the game
+const NAMES_AUXILIARY : [(&str, i32); 14] =
+[
+  ("unins", -100),
+  ("uninst", -100),
+  ("vcredist", -100),
+  ("vc_redist", -100),
+  ("dxsetup", -100),
+  ("dxwebsetup", -100),
+  ("dotnetfx", -100),
+  ("ndp4", -100),
+  ("oalinst", -100),
+  ("physx", -80),
+  ("setup", -60),
+  ("install", -60),
+  ("crash", -40),
+  ("report", -30),
+];
+
+// pub struct Candidate {{{
+// An executable of the project and how likely it is the game
+#[derive(Debug, Clone)]
+pub struct Candidate
+{
+  // Relative to the project directory
+  pub path_file : PathBuf,
+  pub size      : u64,
+  pub opt_pe    : Option<Pe>,
+  pub error     : Option<String>,
+  // Descriptions of the installer markers found
+  pub markers   : Vec<String>,
+  pub score     : i32,
+} // struct Candidate }}}
+
+impl Candidate
+{
+
+// pub fn details() {{{
+pub fn details(&self) -> String
+{
+  let mut text = String::new();
+  text.push_str(&format!("File: {}\n", self.path_file.file_name_string()));
+  text.push_str(&format!("Size: {:.2} MiB\n", self.size as f64 / (1u64 << 20) as f64));
+  match &self.opt_pe
+  {
+    Some(pe) =>
+    {
+      text.push_str(&format!("Architecture: {}\n", pe.machine.as_str()));
+      text.push_str(&format!("Subsystem: {}\n", pe.subsystem.as_str()));
+      if pe.is_dll { text.push_str("Type: library\n"); } // if
+      if let Some(version) = &pe.clr_version { text.push_str(&format!(".NET runtime: {}\n", version)); } // if
+      for key in ["ProductName", "FileDescription", "FileVersion", "ProductVersion", "CompanyName", "OriginalFilename"]
+      {
+        text.push_str(&format!("{}: {}\n", key, pe.version.get(key).map(|e| e.as_str()).unwrap_or("-")));
+      } // for
+    },
+    None => text.push_str(&format!("Not a valid executable: {}\n", self.error.clone().unwrap_or_default())),
+  } // match
+  if self.markers.is_empty() { text.push_str("Installer markers: none\n"); } // if
+  else { text.push_str(&format!("Installer markers: {}\n", self.markers.join(", "))); } // else
+  text.push_str(&format!("Score: {}\n", self.score));
+  text
+} // fn details() }}}
+
+} // impl Candidate
+
+// fn markers() {{{
+// Searches the start and the end of the file, installers keep their payload after the image
+fn markers(path_file : &PathBuf, size : u64) -> Vec<String>
+{
+  let mut file = match File::open(path_file) { Ok(file) => file, Err(_) => return vec![] };
+  let mut data = vec![];
+  let _ = (&mut file).take(SIZE_SCAN_MARKERS).read_to_end(&mut data);
+  if size > SIZE_SCAN_MARKERS && file.seek(SeekFrom::Start(size.saturating_sub(SIZE_SCAN_MARKERS).max(SIZE_SCAN_MARKERS))).is_ok()
+  {
+    let _ = file.take(SIZE_SCAN_MARKERS).read_to_end(&mut data);
+  } // if
+  let mut markers : Vec<String> = vec![];
+  for (marker, description) in MARKERS_INSTALLER
+  {
+    if data.windows(marker.len()).any(|e| e == marker.as_bytes()) && ! markers.contains(&description.to_string())
+    {
+      markers.push(description.to_string());
+    } // if
+  } // for
+  markers
+} // fn markers() }}}
+
+// fn score() {{{
+fn score(candidate : &Candidate) -> i32
+{
+  let name = candidate.path_file.file_name_string().to_lowercase();
+  let mut score = NAMES_AUXILIARY.iter()
+    .filter(|(part, _)| name.contains(part))
+    .map(|(_, score)| score)
+    .min()
+    .copied()
+    .unwrap_or(0);
+  let Some(pe) = &candidate.opt_pe else { return score - 200; };
+  if pe.is_dll { score -= 200; } // if
+  score += match pe.subsystem { Subsystem::Gui => 20, Subsystem::Console => -20, Subsystem::Other(_) => -10 };
+  if pe.is_64() { score += 5; } // if
+  if ! candidate.markers.is_empty() { score -= 60; } // if
+  // Descriptions of helper programs
+  let description = format!("{} {}"
+    , pe.version.get("FileDescription").cloned().unwrap_or_default()
+    , pe.version.get("ProductName").cloned().unwrap_or_default()
+  ).to_lowercase();
+  if ["setup", "installer", "uninstall", "redistributable", "launcher", "crash"].iter().any(|e| description.contains(e))
+  {
+    score -= 40;
+  } // if
+  if pe.version.contains_key("ProductName") { score += 5; } // if
+  // Games are larger than their tools, a bonus per doubling of size above 1 MiB
+  score += 4 * (candidate.size >> 20).max(1).ilog2() as i32;
+  // Executables deep in the tree are often tools
+  score -= 2 * candidate.path_file.components().count() as i32;
+  score
+} // fn score() }}}
+
+// pub fn inspect() {{{
+// Reads an executable of the project
+pub fn inspect(path_dir_project : &PathBuf, path_file : &PathBuf) -> Candidate
+{
+  let path_file_abs = path_dir_project.join(path_file);
+  let size = std::fs::metadata(&path_file_abs).map(|e| e.len()).unwrap_or(0);
+  let (opt_pe, error) = match Pe::open(&path_file_abs)
+  {
+    Ok(pe) => (Some(pe), None),
+    Err(e) => (None, Some(e.to_string())),
+  }; // match
+  let mut candidate = Candidate
+  {
+    path_file: path_file.clone(),
+    size,
+    opt_pe,
+    error,
+    markers: markers(&path_file_abs, size),
+    score: 0,
+  };
+  candidate.score = score(&candidate);
+  candidate
+} // fn inspect() }}}
+
+// pub fn rank() {{{
+// Inspects the executables, the likely game comes first
+pub fn rank(path_dir_project : &PathBuf, paths_file : Vec<PathBuf>) -> Vec<Candidate>
+{
+  let mut candidates : Vec<Candidate> = paths_file.iter().map(|e| inspect(path_dir_project, e)).collect();
+  candidates.sort_by(|a, b| b.score.cmp(&a.score));
+  candidates
+} // fn rank() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
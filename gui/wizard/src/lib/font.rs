@@ -0,0 +1,281 @@
+use std::
+{
+  collections::BTreeMap,
+  fs,
+  io::{Read,Seek,SeekFrom},
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+use shared::wine::registry::{Registry,Data};
+
+use crate::log;
+
+// Keys of 'system.reg' with the registered fonts and with the substitutes of font names
+const KEY_FONTS : &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts";
+const KEY_SUBSTITUTES : &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\FontSubstitutes";
+
+// Extensions of TrueType and OpenType fonts and collections
+pub const EXTENSIONS : [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+// Largest 'name' table that is read, protects against corrupt files
+const SIZE_NAME_MAX : usize = 1 << 20;
+
+// pub struct Font {{{
+// A font file in the 'windows/Fonts' directory of the prefix
+#[derive(Debug, Clone)]
+pub struct Font
+{
+  pub file_name     : String,
+  pub family        : String,
+  pub style         : String,
+  pub full_name     : String,
+  // Outlines are CFF instead of TrueType
+  pub is_opentype   : bool,
+  pub is_registered : bool,
+} // struct Font }}}
+
+impl Font
+{
+
+// pub fn registry_name() {{{
+// Name of the value in the fonts key, e.g., 'Noto Sans CJK JP Regular (OpenType)'
+pub fn registry_name(&self) -> String
+{
+  format!("{} ({})", self.full_name, if self.is_opentype { "OpenType" } else { "TrueType" })
+} // fn registry_name() }}}
+
+} // impl Font
+
+// pub struct Family {{{
+#[derive(Debug, Clone)]
+pub struct Family
+{
+  pub name  : String,
+  pub fonts : Vec<Font>,
+} // struct Family }}}
+
+// fn read_u16() {{{
+fn read_u16(data : &[u8], offset : usize) -> Option<u16>
+{
+  Some(u16::from_be_bytes(data.get(offset..offset+2)?.try_into().ok()?))
+} // fn read_u16() }}}
+
+// fn read_u32() {{{
+fn read_u32(data : &[u8], offset : usize) -> Option<u32>
+{
+  Some(u32::from_be_bytes(data.get(offset..offset+4)?.try_into().ok()?))
+} // fn read_u32() }}}
+
+// fn read_at() {{{
+// Reads a range of the file, fonts are read by table instead of whole
+fn read_at(file : &mut fs::File, offset : u64, length : usize) -> Option<Vec<u8>>
+{
+  let mut data = vec![0; length];
+  file.seek(SeekFrom::Start(offset)).ok()?;
+  file.read_exact(&mut data).ok()?;
+  Some(data)
+} // fn read_at() }}}
+
+// fn decode_utf16() {{{
+fn decode_utf16(data : &[u8]) -> String
+{
+  let units : Vec<u16> = data.chunks_exact(2).map(|e| u16::from_be_bytes([e[0], e[1]])).collect();
+  String::from_utf16_lossy(&units)
+} // fn decode_utf16() }}}
+
+// fn parse() {{{
+// Family, style, full name and whether the outlines are CFF, from the 'name' table of the first
+// font of the file. Only the headers and the 'name' table are read, fonts of CJK languages are
+// dozens of megabytes. Windows names in english are preferred over the others.
+fn parse(file : &mut fs::File) -> Option<(String, String, String, bool)>
+{
+  // Collections have a header with the offsets of the fonts
+  let offset_font = match read_at(file, 0, 4)?.as_slice()
+  {
+    b"ttcf" => read_u32(&read_at(file, 12, 4)?, 0)? as u64,
+    _ => 0,
+  }; // match
+  let header = read_at(file, offset_font, 12)?;
+  let is_opentype = header.get(0..4)? == b"OTTO";
+  let count_tables = read_u16(&header, 4)? as usize;
+  let directory = read_at(file, offset_font + 12, count_tables * 16)?;
+  let offset_record_name = (0..count_tables)
+    .map(|i| i * 16)
+    .find(|e| directory.get(*e..*e+4) == Some(b"name".as_slice()))?;
+  let offset_name = read_u32(&directory, offset_record_name + 8)? as u64;
+  let length_name = read_u32(&directory, offset_record_name + 12)? as usize;
+  if length_name > SIZE_NAME_MAX { return None; } // if
+  let data = read_at(file, offset_name, length_name)?;
+  let count_records = read_u16(&data, 2)? as usize;
+  let offset_strings = read_u16(&data, 4)? as usize;
+  // Names by id with the priority of their platform and language
+  let mut names : BTreeMap<u16, (u8, String)> = BTreeMap::new();
+  for offset_record in (0..count_records).map(|i| 6 + i * 12)
+  {
+    let platform = read_u16(&data, offset_record)?;
+    let encoding = read_u16(&data, offset_record + 2)?;
+    let language = read_u16(&data, offset_record + 4)?;
+    let id = read_u16(&data, offset_record + 6)?;
+    if ! [1, 2, 4, 16, 17].contains(&id) { continue; } // if
+    let length = read_u16(&data, offset_record + 8)? as usize;
+    let offset = offset_strings + read_u16(&data, offset_record + 10)? as usize;
+    let Some(bytes) = data.get(offset..offset+length) else { continue; };
+    let (priority, text) = match (platform, encoding)
+    {
+      (3, 1) | (3, 10) => (if language == 0x409 { 3 } else { 2 }, decode_utf16(bytes)),
+      (0, _) => (1, decode_utf16(bytes)),
+      (1, 0) => (0, bytes.iter().map(|e| *e as char).collect()),
+      _ => continue,
+    }; // match
+    if text.trim().is_empty() { continue; } // if
+    if names.get(&id).is_none_or(|e| e.0 < priority) { names.insert(id, (priority, text.trim().to_string())); } // if
+  } // for
+  // Typographic names group the styles that do not fit the four legacy ones
+  let f_name = |ids : [u16; 2]| ids.iter().find_map(|e| names.get(e).map(|e| e.1.clone()));
+  let family = f_name([16, 1])?;
+  let style = f_name([17, 2]).unwrap_or("Regular".to_string());
+  let full_name = names.get(&4).map(|e| e.1.clone()).unwrap_or(format!("{} {}", family, style));
+  Some((family, style, full_name, is_opentype))
+} // fn parse() }}}
+
+// fn path_dir_fonts() {{{
+// The directory is created by wine with this case
+fn path_dir_fonts(path_dir_project : &PathBuf) -> PathBuf
+{
+  path_dir_project.join("wine").join("drive_c").join("windows").join("Fonts")
+} // fn path_dir_fonts() }}}
+
+// fn path_file_system() {{{
+fn path_file_system(path_dir_project : &PathBuf) -> anyhow::Result<PathBuf>
+{
+  let path_file_reg = path_dir_project.join("wine").join("system.reg");
+  if ! path_file_reg.exists() { return Err(ah!("Registry of the wine prefix does not exist")); } // if
+  Ok(path_file_reg)
+} // fn path_file_system() }}}
+
+// pub fn is_font() {{{
+pub fn is_font(path_file : &PathBuf) -> bool
+{
+  path_file.extension().is_some_and(|e| EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+} // fn is_font() }}}
+
+// pub fn inspect() {{{
+pub fn inspect(path_file : &PathBuf) -> anyhow::Result<Font>
+{
+  let file_name = path_file.file_name().ok_or(ah!("Invalid font path '{}'", path_file.string()))?
+    .to_string_lossy()
+    .to_string();
+  let (family, style, full_name, is_opentype) = parse(&mut fs::File::open(path_file)?)
+    .ok_or(ah!("Could not read the names of font '{}'", file_name))?;
+  Ok(Font{ file_name, family, style, full_name, is_opentype, is_registered: false })
+} // fn inspect() }}}
+
+// pub fn list() {{{
+// Fonts of the prefix grouped by family, files that could not be read are listed by their name
+pub fn list(path_dir_project : &PathBuf) -> anyhow::Result<Vec<Family>>
+{
+  let registered : Vec<String> = Registry::open(&path_file_system(path_dir_project)?)?
+    .key(KEY_FONTS)
+    .map(|key| key.values.iter().map(|(_, v)| Data::decode(v).text().to_lowercase()).collect())
+    .unwrap_or_default();
+  let mut families : BTreeMap<String, Family> = BTreeMap::new();
+  let Ok(entries) = fs::read_dir(path_dir_fonts(path_dir_project)) else { return Ok(vec![]); };
+  for path_file in entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(is_font)
+  {
+    let mut font = match inspect(&path_file)
+    {
+      Ok(font) => font,
+      Err(e) =>
+      {
+        log!("{}", e);
+        let name = path_file.file_stem().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        Font{ file_name: path_file.file_name_string(), family: name.clone(), style: String::new(), full_name: name
+          , is_opentype: false, is_registered: false }
+      },
+    }; // match
+    // Values are the file name or the full path for fonts outside of the directory
+    let file_name = font.file_name.to_lowercase();
+    font.is_registered = registered.iter().any(|e| e.rsplit('\\').next() == Some(file_name.as_str()));
+    families.entry(font.family.to_lowercase())
+      .or_insert(Family{ name: font.family.clone(), fonts: vec![] })
+      .fonts
+      .push(font);
+  } // for
+  let mut families : Vec<Family> = families.into_values().collect();
+  families.iter_mut().for_each(|e| e.fonts.sort_by(|a, b| a.style.cmp(&b.style)));
+  Ok(families)
+} // fn list() }}}
+
+// pub fn register() {{{
+// Registers fonts of the fonts directory, wine must not be running
+pub fn register(path_dir_project : &PathBuf, fonts : &[Font]) -> anyhow::Result<()>
+{
+  let path_file_reg = path_file_system(path_dir_project)?;
+  let mut registry = Registry::open(&path_file_reg)?;
+  let key = registry.key_mut(KEY_FONTS);
+  fonts.iter().for_each(|e| key.set(&e.registry_name(), &Data::String(e.file_name.clone()).encode()));
+  registry.save(&path_file_reg)
+} // fn register() }}}
+
+// pub fn add() {{{
+// Copies a font into the prefix and registers it
+pub fn add(path_dir_project : &PathBuf, path_file : &PathBuf) -> anyhow::Result<Font>
+{
+  if ! is_font(path_file) { return Err(ah!("'{}' is not a TrueType or OpenType font", path_file.string())); } // if
+  let mut font = inspect(path_file)?;
+  let path_dir_fonts = path_dir_fonts(path_dir_project);
+  fs::create_dir_all(&path_dir_fonts)?;
+  fs::copy(path_file, path_dir_fonts.join(&font.file_name))?;
+  register(path_dir_project, std::slice::from_ref(&font))?;
+  font.is_registered = true;
+  Ok(font)
+} // fn add() }}}
+
+// pub fn remove() {{{
+// Removes a font from the prefix and the values that register it
+pub fn remove(path_dir_project : &PathBuf, font : &Font) -> anyhow::Result<()>
+{
+  let path_file_reg = path_file_system(path_dir_project)?;
+  let mut registry = Registry::open(&path_file_reg)?;
+  let file_name = font.file_name.to_lowercase();
+  registry.key_mut(KEY_FONTS).values.retain(|(_, v)|
+  {
+    Data::decode(v).text().to_lowercase().rsplit('\\').next() != Some(file_name.as_str())
+  });
+  registry.save(&path_file_reg)?;
+  fs::remove_file(path_dir_fonts(path_dir_project).join(&font.file_name))?;
+  Ok(())
+} // fn remove() }}}
+
+// pub fn substitutes() {{{
+// Font names that are replaced by other fonts, e.g., 'MS Gothic' by 'Noto Sans CJK JP'
+pub fn substitutes(path_dir_project : &PathBuf) -> anyhow::Result<BTreeMap<String, String>>
+{
+  Ok(Registry::open(&path_file_system(path_dir_project)?)?
+    .key(KEY_SUBSTITUTES)
+    .map(|key| key.values.iter()
+      .filter(|(k, _)| ! k.is_empty())
+      .map(|(k, v)| (k.clone(), Data::decode(v).text()))
+      .collect())
+    .unwrap_or_default())
+} // fn substitutes() }}}
+
+// pub fn set_substitute() {{{
+// Sets or erases the substitute of a font name, wine must not be running
+pub fn set_substitute(path_dir_project : &PathBuf, name : &str, opt_replacement : Option<&str>) -> anyhow::Result<()>
+{
+  let path_file_reg = path_file_system(path_dir_project)?;
+  let mut registry = Registry::open(&path_file_reg)?;
+  let key = registry.key_mut(KEY_SUBSTITUTES);
+  match opt_replacement
+  {
+    Some(replacement) => key.set(name, &Data::String(replacement.to_string()).encode()),
+    None => key.erase(name),
+  } // match
+  registry.save(&path_file_reg)
+} // fn set_substitute() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
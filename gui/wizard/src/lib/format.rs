@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+// pub fn format_size() {{{
+// Size with the largest unit that keeps it above one, used by every screen that shows sizes
+pub fn format_size(size : u64) -> String
+{
+  match size
+  {
+    size if size >= 1 << 30 => format!("{:.2} GiB", size as f64 / (1u64 << 30) as f64),
+    size if size >= 1 << 20 => format!("{:.1} MiB", size as f64 / (1u64 << 20) as f64),
+    size if size >= 1 << 10 => format!("{:.1} KiB", size as f64 / (1u64 << 10) as f64),
+    size => format!("{} B", size),
+  } // match
+} // fn format_size() }}}
+
+// pub fn format_time() {{{
+pub fn format_time(time : Duration) -> String
+{
+  let secs = time.as_secs();
+  format!("{}h {:02}m {:02}s", secs / 3600, secs % 3600 / 60, secs % 60)
+} // fn format_time() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,278 @@
+use std::
+{
+  collections::BTreeMap,
+  env,
+  fs,
+  path::PathBuf,
+  sync::LazyLock,
+  time::{Instant,SystemTime,UNIX_EPOCH,Duration},
+};
+
+use anyhow::anyhow as ah;
+use serde::{Deserialize, Serialize};
+
+use shared::std::PathBufExt;
+
+use crate::db;
+use crate::lib;
+
+// Runs kept per project, the logs of older runs are erased
+const COUNT_RUNS_MAX : usize = 100;
+// Prefixes of the variables of the wizard environment recorded with a run
+const PREFIXES_ENV : [&str; 5] = ["WINE", "DXVK", "VKD3D", "PROTON", "FIM_"];
+
+// pub struct Run {{{
+// A test of the project, as it was configured when it started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run
+{
+  // Unix time of the start
+  pub time          : u64,
+  pub platform      : String,
+  // Selected executable or rom, relative to the project directory
+  pub path_file_rom : Option<PathBuf>,
+  pub args          : Option<String>,
+  pub env           : BTreeMap<String, String>,
+  pub dist_wine     : Option<String>,
+  pub secs_duration : f64,
+  pub code          : i32,
+  pub path_file_log : PathBuf,
+} // struct Run }}}
+
+impl Run
+{
+
+// pub fn time_str() {{{
+pub fn time_str(&self) -> String
+{
+  let (year, month, day, hour, min, sec) = lib::log::datetime(UNIX_EPOCH + Duration::from_secs(self.time));
+  format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, hour, min, sec)
+} // fn time_str() }}}
+
+// pub fn summary() {{{
+// One line description for lists
+pub fn summary(&self) -> String
+{
+  format!("{}  exit {}  {}  {}"
+    , self.time_str()
+    , self.code
+    , lib::format::format_time(Duration::from_secs_f64(self.secs_duration))
+    , self.path_file_rom.as_ref().map(|e| e.string()).unwrap_or("no executable".into())
+  )
+} // fn summary() }}}
+
+// pub fn changes() {{{
+// Differences in the configuration from a previous run
+pub fn changes(&self, prev : &Run) -> Vec<String>
+{
+  let f_opt = |value : &Option<String>| value.clone().unwrap_or("none".into());
+  let mut changes = vec![];
+  if self.path_file_rom != prev.path_file_rom
+  {
+    changes.push(format!("Executable: {} -> {}"
+      , f_opt(&prev.path_file_rom.as_ref().map(|e| e.string()))
+      , f_opt(&self.path_file_rom.as_ref().map(|e| e.string()))
+    ));
+  } // if
+  if self.args != prev.args
+  {
+    changes.push(format!("Arguments: '{}' -> '{}'", f_opt(&prev.args), f_opt(&self.args)));
+  } // if
+  if self.dist_wine != prev.dist_wine
+  {
+    changes.push(format!("Wine distribution: {} -> {}", f_opt(&prev.dist_wine), f_opt(&self.dist_wine)));
+  } // if
+  for (key, value) in &self.env
+  {
+    match prev.env.get(key)
+    {
+      Some(value_prev) if value_prev == value => (),
+      Some(value_prev) => changes.push(format!("Variable {}: '{}' -> '{}'", key, value_prev, value)),
+      None => changes.push(format!("Variable {} added: '{}'", key, value)),
+    } // match
+  } // for
+  for key in prev.env.keys().filter(|e| ! self.env.contains_key(*e))
+  {
+    changes.push(format!("Variable {} removed", key));
+  } // for
+  changes
+} // fn changes() }}}
+
+// pub fn details() {{{
+// Configuration of the run, its changes from the previous run and the end of its log
+pub fn details(&self, opt_prev : Option<&Run>) -> String
+{
+  let mut text = String::new();
+  text.push_str(&format!("Started: {}\n", self.time_str()));
+  text.push_str(&format!("Exit code: {}\n", self.code));
+  text.push_str(&format!("Duration: {}\n", lib::format::format_time(Duration::from_secs_f64(self.secs_duration))));
+  text.push_str(&format!("Executable: {}\n", self.path_file_rom.as_ref().map(|e| e.string()).unwrap_or("none".into())));
+  text.push_str(&format!("Arguments: {}\n", self.args.clone().unwrap_or("none".into())));
+  if let Some(dist_wine) = &self.dist_wine
+  {
+    text.push_str(&format!("Wine distribution: {}\n", dist_wine));
+  } // if
+  text.push_str("Environment:\n");
+  self.env.iter().for_each(|(k,v)| text.push_str(&format!("  {}={}\n", k, v)));
+  if let Some(prev) = opt_prev
+  {
+    let changes = self.changes(prev);
+    text.push_str(&format!("\nChanges since the run of {}:\n", prev.time_str()));
+    if changes.is_empty() { text.push_str("  None\n"); } // if
+    if prev.code != self.code
+    {
+      text.push_str(&format!("  Exit code changed from {} to {}\n", prev.code, self.code));
+    } // if
+    changes.iter().for_each(|e| text.push_str(&format!("  {}\n", e)));
+  } // if
+  text.push_str(&format!("\nLog: {}\n", self.path_file_log.string()));
+  match fs::read_to_string(&self.path_file_log)
+  {
+    Ok(log) =>
+    {
+      let lines : Vec<&str> = log.lines().collect();
+      lines[lines.len().saturating_sub(200)..].iter().for_each(|e| text.push_str(&format!("{}\n", e)));
+    },
+    Err(e) => text.push_str(&format!("Could not read log: {}\n", e)),
+  } // match
+  text
+} // fn details() }}}
+
+} // impl Run
+
+// pub struct Recorder {{{
+// Collects a run while it executes
+pub struct Recorder
+{
+  run          : Run,
+  instant      : Instant,
+  path_file_db : PathBuf,
+} // struct Recorder }}}
+
+impl Recorder
+{
+
+// pub fn path_file_log() {{{
+// File that receives the output of the run
+pub fn path_file_log(&self) -> PathBuf
+{
+  self.run.path_file_log.clone()
+} // fn path_file_log() }}}
+
+// pub fn finish() {{{
+// Stores the run in the project with its exit code
+pub fn finish(mut self, code : i32) -> anyhow::Result<Run>
+{
+  self.run.secs_duration = self.instant.elapsed().as_secs_f64();
+  self.run.code = code;
+  // Keep the text of the log, terminal runs include escape sequences
+  if let Ok(log) = fs::read(&self.run.path_file_log)
+  {
+    fs::write(&self.run.path_file_log, strip_escapes(&String::from_utf8_lossy(&log)))?;
+  } // if
+  let mut runs = read(&self.path_file_db);
+  runs.push(self.run.clone());
+  if runs.len() > COUNT_RUNS_MAX
+  {
+    for run in runs.drain(..runs.len() - COUNT_RUNS_MAX)
+    {
+      let _ = fs::remove_file(run.path_file_log);
+    } // for
+  } // if
+  serde_json::to_writer_pretty(fs::File::create(&self.path_file_db)?, &runs)?;
+  Ok(self.run)
+} // fn finish() }}}
+
+} // impl Recorder
+
+// fn strip_escapes() {{{
+// Removes terminal control sequences and carriage returns
+fn strip_escapes(text : &str) -> String
+{
+  static REGEX : LazyLock<regex::Regex> = LazyLock::new(||
+  {
+    regex::Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b[()][0-9A-Za-z]|\x1b[=>@-Z\\^_]|\r")
+      .expect("Invalid escape regex")
+  });
+  REGEX.replace_all(text, "").to_string()
+} // fn strip_escapes() }}}
+
+// fn path_file_db() {{{
+fn path_file_db() -> anyhow::Result<PathBuf>
+{
+  let global = db::global::read()?;
+  Ok(global.get_project_dir(&global.project)?.join("gameimage.history.json"))
+} // fn path_file_db() }}}
+
+// fn read() {{{
+fn read(path_file_db : &PathBuf) -> Vec<Run>
+{
+  fs::File::open(path_file_db)
+    .ok()
+    .and_then(|file| serde_json::from_reader(file).ok())
+    .unwrap_or_default()
+} // fn read() }}}
+
+// pub fn begin() {{{
+// Records the configuration of the current project before a test starts
+pub fn begin() -> anyhow::Result<Recorder>
+{
+  let global = db::global::read()?;
+  let project = db::project::current()?;
+  let path_dir_project = global.get_project_dir(&global.project)?;
+  let path_file_rom = project.get_path_relative(db::project::EntryName::PathFileRom).ok();
+  // Arguments are keyed by the path as it is displayed in the wizard
+  let args = path_file_rom.as_ref().and_then(|path_file_rom|
+  {
+    let kv = shared::db::kv::read(&path_dir_project.join("gameimage.args.json")).ok()?;
+    kv.get(&path_file_rom.string())
+      .or(kv.get(&path_dir_project.join(path_file_rom).string()))
+      .cloned()
+  });
+  // Variables of the project and of the wizard
+  let mut env : BTreeMap<String,String> = shared::db::kv::read(&path_dir_project.join("gameimage.env.json"))
+    .unwrap_or_default()
+    .into_iter()
+    .collect();
+  env.extend(env::vars().filter(|(k,_)| PREFIXES_ENV.iter().any(|e| k.starts_with(e))));
+  let platform = project.get_platform();
+  let dist_wine = (platform == "wine").then_some(global.dist_wine.clone());
+  // Log file in the build directory, so it is not packaged
+  let time = SystemTime::now();
+  let (year, month, day, hour, min, sec) = lib::log::datetime(time);
+  let path_file_log = global.path_dir_build
+    .join("log")
+    .join("test")
+    .join(&global.project)
+    .join(format!("{:04}{:02}{:02}-{:02}{:02}{:02}.log", year, month, day, hour, min, sec));
+  fs::create_dir_all(path_file_log.parent().ok_or(ah!("Invalid log path"))?)?;
+  fs::File::create(&path_file_log)?;
+  Ok(Recorder
+  {
+    run: Run
+    {
+      time: time.duration_since(UNIX_EPOCH)?.as_secs(),
+      platform,
+      path_file_rom,
+      args,
+      env,
+      dist_wine,
+      secs_duration: 0.0,
+      code: -1,
+      path_file_log,
+    },
+    instant: Instant::now(),
+    path_file_db: path_dir_project.join("gameimage.history.json"),
+  })
+} // fn begin() }}}
+
+// pub fn list() {{{
+// Runs of the current project, newest first
+pub fn list() -> anyhow::Result<Vec<Run>>
+{
+  let mut runs = read(&path_file_db()?);
+  runs.reverse();
+  Ok(runs)
+} // fn list() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -5,7 +5,6 @@ use anyhow::anyhow as ah;
 use shared::std::PathBufExt;
 
 use crate::log;
-use crate::common;
 use crate::gameimage;
 
 // struct MsgBuf {{{
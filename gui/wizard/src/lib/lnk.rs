@@ -0,0 +1,381 @@
+use std::
+{
+  fs,
+  path::{Path,PathBuf},
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+use crate::log;
+
+// Size of the header and identifier of the shell link class
+const SIZE_HEADER : usize = 0x4C;
+const CLSID_SHELL_LINK : [u8; 16] = [0x01,0x14,0x02,0x00,0x00,0x00,0x00,0x00,0xC0,0x00,0x00,0x00,0x00,0x00,0x00,0x46];
+// Largest shortcut that is read, protects against corrupt files
+const SIZE_LINK_MAX : u64 = 1 << 20;
+// Flags of the header
+const FLAG_HAS_ID_LIST       : u32 = 0x01;
+const FLAG_HAS_LINK_INFO     : u32 = 0x02;
+const FLAG_HAS_NAME          : u32 = 0x04;
+const FLAG_HAS_RELATIVE_PATH : u32 = 0x08;
+const FLAG_HAS_WORKING_DIR   : u32 = 0x10;
+const FLAG_HAS_ARGUMENTS     : u32 = 0x20;
+const FLAG_HAS_ICON_LOCATION : u32 = 0x40;
+const FLAG_IS_UNICODE        : u32 = 0x80;
+// Extra data block with the target path written with environment variables
+const SIGNATURE_ENVIRONMENT : u32 = 0xA0000001;
+// Variables of the environment blocks that can be expanded without a running system
+const VARIABLES : [(&str, &str); 7] =
+[
+  ("%ProgramFiles%", "C:\\Program Files"),
+  ("%ProgramFiles(x86)%", "C:\\Program Files (x86)"),
+  ("%ProgramW6432%", "C:\\Program Files"),
+  ("%CommonProgramFiles%", "C:\\Program Files\\Common Files"),
+  ("%SystemDrive%", "C:"),
+  ("%SystemRoot%", "C:\\windows"),
+  ("%windir%", "C:\\windows"),
+];
+// Directories of the prefix with start menu entries and desktop icons, relative to drive_c
+const DIRS_SHORTCUTS : [&str; 2] = ["users", "ProgramData/Microsoft/Windows/Start Menu"];
+
+// pub struct Link {{{
+// Contents of a shell link (.lnk) file
+#[derive(Debug, Clone, Default)]
+pub struct Link
+{
+  pub name          : Option<String>,
+  // Windows path of the target
+  pub target        : Option<String>,
+  // Path of the target relative to the link
+  pub relative_path : Option<String>,
+  pub working_dir   : Option<String>,
+  pub arguments     : Option<String>,
+  pub icon_location : Option<String>,
+  pub icon_index    : i32,
+} // struct Link }}}
+
+// struct Data {{{
+// Bounds checked reads of the file contents
+struct Data<'a>
+{
+  bytes : &'a [u8],
+} // struct Data }}}
+
+impl<'a> Data<'a>
+{
+
+fn slice(&self, offset : usize, len : usize) -> anyhow::Result<&'a [u8]>
+{
+  self.bytes.get(offset..offset.checked_add(len).ok_or(ah!("Invalid offset"))?)
+    .ok_or(ah!("Offset {:#x} is out of bounds", offset))
+} // slice
+
+fn u16(&self, offset : usize) -> anyhow::Result<u16>
+{
+  let buf = self.slice(offset, 2)?;
+  Ok(u16::from_le_bytes([buf[0], buf[1]]))
+} // u16
+
+fn u32(&self, offset : usize) -> anyhow::Result<u32>
+{
+  let buf = self.slice(offset, 4)?;
+  Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+} // u32
+
+// Null terminated string in the system code page, read as latin-1
+fn cstr(&self, offset : usize) -> anyhow::Result<String>
+{
+  let buf = self.bytes.get(offset..).ok_or(ah!("Offset {:#x} is out of bounds", offset))?;
+  Ok(buf.iter().take_while(|e| **e != 0).map(|e| *e as char).collect())
+} // cstr
+
+// Null terminated utf-16 string
+fn wstr(&self, offset : usize) -> anyhow::Result<String>
+{
+  let buf = self.bytes.get(offset..).ok_or(ah!("Offset {:#x} is out of bounds", offset))?;
+  let units : Vec<u16> = buf.chunks_exact(2)
+    .map(|e| u16::from_le_bytes([e[0], e[1]]))
+    .take_while(|e| *e != 0)
+    .collect();
+  Ok(String::from_utf16_lossy(&units))
+} // wstr
+
+// String of the string data section, prefixed by its count of characters
+fn counted(&self, offset : usize, is_unicode : bool) -> anyhow::Result<(String, usize)>
+{
+  let count = self.u16(offset)? as usize;
+  if is_unicode
+  {
+    let units : Vec<u16> = self.slice(offset + 2, count * 2)?
+      .chunks_exact(2)
+      .map(|e| u16::from_le_bytes([e[0], e[1]]))
+      .collect();
+    Ok((String::from_utf16_lossy(&units), offset + 2 + count * 2))
+  } // if
+  else
+  {
+    let text = self.slice(offset + 2, count)?.iter().map(|e| *e as char).collect();
+    Ok((text, offset + 2 + count))
+  } // else
+} // counted
+
+} // impl Data
+
+// fn link_info() {{{
+// Target path of the link info section, prefers the unicode variant
+fn link_info(data : &Data, offset : usize) -> anyhow::Result<Option<String>>
+{
+  let size_header = data.u32(offset + 4)? as usize;
+  let flags = data.u32(offset + 8)?;
+  // Only local targets have a base path
+  if flags & 0x1 == 0 { return Ok(None); } // if
+  let f_read = |index_ansi : usize, index_unicode : usize| -> anyhow::Result<String>
+  {
+    let offset_unicode = if size_header >= 0x24 { data.u32(offset + index_unicode)? as usize } else { 0 };
+    if offset_unicode != 0 { return data.wstr(offset + offset_unicode); } // if
+    data.cstr(offset + data.u32(offset + index_ansi)? as usize)
+  };
+  let base = f_read(0x10, 0x1C)?;
+  let suffix = f_read(0x18, 0x20)?;
+  if suffix.is_empty() || base.ends_with('\\') { return Ok(Some(base + &suffix)); } // if
+  Ok(Some(base + "\\" + &suffix))
+} // fn link_info() }}}
+
+// fn expand() {{{
+// Expands the known variables of a path, fails if unknown ones remain
+fn expand(path : &str) -> Option<String>
+{
+  let mut path = path.to_string();
+  for (variable, value) in VARIABLES
+  {
+    if let Some(index) = path.to_ascii_lowercase().find(&variable.to_ascii_lowercase())
+    {
+      path.replace_range(index..index+variable.len(), value);
+    } // if
+  } // for
+  (! path.contains('%')).then_some(path)
+} // fn expand() }}}
+
+impl Link
+{
+
+// pub fn parse() {{{
+pub fn parse(bytes : &[u8]) -> anyhow::Result<Link>
+{
+  let data = Data{ bytes };
+  if data.u32(0)? as usize != SIZE_HEADER || data.slice(4, 16)? != CLSID_SHELL_LINK
+  {
+    return Err(ah!("Not a shell link"));
+  } // if
+  let flags = data.u32(0x14)?;
+  let mut link = Link{ icon_index: data.u32(0x38)? as i32, ..Link::default() };
+  let mut offset = SIZE_HEADER;
+  // Item identifiers of the target, the same target is also in the link info
+  if flags & FLAG_HAS_ID_LIST != 0
+  {
+    offset += 2 + data.u16(offset)? as usize;
+  } // if
+  if flags & FLAG_HAS_LINK_INFO != 0
+  {
+    link.target = link_info(&data, offset)?;
+    offset += data.u32(offset)? as usize;
+  } // if
+  // Strings in the order they are stored
+  let is_unicode = flags & FLAG_IS_UNICODE != 0;
+  for (flag, field) in [
+      (FLAG_HAS_NAME, &mut link.name),
+      (FLAG_HAS_RELATIVE_PATH, &mut link.relative_path),
+      (FLAG_HAS_WORKING_DIR, &mut link.working_dir),
+      (FLAG_HAS_ARGUMENTS, &mut link.arguments),
+      (FLAG_HAS_ICON_LOCATION, &mut link.icon_location)]
+  {
+    if flags & flag == 0 { continue; } // if
+    let (text, offset_next) = data.counted(offset, is_unicode)?;
+    *field = (! text.is_empty()).then_some(text);
+    offset = offset_next;
+  } // for
+  // Extra data, terminated by a block smaller than four bytes
+  while let Ok(size) = data.u32(offset) && size >= 8
+  {
+    if data.u32(offset + 4)? == SIGNATURE_ENVIRONMENT && link.target.is_none()
+    {
+      let target = match data.wstr(offset + 268)
+      {
+        Ok(target) if ! target.is_empty() => target,
+        _ => data.cstr(offset + 8)?,
+      }; // match
+      link.target = expand(&target);
+    } // if
+    offset += size as usize;
+  } // while
+  Ok(link)
+} // fn parse() }}}
+
+// pub fn open() {{{
+pub fn open(path_file : &PathBuf) -> anyhow::Result<Link>
+{
+  let size = fs::metadata(path_file)?.len();
+  if size > SIZE_LINK_MAX { return Err(ah!("Shortcut '{}' is too large", path_file.string())); } // if
+  Link::parse(&fs::read(path_file)?)
+} // fn open() }}}
+
+} // impl Link
+
+// fn resolve() {{{
+// Joins the components to the directory ignoring their case, like windows does, fails if one of
+// them does not exist
+fn resolve(path_dir_base : &Path, components : &[&str]) -> Option<PathBuf>
+{
+  let mut path = path_dir_base.to_path_buf();
+  for component in components
+  {
+    match *component
+    {
+      "" | "." => continue,
+      ".." => { path.pop(); continue; },
+      _ => (),
+    } // match
+    let path_exact = path.join(component);
+    if path_exact.exists() { path = path_exact; continue; } // if
+    path = fs::read_dir(&path).ok()?
+      .filter_map(|e| e.ok())
+      .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(component))?
+      .path();
+  } // for
+  Some(path)
+} // fn resolve() }}}
+
+// pub fn to_unix() {{{
+// Path of a windows path in the prefix, only the 'C:' drive is inside of the prefix
+pub fn to_unix(path_dir_prefix : &Path, path_windows : &str) -> Option<PathBuf>
+{
+  let (drive, path) = path_windows.split_once(':')?;
+  if ! drive.eq_ignore_ascii_case("c") { return None; } // if
+  let components : Vec<&str> = path.split(['\\', '/']).collect();
+  resolve(&path_dir_prefix.join("drive_c"), &components)
+} // fn to_unix() }}}
+
+// pub struct Shortcut {{{
+// A shortcut of the prefix that launches an executable of the project
+#[derive(Debug, Clone)]
+pub struct Shortcut
+{
+  // Both paths are relative to the project directory
+  pub path_file_lnk    : PathBuf,
+  pub path_file_target : PathBuf,
+  // Working directory of the shortcut, when it is inside of the project
+  pub path_dir_working : Option<PathBuf>,
+  pub link             : Link,
+} // struct Shortcut }}}
+
+impl Shortcut
+{
+
+// pub fn alias() {{{
+// Name of the shortcut as shown by windows
+pub fn alias(&self) -> String
+{
+  self.path_file_lnk.file_stem().map(|e| e.to_string_lossy().to_string()).unwrap_or_default()
+} // fn alias() }}}
+
+// pub fn arguments() {{{
+pub fn arguments(&self) -> String
+{
+  self.link.arguments.clone().unwrap_or_default()
+} // fn arguments() }}}
+
+// pub fn summary() {{{
+pub fn summary(&self) -> String
+{
+  format!("{} ({})", self.alias(), self.path_file_target.file_name_string())
+} // fn summary() }}}
+
+// pub fn working_dir() {{{
+// Working directory to store with the launch profile, when it is not the directory of the executable
+pub fn working_dir(&self) -> Option<&PathBuf>
+{
+  self.path_dir_working.as_ref().filter(|e| Some(e.as_path()) != self.path_file_target.parent())
+} // fn working_dir() }}}
+
+// pub fn working_dir_outside() {{{
+// Working directory of the shortcut when it is outside of the project, the launcher starts the
+// executable from its own directory instead
+pub fn working_dir_outside(&self) -> Option<String>
+{
+  if self.path_dir_working.is_some() { return None; } // if
+  self.link.working_dir.clone().filter(|e| ! e.trim().is_empty())
+} // fn working_dir_outside() }}}
+
+// pub fn details() {{{
+pub fn details(&self) -> String
+{
+  let f_value = |e : &Option<String>| e.clone().unwrap_or("-".to_string());
+  let mut text = String::new();
+  text.push_str(&format!("Alias: {}\n", self.alias()));
+  text.push_str(&format!("Target: {}\n", f_value(&self.link.target)));
+  text.push_str(&format!("Executable: {}\n", self.path_file_target.string()));
+  text.push_str(&format!("Arguments: {}\n", f_value(&self.link.arguments)));
+  text.push_str(&format!("Working directory: {}\n", f_value(&self.link.working_dir)));
+  if self.working_dir_outside().is_some()
+  {
+    text.push_str("Note: the working directory is outside of the project, the launcher starts the executable from its own directory instead\n");
+  } // if
+  text.push_str(&format!("Icon: {}", f_value(&self.link.icon_location)));
+  if self.link.icon_location.is_some() { text.push_str(&format!(" ({})", self.link.icon_index)); } // if
+  text.push('\n');
+  if let Some(name) = &self.link.name { text.push_str(&format!("Description: {}\n", name)); } // if
+  text.push_str(&format!("Shortcut: {}\n", self.path_file_lnk.string()));
+  text
+} // fn details() }}}
+
+} // impl Shortcut
+
+// pub fn list() {{{
+// Shortcuts of the start menu and desktops of the prefix whose target is an executable of the
+// project, sorted by alias
+pub fn list(path_dir_project : &PathBuf) -> Vec<Shortcut>
+{
+  let path_dir_prefix = path_dir_project.join("wine");
+  let mut shortcuts : Vec<Shortcut> = DIRS_SHORTCUTS.iter()
+    .flat_map(|e| walkdir::WalkDir::new(path_dir_prefix.join("drive_c").join(e)).follow_links(false))
+    .filter_map(|e| e.ok())
+    .filter(|e| e.file_type().is_file())
+    .filter(|e| e.path().extension().is_some_and(|e| e.eq_ignore_ascii_case("lnk")))
+    .filter_map(|entry|
+    {
+      let path_file_lnk = entry.path().to_path_buf();
+      let link = Link::open(&path_file_lnk).map_err(|e| log!("Could not read '{}': {}", path_file_lnk.string(), e)).ok()?;
+      // Absolute target, or relative to the shortcut
+      let path_file_target = link.target.as_ref()
+        .and_then(|e| to_unix(&path_dir_prefix, e))
+        .or_else(||
+        {
+          let components : Vec<&str> = link.relative_path.as_ref()?.split(['\\', '/']).collect();
+          resolve(path_file_lnk.parent()?, &components)
+        })?;
+      if ! path_file_target.is_file()
+      || ! path_file_target.extension().is_some_and(|e| e.eq_ignore_ascii_case("exe"))
+      {
+        return None;
+      } // if
+      let path_dir_working = link.working_dir.as_ref()
+        .and_then(|e| to_unix(&path_dir_prefix, e))
+        .and_then(|e| e.strip_prefix(path_dir_project).ok().map(|e| e.to_path_buf()));
+      Some(Shortcut
+      {
+        path_file_lnk: path_file_lnk.strip_prefix(path_dir_project).ok()?.to_path_buf(),
+        path_file_target: path_file_target.strip_prefix(path_dir_project).ok()?.to_path_buf(),
+        path_dir_working,
+        link,
+      })
+    })
+    .collect();
+  // The same shortcut is often both in the start menu and in the desktop
+  shortcuts.sort_by(|a, b| a.alias().to_lowercase().cmp(&b.alias().to_lowercase()));
+  shortcuts.dedup_by(|a, b| a.path_file_target == b.path_file_target && a.arguments() == b.arguments());
+  shortcuts
+} // fn list() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,320 @@
+use std::
+{
+  env,
+  fs,
+  cell::RefCell,
+  collections::VecDeque,
+  io::Write,
+  path::PathBuf,
+  sync::{Mutex,OnceLock,mpsc},
+  time::{SystemTime,UNIX_EPOCH},
+};
+
+// Log files larger than this are continued in a new file
+const SIZE_FILE_MAX : u64 = 4 << 20;
+// Log files kept in the build directory across sessions
+const COUNT_FILES_MAX : usize = 20;
+// Records kept in memory for the log viewer
+const COUNT_HISTORY_MAX : usize = 100_000;
+
+// pub enum Level {{{
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level
+{
+  Debug,
+  Info,
+  Warn,
+  Error,
+} // }}}
+
+// impl Level {{{
+impl Level
+{
+  pub fn all() -> [Level; 4]
+  {
+    [Level::Debug, Level::Info, Level::Warn, Level::Error]
+  } // all
+
+  pub fn as_str(&self) -> &'static str
+  {
+    match self
+    {
+      Level::Debug => "debug",
+      Level::Info  => "info",
+      Level::Warn  => "warn",
+      Level::Error => "error",
+    } // match
+  } // as_str
+
+  pub fn from_str(src : &str) -> Option<Level>
+  {
+    Level::all().into_iter().find(|e| e.as_str() == src.to_lowercase())
+  } // from_str
+} // impl Level }}}
+
+// pub struct Record {{{
+#[derive(Debug, Clone)]
+pub struct Record
+{
+  pub time    : SystemTime,
+  pub level   : Level,
+  // Source of the message, as file:line
+  pub target  : String,
+  // Backend job that produced the message
+  pub job     : Option<String>,
+  pub message : String,
+} // struct Record }}}
+
+impl Record
+{
+
+// pub fn format() {{{
+pub fn format(&self) -> String
+{
+  let (_, _, _, hour, min, sec) = datetime(self.time);
+  let millis = self.time.duration_since(UNIX_EPOCH).map(|e| e.subsec_millis()).unwrap_or(0);
+  match &self.job
+  {
+    Some(job) => format!("{:02}:{:02}:{:02}.{:03} {:<5} [{}] {}: {}"
+      , hour, min, sec, millis, self.level.as_str(), job, self.target, self.message),
+    None => format!("{:02}:{:02}:{:02}.{:03} {:<5} {}: {}"
+      , hour, min, sec, millis, self.level.as_str(), self.target, self.message),
+  } // match
+} // fn format() }}}
+
+} // impl Record
+
+// pub struct Filter {{{
+// Selects the records displayed in the log viewer
+#[derive(Debug, Clone)]
+pub struct Filter
+{
+  pub level : Level,
+  pub text  : String,
+  pub job   : Option<String>,
+} // struct Filter }}}
+
+impl Default for Filter
+{
+  fn default() -> Self
+  {
+    Filter{ level: Level::Debug, text: String::new(), job: None }
+  } // default
+} // impl Default
+
+impl Filter
+{
+
+// pub fn matches() {{{
+pub fn matches(&self, record : &Record) -> bool
+{
+  record.level >= self.level
+    && (self.job.is_none() || self.job == record.job)
+    && (self.text.is_empty() || record.message.to_lowercase().contains(&self.text.to_lowercase()))
+} // fn matches() }}}
+
+} // impl Filter
+
+// Records of the session
+static HISTORY : Mutex<VecDeque<Record>> = Mutex::new(VecDeque::new());
+// Filter of the gui sink
+static FILTER : OnceLock<Mutex<Filter>> = OnceLock::new();
+
+thread_local!
+{
+  // Backend job of the current thread
+  static JOB : RefCell<Option<String>> = RefCell::new(None);
+}
+
+// pub fn datetime() {{{
+// Converts a time to utc (year, month, day, hour, minute, second)
+pub fn datetime(time : SystemTime) -> (i64, u32, u32, u32, u32, u32)
+{
+  let secs = time.duration_since(UNIX_EPOCH).map(|e| e.as_secs() as i64).unwrap_or(0);
+  let (days, secs_day) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+  // Civil from days, http://howardhinnant.github.io/date_algorithms.html
+  let z = days + 719468;
+  let era = z.div_euclid(146097);
+  let doe = z.rem_euclid(146097);
+  let yoe = (doe - doe/1460 + doe/36524 - doe/146096) / 365;
+  let doy = doe - (365*yoe + yoe/4 - yoe/100);
+  let mp = (5*doy + 2) / 153;
+  let day = (doy - (153*mp + 2)/5 + 1) as u32;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  (year, month, day, (secs_day / 3600) as u32, (secs_day % 3600 / 60) as u32, (secs_day % 60) as u32)
+} // fn datetime() }}}
+
+// struct SinkFile {{{
+// Writes the records to the log directory of the build dir
+struct SinkFile
+{
+  path_dir_build : PathBuf,
+  session        : String,
+  index          : u32,
+  file           : fs::File,
+} // struct SinkFile }}}
+
+impl SinkFile
+{
+
+// fn new() {{{
+fn new(path_dir_build : PathBuf, session : String) -> anyhow::Result<SinkFile>
+{
+  let path_dir_log = path_dir_build.join("log");
+  fs::create_dir_all(&path_dir_log)?;
+  // Remove the oldest files, names sort by date
+  let mut vec_files : Vec<PathBuf> = fs::read_dir(&path_dir_log)?
+    .filter_map(|e| e.ok())
+    .map(|e| e.path())
+    .filter(|e| e.file_name().map(|e| e.to_string_lossy().starts_with("wizard-")).unwrap_or(false))
+    .collect();
+  vec_files.sort();
+  let count_remove = vec_files.len().saturating_sub(COUNT_FILES_MAX - 1);
+  for path_file in vec_files.into_iter().take(count_remove)
+  {
+    let _ = fs::remove_file(path_file);
+  } // for
+  let file = fs::File::create(path_dir_log.join(format!("wizard-{}.log", session)))?;
+  Ok(SinkFile{ path_dir_build, session, index: 0, file })
+} // fn new() }}}
+
+// fn write() {{{
+fn write(&mut self, record : &Record) -> anyhow::Result<()>
+{
+  // Continue in a new file once the size limit is reached
+  if self.file.metadata()?.len() > SIZE_FILE_MAX
+  {
+    self.index += 1;
+    let path_file = self.path_dir_build.join("log").join(format!("wizard-{}.{}.log", self.session, self.index));
+    self.file = fs::File::create(path_file)?;
+  } // if
+  writeln!(self.file, "{}", record.format())?;
+  Ok(())
+} // fn write() }}}
+
+} // impl SinkFile
+
+// fn sink_term() {{{
+// Appends to the terminal if it exists, never waits for it
+fn sink_term(text : &str)
+{
+  if let Some(mut term) = fltk::app::widget_from_id::<fltk::text::SimpleTerminal>("term_log")
+  {
+    term.append(text);
+    fltk::app::awake();
+  } // if
+} // fn sink_term() }}}
+
+// fn sender() {{{
+// Channel to the logger thread, which owns the sinks
+fn sender() -> &'static mpsc::Sender<Record>
+{
+  static TX: OnceLock<mpsc::Sender<Record>> = OnceLock::new();
+  TX.get_or_init(||
+  {
+    let (tx, rx) = mpsc::channel::<Record>();
+    std::thread::spawn(move ||
+    {
+      let (year, month, day, hour, min, sec) = datetime(SystemTime::now());
+      let session = format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, hour, min, sec);
+      let mut opt_sink_file : Option<SinkFile> = None;
+      while let Ok(record) = rx.recv()
+      {
+        let line = record.format();
+        eprintln!("{}", line);
+        // The build dir is known after the welcome screen, (re)open the file when it changes
+        if let Ok(path_dir_build) = env::var("GIMG_DIR").map(PathBuf::from)
+        && opt_sink_file.as_ref().map(|e| e.path_dir_build != path_dir_build).unwrap_or(true)
+        {
+          opt_sink_file = match SinkFile::new(path_dir_build, session.clone())
+          {
+            Ok(mut sink) =>
+            {
+              // Include what was logged before the file was available
+              for record in HISTORY.lock().unwrap().iter() { let _ = sink.write(record); }
+              Some(sink)
+            },
+            Err(e) => { eprintln!("Could not create log file: {}", e); None },
+          }; // match
+        } // if
+        if let Some(sink) = opt_sink_file.as_mut()
+        && let Err(e) = sink.write(&record)
+        {
+          eprintln!("Could not write to log file: {}", e);
+        } // if
+        if filter().matches(&record)
+        {
+          sink_term(&format!("{}\n", line));
+        } // if
+        let mut history = HISTORY.lock().unwrap();
+        if history.len() >= COUNT_HISTORY_MAX { history.pop_front(); } // if
+        history.push_back(record);
+      } // while
+    });
+    tx
+  })
+} // fn sender() }}}
+
+// pub fn log() {{{
+// Queues a record, does not block the caller
+pub fn log(level : Level, target : &str, message : &str)
+{
+  let record = Record
+  {
+    time: SystemTime::now(),
+    level,
+    target: target.to_string(),
+    job: job(),
+    message: message.trim_end().to_string(),
+  };
+  if let Err(e) = sender().send(record)
+  {
+    eprintln!("Failed to send log message: {}", e);
+  } // if
+} // fn log() }}}
+
+// pub fn job() {{{
+pub fn job() -> Option<String>
+{
+  JOB.with(|e| e.borrow().clone())
+} // fn job() }}}
+
+// pub fn set_job() {{{
+// Tags the records of the current thread with a backend job
+pub fn set_job(job : Option<String>)
+{
+  JOB.with(|e| *e.borrow_mut() = job);
+} // fn set_job() }}}
+
+// pub fn jobs() {{{
+// Backend jobs seen in the session, in order
+pub fn jobs() -> Vec<String>
+{
+  let mut jobs = Vec::<String>::new();
+  for job in HISTORY.lock().unwrap().iter().filter_map(|e| e.job.clone())
+  {
+    if ! jobs.contains(&job) { jobs.push(job); } // if
+  } // for
+  jobs
+} // fn jobs() }}}
+
+// pub fn filter() {{{
+pub fn filter() -> Filter
+{
+  FILTER.get_or_init(|| Mutex::new(Filter::default())).lock().unwrap().clone()
+} // fn filter() }}}
+
+// pub fn set_filter() {{{
+// Changes the filter of the gui sink and returns the matching records of the session
+pub fn set_filter(filter : Filter) -> String
+{
+  *FILTER.get_or_init(|| Mutex::new(Filter::default())).lock().unwrap() = filter.clone();
+  HISTORY.lock().unwrap()
+    .iter()
+    .filter(|e| filter.matches(e))
+    .map(|e| e.format() + "\n")
+    .collect()
+} // fn set_filter() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
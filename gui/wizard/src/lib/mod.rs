@@ -1 +1,21 @@
 pub mod ipc;
+pub mod log;
+pub mod pty;
+pub mod vt;
+pub mod preflight;
+pub mod verify;
+pub mod compression;
+pub mod format;
+pub mod history;
+pub mod pe;
+pub mod dependencies;
+pub mod executable;
+pub mod snapshot;
+pub mod lnk;
+pub mod uninstall;
+pub mod slim;
+pub mod usage;
+pub mod distribution;
+pub mod winetricks;
+pub mod font;
+pub mod template;
@@ -0,0 +1,355 @@
+use std::
+{
+  collections::BTreeMap,
+  fs::File,
+  io::{Read, Seek, SeekFrom},
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+// Indexes in the data directory of the optional header
+const DIRECTORY_IMPORT : u32 = 1;
+const DIRECTORY_RESOURCE : u32 = 2;
+const DIRECTORY_DELAY_IMPORT : u32 = 13;
+const DIRECTORY_CLR : u32 = 14;
+// Limit of entries read from a table, protects against corrupt files
+const COUNT_ENTRIES_MAX : u32 = 4096;
+// Resource type of the version information
+const RESOURCE_VERSION : u32 = 16;
+// Largest version resource that is read
+const SIZE_VERSION_MAX : u32 = 64 << 10;
+
+// pub enum Machine {{{
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine
+{
+  X86,
+  X64,
+  Arm64,
+  Other(u16),
+} // enum Machine }}}
+
+impl Machine
+{
+  pub fn as_str(&self) -> String
+  {
+    match self
+    {
+      Machine::X86 => "x86".into(),
+      Machine::X64 => "x86_64".into(),
+      Machine::Arm64 => "arm64".into(),
+      Machine::Other(value) => format!("unknown ({:#x})", value),
+    } // match
+  } // as_str
+} // impl Machine
+
+// pub enum Subsystem {{{
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem
+{
+  Gui,
+  Console,
+  Other(u16),
+} // enum Subsystem }}}
+
+impl Subsystem
+{
+  pub fn as_str(&self) -> String
+  {
+    match self
+    {
+      Subsystem::Gui => "gui".into(),
+      Subsystem::Console => "console".into(),
+      Subsystem::Other(value) => format!("other ({})", value),
+    } // match
+  } // as_str
+} // impl Subsystem
+
+// struct Section {{{
+#[derive(Debug, Clone)]
+struct Section
+{
+  rva          : u32,
+  size_virtual : u32,
+  offset       : u32,
+  size_raw     : u32,
+} // struct Section }}}
+
+// pub struct Pe {{{
+// Headers of a portable executable (.exe, .dll)
+#[derive(Debug, Clone)]
+pub struct Pe
+{
+  pub machine     : Machine,
+  pub subsystem   : Subsystem,
+  pub is_dll      : bool,
+  // Lowercase names of the imported and delay loaded libraries
+  pub imports     : Vec<String>,
+  // Runtime version of .NET assemblies, e.g., 'v4.0.30319'
+  pub clr_version : Option<String>,
+  // Strings of the version resource, e.g., ProductName, FileDescription, FileVersion
+  pub version     : BTreeMap<String, String>,
+  sections        : Vec<Section>,
+  directories     : Vec<(u32, u32)>,
+} // struct Pe }}}
+
+// struct Reader {{{
+// Random access to the file, executables can be too large to load in memory
+struct Reader
+{
+  file : File,
+} // struct Reader }}}
+
+impl Reader
+{
+
+fn bytes(&mut self, offset : u64, len : usize) -> anyhow::Result<Vec<u8>>
+{
+  let mut buf = vec![0u8; len];
+  self.file.seek(SeekFrom::Start(offset))?;
+  self.file.read_exact(&mut buf)?;
+  Ok(buf)
+} // bytes
+
+fn u16(&mut self, offset : u64) -> anyhow::Result<u16>
+{
+  let buf = self.bytes(offset, 2)?;
+  Ok(u16::from_le_bytes([buf[0], buf[1]]))
+} // u16
+
+fn u32(&mut self, offset : u64) -> anyhow::Result<u32>
+{
+  let buf = self.bytes(offset, 4)?;
+  Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+} // u32
+
+fn cstr(&mut self, offset : u64, len_max : usize) -> anyhow::Result<String>
+{
+  self.file.seek(SeekFrom::Start(offset))?;
+  let mut buf = vec![];
+  (&mut self.file).take(len_max as u64).read_to_end(&mut buf)?;
+  let end = buf.iter().position(|e| *e == 0).unwrap_or(buf.len());
+  Ok(String::from_utf8_lossy(&buf[..end]).to_string())
+} // cstr
+
+} // impl Reader
+
+// fn utf16() {{{
+// Reads a null terminated utf-16 string, returns it and the offset after the terminator
+fn utf16(data : &[u8], offset : usize, end : usize) -> (String, usize)
+{
+  let mut units = vec![];
+  let mut i = offset;
+  while i + 1 < end
+  {
+    let unit = u16::from_le_bytes([data[i], data[i+1]]);
+    i += 2;
+    if unit == 0 { break; } // if
+    units.push(unit);
+  } // while
+  (String::from_utf16_lossy(&units), i)
+} // fn utf16() }}}
+
+// fn version_strings() {{{
+// Walks the blocks of a VS_VERSIONINFO, strings are the leaves of type text under StringFileInfo
+fn version_strings(data : &[u8], offset : usize, end : usize, strings : &mut BTreeMap<String,String>)
+{
+  let f_align = |value : usize| (value + 3) & !3;
+  let mut offset = offset;
+  while offset + 6 <= end
+  {
+    let len = u16::from_le_bytes([data[offset], data[offset+1]]) as usize;
+    let len_value = u16::from_le_bytes([data[offset+2], data[offset+3]]) as usize;
+    let is_text = u16::from_le_bytes([data[offset+4], data[offset+5]]) == 1;
+    if len < 6 || offset + len > end { break; } // if
+    let end_block = offset + len;
+    let (key, offset_key_end) = utf16(data, offset + 6, end_block);
+    let offset_value = f_align(offset_key_end);
+    // Text values are measured in characters
+    let size_value = if is_text { len_value * 2 } else { len_value };
+    let offset_children = f_align(offset_value + size_value).min(end_block);
+    match key.as_str()
+    {
+      "VS_VERSION_INFO" | "StringFileInfo" => version_strings(data, offset_children, end_block, strings),
+      "VarFileInfo" => (),
+      // String tables are named by language and code page, e.g., 040904b0
+      _ if key.len() == 8 && key.chars().all(|e| e.is_ascii_hexdigit()) && size_value == 0 =>
+        version_strings(data, offset_children, end_block, strings),
+      _ if is_text && size_value > 0 && offset_value < end_block =>
+      {
+        let (value, _) = utf16(data, offset_value, (offset_value + size_value).min(end_block));
+        let value = value.trim().to_string();
+        if ! value.is_empty() { strings.entry(key).or_insert(value); } // if
+      },
+      _ => (),
+    } // match
+    offset = f_align(end_block);
+  } // while
+} // fn version_strings() }}}
+
+impl Pe
+{
+
+// fn offset() {{{
+// Converts a relative virtual address to a file offset
+fn offset(&self, rva : u32) -> Option<u64>
+{
+  self.sections.iter()
+    .find(|e| rva >= e.rva && rva - e.rva < e.size_virtual.max(e.size_raw))
+    .map(|e| (rva - e.rva) as u64 + e.offset as u64)
+} // fn offset() }}}
+
+// fn directory() {{{
+// File offset and size of a data directory, if present
+fn directory(&self, index : u32) -> Option<(u64, u32)>
+{
+  let (rva, size) = *self.directories.get(index as usize)?;
+  if rva == 0 || size == 0 { return None; } // if
+  Some((self.offset(rva)?, size))
+} // fn directory() }}}
+
+// fn read_imports() {{{
+fn read_imports(&mut self, reader : &mut Reader) -> anyhow::Result<()>
+{
+  // Descriptors are 20 bytes with the name at 12 for imports, 32 bytes with the name at 4 for
+  // delay imports, the tables end with a zeroed entry
+  for (index, size_entry, offset_name) in [(DIRECTORY_IMPORT, 20, 12), (DIRECTORY_DELAY_IMPORT, 32, 4)]
+  {
+    let Some((offset, _)) = self.directory(index) else { continue; };
+    for i in 0..COUNT_ENTRIES_MAX as u64
+    {
+      let rva_name = reader.u32(offset + i * size_entry + offset_name)?;
+      if rva_name == 0 { break; } // if
+      let Some(offset_str) = self.offset(rva_name) else { continue; };
+      let name = reader.cstr(offset_str, 256)?.to_lowercase();
+      if ! name.is_empty() && ! self.imports.contains(&name) { self.imports.push(name); } // if
+    } // for
+  } // for
+  Ok(())
+} // fn read_imports() }}}
+
+// fn read_clr() {{{
+fn read_clr(&mut self, reader : &mut Reader) -> anyhow::Result<()>
+{
+  let Some((offset, _)) = self.directory(DIRECTORY_CLR) else { return Ok(()); };
+  // The metadata root holds the runtime version string
+  let rva_metadata = reader.u32(offset + 8)?;
+  let offset_metadata = self.offset(rva_metadata).ok_or(ah!("Invalid metadata address"))?;
+  if reader.u32(offset_metadata)? != 0x424a5342 { return Err(ah!("Invalid metadata signature")); } // if
+  let len_version = reader.u32(offset_metadata + 12)?.min(255) as usize;
+  self.clr_version = Some(reader.cstr(offset_metadata + 16, len_version)?);
+  Ok(())
+} // fn read_clr() }}}
+
+// fn read_version() {{{
+fn read_version(&mut self, reader : &mut Reader) -> anyhow::Result<()>
+{
+  let Some((offset_base, _)) = self.directory(DIRECTORY_RESOURCE) else { return Ok(()); };
+  // Entry of a resource directory, with the id if given, otherwise the first
+  let mut f_entry = |offset_dir : u64, opt_id : Option<u32>| -> anyhow::Result<u32>
+  {
+    let count = reader.u16(offset_dir + 12)? as u64 + reader.u16(offset_dir + 14)? as u64;
+    for i in 0..count.min(COUNT_ENTRIES_MAX as u64)
+    {
+      let name = reader.u32(offset_dir + 16 + i * 8)?;
+      if opt_id.is_none_or(|id| id == name) { return Ok(reader.u32(offset_dir + 20 + i * 8)?); } // if
+    } // for
+    Err(ah!("Resource not found"))
+  };
+  // Type, name and language levels, the high bit marks a subdirectory
+  let mut offset = f_entry(offset_base, Some(RESOURCE_VERSION))?;
+  for _ in 0..2
+  {
+    if offset & 0x80000000 == 0 { break; } // if
+    offset = f_entry(offset_base + (offset & 0x7fffffff) as u64, None)?;
+  } // for
+  if offset & 0x80000000 != 0 { return Err(ah!("Invalid resource tree")); } // if
+  let rva_data = reader.u32(offset_base + offset as u64)?;
+  let size_data = reader.u32(offset_base + offset as u64 + 4)?.min(SIZE_VERSION_MAX);
+  let offset_data = self.offset(rva_data).ok_or(ah!("Invalid resource address"))?;
+  let data = reader.bytes(offset_data, size_data as usize)?;
+  version_strings(&data, 0, data.len(), &mut self.version);
+  Ok(())
+} // fn read_version() }}}
+
+// pub fn open() {{{
+pub fn open(path_file : &PathBuf) -> anyhow::Result<Pe>
+{
+  let mut reader = Reader{ file: File::open(path_file)? };
+  if reader.bytes(0, 2)? != b"MZ" { return Err(ah!("Not an executable")); } // if
+  let offset_pe = reader.u32(0x3c)? as u64;
+  if reader.bytes(offset_pe, 4)? != b"PE\0\0" { return Err(ah!("Not a portable executable")); } // if
+  // File header
+  let offset_coff = offset_pe + 4;
+  let machine = match reader.u16(offset_coff)?
+  {
+    0x14c => Machine::X86,
+    0x8664 => Machine::X64,
+    0xaa64 => Machine::Arm64,
+    value => Machine::Other(value),
+  }; // match
+  let count_sections = reader.u16(offset_coff + 2)? as u64;
+  let size_optional = reader.u16(offset_coff + 16)? as u64;
+  let characteristics = reader.u16(offset_coff + 18)?;
+  // Optional header, pe32+ has larger fields before the data directories
+  let offset_optional = offset_coff + 20;
+  let is_pe32_plus = match reader.u16(offset_optional)?
+  {
+    0x10b => false,
+    0x20b => true,
+    value => return Err(ah!("Unknown optional header {:#x}", value)),
+  }; // match
+  let subsystem = match reader.u16(offset_optional + 68)?
+  {
+    2 => Subsystem::Gui,
+    3 => Subsystem::Console,
+    value => Subsystem::Other(value),
+  }; // match
+  let offset_count_directories = offset_optional + if is_pe32_plus { 108 } else { 92 };
+  let count_directories = reader.u32(offset_count_directories)?.min(16) as u64;
+  let directories = (0..count_directories)
+    .map(|i| Ok((reader.u32(offset_count_directories + 4 + i * 8)?, reader.u32(offset_count_directories + 8 + i * 8)?)))
+    .collect::<anyhow::Result<Vec<(u32,u32)>>>()?;
+  // Sections
+  let offset_sections = offset_optional + size_optional;
+  let sections = (0..count_sections.min(COUNT_ENTRIES_MAX as u64))
+    .map(|i|
+    {
+      let offset = offset_sections + i * 40;
+      Ok(Section
+      {
+        size_virtual: reader.u32(offset + 8)?,
+        rva: reader.u32(offset + 12)?,
+        size_raw: reader.u32(offset + 16)?,
+        offset: reader.u32(offset + 20)?,
+      })
+    })
+    .collect::<anyhow::Result<Vec<Section>>>()?;
+  let mut pe = Pe
+  {
+    machine,
+    subsystem,
+    is_dll: characteristics & 0x2000 != 0,
+    imports: vec![],
+    clr_version: None,
+    version: BTreeMap::new(),
+    sections,
+    directories,
+  };
+  // Tables are optional, a broken table does not invalidate the headers
+  let _ = pe.read_imports(&mut reader);
+  let _ = pe.read_clr(&mut reader);
+  let _ = pe.read_version(&mut reader);
+  Ok(pe)
+} // fn open() }}}
+
+// pub fn is_64() {{{
+pub fn is_64(&self) -> bool
+{
+  matches!(self.machine, Machine::X64 | Machine::Arm64)
+} // fn is_64() }}}
+
+} // impl Pe
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
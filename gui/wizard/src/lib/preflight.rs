@@ -0,0 +1,203 @@
+use std::
+{
+  ffi::CString,
+  fs,
+  os::unix::fs::PermissionsExt,
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+use crate::lib::format::format_size;
+
+// Free space below this prevents the build
+const SIZE_FREE_MIN : u64 = 4 << 30;
+// Free space below this is reported as a warning
+const SIZE_FREE_LOW : u64 = 20 << 30;
+
+// pub struct Report {{{
+// Result of the checks of a build directory
+#[derive(Debug, Clone, Default)]
+pub struct Report
+{
+  // Problems that prevent the build
+  pub blockers : Vec<String>,
+  // Problems that can cause failures or slowness
+  pub warnings : Vec<String>,
+  // Facts about the directory
+  pub info     : Vec<String>,
+} // struct Report }}}
+
+impl Report
+{
+
+// pub fn is_ok() {{{
+pub fn is_ok(&self) -> bool
+{
+  self.blockers.is_empty()
+} // fn is_ok() }}}
+
+// pub fn format() {{{
+pub fn format(&self) -> String
+{
+  let mut text = String::new();
+  let f_section = |text : &mut String, title : &str, lines : &Vec<String>|
+  {
+    if lines.is_empty() { return; } // if
+    text.push_str(&format!("{}:\n", title));
+    lines.iter().for_each(|e| text.push_str(&format!("  - {}\n", e)));
+  };
+  f_section(&mut text, "Blocking problems", &self.blockers);
+  f_section(&mut text, "Warnings", &self.warnings);
+  f_section(&mut text, "Details", &self.info);
+  if self.blockers.is_empty() && self.warnings.is_empty()
+  {
+    text.insert_str(0, "No problems found\n");
+  } // if
+  text
+} // fn format() }}}
+
+} // impl Report
+
+// Kinds of filesystems that can hold a build but are reported
+const FS_NO_LINKS : &str = "does not support symlinks and permissions";
+const FS_WINDOWS : &str = "is a Windows filesystem, permissions and case sensitivity depend on its mount options";
+const FS_SLOW : &str = "is network, memory or fuse backed, it can be slow or lack features";
+
+// fn fs_name() {{{
+// Filesystem name, whether it is a blocker (true) or a warning (false) and why
+fn fs_name(magic : i64) -> (&'static str, Option<(bool, &'static str)>)
+{
+  match magic
+  {
+    0x4d44                => ("vfat", Some((true, FS_NO_LINKS))),
+    0x2011bab0            => ("exfat", Some((true, FS_NO_LINKS))),
+    0x7366746e            => ("ntfs3", Some((false, FS_WINDOWS))),
+    0x5346544e            => ("ntfs", Some((false, FS_WINDOWS))),
+    0x65735546            => ("fuse", Some((false, FS_SLOW))),
+    0x6969                => ("nfs", Some((false, FS_SLOW))),
+    0x517b                => ("smb", Some((false, FS_SLOW))),
+    0xff534d42            => ("cifs", Some((false, FS_SLOW))),
+    0xfe534d42            => ("smb2", Some((false, FS_SLOW))),
+    0x01021994            => ("tmpfs", Some((false, FS_SLOW))),
+    0xef53                => ("ext4", None),
+    0x9123683e            => ("btrfs", None),
+    0x58465342            => ("xfs", None),
+    0xf2f52010            => ("f2fs", None),
+    0x2fc12fc1            => ("zfs", None),
+    0x794c7630            => ("overlayfs", None),
+    _                     => ("unknown", None),
+  } // match
+} // fn fs_name() }}}
+
+// fn check_fs() {{{
+fn check_fs(report : &mut Report, path_dir : &PathBuf) -> anyhow::Result<()>
+{
+  let c_path = CString::new(path_dir.string())?;
+  // Mount flags and free space
+  let mut stat_vfs : libc::statvfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat_vfs) } != 0
+  {
+    return Err(ah!("Could not query filesystem of '{}': {}", path_dir.string(), errno::errno()));
+  } // if
+  if stat_vfs.f_flag & libc::ST_RDONLY != 0
+  {
+    report.blockers.push("The filesystem is mounted as read-only".into());
+  } // if
+  if stat_vfs.f_flag & libc::ST_NOEXEC != 0
+  {
+    report.blockers.push("The filesystem is mounted with 'noexec', programs cannot run from it".into());
+  } // if
+  if stat_vfs.f_flag & libc::ST_NOSUID != 0
+  {
+    report.info.push("The filesystem is mounted with 'nosuid'".into());
+  } // if
+  let size_free = stat_vfs.f_bavail as u64 * stat_vfs.f_frsize as u64;
+  report.info.push(format!("Free space: {}", format_size(size_free)));
+  if size_free < SIZE_FREE_MIN
+  {
+    report.blockers.push(format!("Only {} free, at least {} are required", format_size(size_free), format_size(SIZE_FREE_MIN)));
+  } // if
+  else if size_free < SIZE_FREE_LOW
+  {
+    report.warnings.push(format!("Only {} free, large games may not fit", format_size(size_free)));
+  } // else if
+  // Filesystem type
+  let mut stat_fs : libc::statfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statfs(c_path.as_ptr(), &mut stat_fs) } != 0
+  {
+    return Err(ah!("Could not query filesystem type of '{}': {}", path_dir.string(), errno::errno()));
+  } // if
+  let (name, opt_concern) = fs_name(stat_fs.f_type as i64);
+  report.info.push(format!("Filesystem: {} ({:#x})", name, stat_fs.f_type));
+  match opt_concern
+  {
+    Some((true, reason)) => report.blockers.push(format!("The '{}' filesystem {}", name, reason)),
+    Some((false, reason)) => report.warnings.push(format!("The '{}' filesystem {}", name, reason)),
+    None => (),
+  } // match
+  Ok(())
+} // fn check_fs() }}}
+
+// fn check_features() {{{
+// Creates a symlink and runs a script in a scratch directory
+fn check_features(report : &mut Report, path_dir : &PathBuf) -> anyhow::Result<()>
+{
+  let path_dir_probe = path_dir.join(".preflight");
+  let _ = fs::remove_dir_all(&path_dir_probe);
+  if let Err(e) = fs::create_dir_all(&path_dir_probe)
+  {
+    report.blockers.push(format!("The directory is not writable: {}", e));
+    return Ok(());
+  } // if
+  // Symlinks
+  if let Err(e) = std::os::unix::fs::symlink("target", path_dir_probe.join("link"))
+  {
+    report.blockers.push(format!("Could not create a symbolic link: {}", e));
+  } // if
+  // Permissions and execution
+  let path_file_script = path_dir_probe.join("probe.sh");
+  fs::write(&path_file_script, "#!/bin/sh\nexit 0\n")?;
+  fs::set_permissions(&path_file_script, fs::Permissions::from_mode(0o755))?;
+  if fs::metadata(&path_file_script)?.permissions().mode() & 0o111 == 0
+  {
+    report.blockers.push("The filesystem does not keep the executable permission".into());
+  } // if
+  else
+  {
+    match std::process::Command::new(&path_file_script).status()
+    {
+      Ok(status) if status.success() => (),
+      Ok(status) => report.blockers.push(format!("Test program failed with {}", status)),
+      Err(e) => report.blockers.push(format!("Could not run a program from the directory: {}", e)),
+    } // match
+  } // else
+  let _ = fs::remove_dir_all(&path_dir_probe);
+  Ok(())
+} // fn check_features() }}}
+
+// pub fn check() {{{
+// Checks if the build directory can hold a build, it is created if it does not exist
+pub fn check(path_dir_build : &PathBuf) -> Report
+{
+  let mut report = Report::default();
+  if let Err(e) = fs::create_dir_all(path_dir_build)
+  {
+    report.blockers.push(format!("Could not create '{}': {}", path_dir_build.string(), e));
+    return report;
+  } // if
+  report.info.push(format!("Directory: {}", path_dir_build.string()));
+  if let Err(e) = check_fs(&mut report, path_dir_build)
+  {
+    report.warnings.push(e.to_string());
+  } // if
+  if let Err(e) = check_features(&mut report, path_dir_build)
+  {
+    report.warnings.push(format!("Could not complete the checks: {}", e));
+  } // if
+  report
+} // fn check() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,101 @@
+use std::
+{
+  ffi::CStr,
+  fs::{File,OpenOptions},
+  io::Write,
+  os::fd::{AsRawFd,FromRawFd},
+  os::unix::fs::OpenOptionsExt,
+  os::unix::process::CommandExt,
+  process::{Child,Command},
+};
+
+use anyhow::anyhow as ah;
+
+// pub struct Pty {{{
+// Master side of a pseudo terminal, the child process owns the slave side
+pub struct Pty
+{
+  master : File,
+} // struct Pty }}}
+
+impl Pty
+{
+
+// pub fn spawn() {{{
+// Runs cmd with stdin, stdout and stderr attached to a new pseudo terminal of rows x cols
+pub fn spawn(mut cmd : Command, rows : u16, cols : u16) -> anyhow::Result<(Pty, Child)>
+{
+  // Open master
+  let fd_master = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC) };
+  if fd_master < 0 { return Err(ah!("Could not open pseudo terminal: {}", errno::errno())); } // if
+  let master = unsafe { File::from_raw_fd(fd_master) };
+  if unsafe { libc::grantpt(fd_master) } != 0 || unsafe { libc::unlockpt(fd_master) } != 0
+  {
+    return Err(ah!("Could not unlock pseudo terminal: {}", errno::errno()));
+  } // if
+  // Open slave
+  let mut buf_name = [0 as libc::c_char; 128];
+  if unsafe { libc::ptsname_r(fd_master, buf_name.as_mut_ptr(), buf_name.len()) } != 0
+  {
+    return Err(ah!("Could not get pseudo terminal name: {}", errno::errno()));
+  } // if
+  let path_slave = unsafe { CStr::from_ptr(buf_name.as_ptr()) }.to_string_lossy().to_string();
+  let slave = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .custom_flags(libc::O_NOCTTY)
+    .open(&path_slave)?;
+  let pty = Pty{ master };
+  pty.resize(rows, cols)?;
+  // Attach child to slave
+  cmd.stdin(slave.try_clone()?)
+    .stdout(slave.try_clone()?)
+    .stderr(slave)
+    .env("TERM", "xterm");
+  unsafe
+  {
+    cmd.pre_exec(||
+    {
+      // New session with the slave as the controlling terminal
+      if libc::setsid() < 0 { return Err(std::io::Error::last_os_error()); } // if
+      if libc::ioctl(0, libc::TIOCSCTTY, 0) < 0 { return Err(std::io::Error::last_os_error()); } // if
+      Ok(())
+    });
+  } // unsafe
+  let child = cmd.spawn()?;
+  // Drop the copies of the slave held by cmd, so reads fail once the child exits
+  drop(cmd);
+  Ok((pty, child))
+} // fn spawn() }}}
+
+// pub fn reader() {{{
+pub fn reader(&self) -> anyhow::Result<File>
+{
+  Ok(self.master.try_clone()?)
+} // fn reader() }}}
+
+// pub fn write() {{{
+// Sends input to the process, as typed in a terminal
+pub fn write(&self, data : &[u8]) -> anyhow::Result<()>
+{
+  let mut master = &self.master;
+  master.write_all(data)?;
+  master.flush()?;
+  Ok(())
+} // fn write() }}}
+
+// pub fn resize() {{{
+// Sets the terminal size, the process receives SIGWINCH
+pub fn resize(&self, rows : u16, cols : u16) -> anyhow::Result<()>
+{
+  let size = libc::winsize{ ws_row: rows, ws_col: cols, ws_xpixel: 0, ws_ypixel: 0 };
+  if unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size) } < 0
+  {
+    return Err(ah!("Could not resize pseudo terminal: {}", errno::errno()));
+  } // if
+  Ok(())
+} // fn resize() }}}
+
+} // impl Pty
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,237 @@
+use std::
+{
+  fs,
+  path::PathBuf,
+};
+
+use shared::std::PathBufExt;
+
+use crate::lib::format::format_size;
+
+// Directories of 'drive_c' that do not contain installations
+const DIRS_SYSTEM : [&str; 3] = ["windows", "users", "ProgramData"];
+
+// pub struct Category {{{
+// Content of the prefix that is not needed to run the game. Patterns are case insensitive, '*'
+// matches inside of a component, patterns without '/' match the name of content inside of an
+// installation directory, e.g., 'Program Files/<game>/Redist', and patterns with '/' match the path
+// relative to 'drive_c'.
+pub struct Category
+{
+  pub label       : &'static str,
+  pub description : &'static str,
+  dirs            : &'static [&'static str],
+  files           : &'static [&'static str],
+} // struct Category }}}
+
+pub static CATEGORIES : [Category; 4] =
+[
+  Category
+  {
+    label: "Redistributable installers",
+    description: "Runtime installers shipped with the game, the runtimes are already installed",
+    dirs: &["_CommonRedist", "__Installer", "Redist", "Redistributables", "vcredist", "DirectX"],
+    files: &["vcredist*.exe", "vc_redist*.exe", "dxsetup.exe", "dxwebsetup.exe", "directx*redist*.exe"
+      , "dotnetfx*.exe", "ndp4*.exe", "oalinst.exe", "physx*.msi", "xnafx*.msi"],
+  },
+  Category
+  {
+    label: "Installer caches",
+    description: "Copies of installation packages kept for repair and removal",
+    dirs: &["windows/Installer", "ProgramData/Package Cache", "windows/Downloaded Installations"],
+    files: &[],
+  },
+  Category
+  {
+    label: "Temporary files",
+    description: "Leftovers of installers and of test runs",
+    dirs: &["windows/temp", "users/*/Temp", "users/*/AppData/Local/Temp"],
+    files: &[],
+  },
+  Category
+  {
+    label: "Crash dumps",
+    description: "Memory dumps written when a program crashes",
+    dirs: &["users/*/AppData/Local/CrashDumps"],
+    files: &["*.dmp", "*.mdmp"],
+  },
+];
+
+// pub struct Entry {{{
+// A file or directory that can be removed, relative to 'drive_c'
+#[derive(Debug, Clone)]
+pub struct Entry
+{
+  pub path        : PathBuf,
+  pub is_dir      : bool,
+  pub size        : u64,
+  pub count_files : u64,
+} // struct Entry }}}
+
+// pub struct Group {{{
+// Entries of a category, the index is in 'CATEGORIES'
+#[derive(Debug, Clone)]
+pub struct Group
+{
+  pub index   : usize,
+  pub entries : Vec<Entry>,
+} // struct Group }}}
+
+impl Group
+{
+
+// pub fn category() {{{
+pub fn category(&self) -> &'static Category
+{
+  &CATEGORIES[self.index]
+} // fn category() }}}
+
+// pub fn size() {{{
+pub fn size(&self) -> u64
+{
+  self.entries.iter().map(|e| e.size).sum()
+} // fn size() }}}
+
+// pub fn summary() {{{
+pub fn summary(&self) -> String
+{
+  format!("{}: {} in {} item(s)", self.category().label, format_size(self.size()), self.entries.len())
+} // fn summary() }}}
+
+} // impl Group
+
+// fn glob() {{{
+// Matches a component against a pattern with '*' wildcards, ignoring case
+fn glob(pattern : &str, text : &str) -> bool
+{
+  let pattern = pattern.to_lowercase();
+  let text = text.to_lowercase();
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or_default();
+  let Some(mut rest) = text.strip_prefix(first) else { return false; };
+  let parts : Vec<&str> = parts.collect();
+  for (index, part) in parts.iter().enumerate()
+  {
+    // The last part must be at the end
+    if index == parts.len() - 1 { return rest.ends_with(part); } // if
+    match rest.find(part)
+    {
+      Some(position) => rest = &rest[position + part.len()..],
+      None => return false,
+    } // match
+  } // for
+  rest.is_empty()
+} // fn glob() }}}
+
+// fn matches() {{{
+fn matches(pattern : &str, path_relative : &PathBuf) -> bool
+{
+  let components : Vec<String> = path_relative.components().map(|e| e.as_os_str().to_string_lossy().to_string()).collect();
+  if ! pattern.contains('/')
+  {
+    // Names such as 'Redist' are common, so only content below the directory of an installation
+    // matches, e.g., 'Program Files/<game>/Redist' but not 'Program Files/Redist'
+    let is_system = components.first().is_some_and(|e| DIRS_SYSTEM.iter().any(|d| e.eq_ignore_ascii_case(d)));
+    return ! is_system && components.len() >= 3 && components.last().is_some_and(|e| glob(pattern, e));
+  } // if
+  let patterns : Vec<&str> = pattern.split('/').collect();
+  components.len() == patterns.len() && patterns.iter().zip(components.iter()).all(|(p, c)| glob(p, c))
+} // fn matches() }}}
+
+// pub fn analyze() {{{
+// Removable content of the prefix of the project, the first category that matches a path wins and
+// the content of matched directories is not visited again
+pub fn analyze(path_dir_project : &PathBuf) -> Vec<Group>
+{
+  let path_dir_drive = path_dir_project.join("wine").join("drive_c");
+  let mut groups : Vec<Group> = (0..CATEGORIES.len()).map(|index| Group{ index, entries: vec![] }).collect();
+  let mut iter = walkdir::WalkDir::new(&path_dir_drive).min_depth(1).follow_links(false).into_iter();
+  while let Some(result) = iter.next()
+  {
+    let Ok(entry) = result else { continue; };
+    let Ok(path_relative) = entry.path().strip_prefix(&path_dir_drive).map(|e| e.to_path_buf()) else { continue; };
+    let file_type = entry.file_type();
+    let opt_index = CATEGORIES.iter().position(|category|
+    {
+      (file_type.is_dir() && category.dirs.iter().any(|e| matches(e, &path_relative)))
+      || (file_type.is_file() && category.files.iter().any(|e| matches(e, &path_relative)))
+    });
+    let Some(index) = opt_index else { continue; };
+    let (size, count_files) = if file_type.is_dir()
+    {
+      iter.skip_current_dir();
+      let files : Vec<u64> = walkdir::WalkDir::new(entry.path())
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|e| e.is_file())
+        .map(|e| e.len())
+        .collect();
+      (files.iter().sum(), files.len() as u64)
+    }
+    else
+    {
+      (entry.metadata().map(|e| e.len()).unwrap_or(0), 1)
+    }; // if
+    // Empty directories are not worth listing
+    if count_files == 0 { continue; } // if
+    groups[index].entries.push(Entry{ path: path_relative, is_dir: file_type.is_dir(), size, count_files });
+  } // while
+  groups.iter_mut().for_each(|e| e.entries.sort_by(|a, b| b.size.cmp(&a.size)));
+  groups
+} // fn analyze() }}}
+
+// pub fn report() {{{
+// What removing the selected categories would delete, without deleting anything
+pub fn report(groups : &[Group], selected : &[usize]) -> String
+{
+  let groups : Vec<&Group> = groups.iter().filter(|e| selected.contains(&e.index)).collect();
+  let mut text = String::from("Dry run, nothing was removed\n");
+  for group in &groups
+  {
+    text.push_str(&format!("\n{}\n", group.summary()));
+    for entry in &group.entries
+    {
+      text.push_str(&format!("  {:>12}  {}{}\n"
+        , format_size(entry.size)
+        , entry.path.string()
+        , if entry.is_dir { format!("/ ({} files)", entry.count_files) } else { String::new() }
+      ));
+    } // for
+  } // for
+  let size : u64 = groups.iter().map(|e| e.size()).sum();
+  text.push_str(&format!("\nTotal: {}\n", format_size(size)));
+  text
+} // fn report() }}}
+
+// pub fn remove() {{{
+// Removes the entries of the selected categories and returns the freed size. The contents of
+// directories are removed but the directories are kept, since wine expects some of them to exist.
+pub fn remove(path_dir_project : &PathBuf, groups : &[Group], selected : &[usize]) -> anyhow::Result<u64>
+{
+  let path_dir_drive = path_dir_project.join("wine").join("drive_c");
+  let mut size_freed = 0;
+  for entry in groups.iter().filter(|e| selected.contains(&e.index)).flat_map(|e| e.entries.iter())
+  {
+    let path = path_dir_drive.join(&entry.path);
+    if entry.is_dir
+    {
+      for child in fs::read_dir(&path)?.filter_map(|e| e.ok())
+      {
+        match child.file_type()?.is_dir()
+        {
+          true => fs::remove_dir_all(child.path())?,
+          false => fs::remove_file(child.path())?,
+        } // match
+      } // for
+    } // if
+    else
+    {
+      fs::remove_file(&path)?;
+    } // else
+    size_freed += entry.size;
+  } // for
+  Ok(size_freed)
+} // fn remove() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
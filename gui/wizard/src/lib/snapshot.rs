@@ -0,0 +1,233 @@
+use std::
+{
+  fs,
+  fs::File,
+  os::fd::AsRawFd,
+  os::unix::fs::MetadataExt,
+  path::{Path,PathBuf},
+  time::{SystemTime,UNIX_EPOCH,Duration},
+};
+
+use anyhow::anyhow as ah;
+use serde::{Deserialize, Serialize};
+
+use shared::std::PathBufExt;
+
+use crate::db;
+use crate::lib;
+
+// Snapshots kept per project, the oldest are erased
+const COUNT_SNAPSHOTS_MAX : usize = 20;
+// _IOW(0x94, 9, int), clones the extents of a file on copy-on-write filesystems
+const FICLONE : libc::c_ulong = 0x40049409;
+
+// pub struct Snapshot {{{
+// A copy of the wine prefix of a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot
+{
+  // Unix time of the creation
+  pub time         : u64,
+  pub label        : String,
+  // How the files were stored
+  pub count_linked : u64,
+  pub count_cloned : u64,
+  pub count_copied : u64,
+  // Bytes that are not shared with the previous snapshot
+  pub size_copied  : u64,
+  #[serde(skip)]
+  pub path_dir     : PathBuf,
+} // struct Snapshot }}}
+
+impl Snapshot
+{
+
+// pub fn summary() {{{
+// One line description for lists
+pub fn summary(&self) -> String
+{
+  let (year, month, day, hour, min, sec) = lib::log::datetime(UNIX_EPOCH + Duration::from_secs(self.time));
+  format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}  {}", year, month, day, hour, min, sec, self.label)
+} // fn summary() }}}
+
+// pub fn details() {{{
+pub fn details(&self) -> String
+{
+  let mut text = String::new();
+  text.push_str(&format!("Snapshot: {}\n", self.summary()));
+  text.push_str(&format!("Location: {}\n", self.path_dir.string()));
+  text.push_str(&format!("Files shared with the previous snapshot: {}\n", self.count_linked));
+  text.push_str(&format!("Files cloned: {}\n", self.count_cloned));
+  text.push_str(&format!("Files copied: {}\n", self.count_copied));
+  text.push_str(&format!("New data: {}\n", lib::format::format_size(self.size_copied)));
+  text
+} // fn details() }}}
+
+} // impl Snapshot
+
+// fn clone_file() {{{
+// Shares the data of the file with a reflink, falls back to a copy, returns true if cloned. The
+// copy keeps the modification time and permissions of the source.
+fn clone_file(path_src : &Path, path_dst : &Path, meta_src : &fs::Metadata) -> anyhow::Result<bool>
+{
+  let mut file_src = File::open(path_src)?;
+  let mut file_dst = File::create(path_dst)?;
+  let is_cloned = unsafe { libc::ioctl(file_dst.as_raw_fd(), FICLONE, file_src.as_raw_fd()) } == 0;
+  if ! is_cloned
+  {
+    std::io::copy(&mut file_src, &mut file_dst)?;
+  } // if
+  file_dst.set_modified(meta_src.modified()?)?;
+  file_dst.set_permissions(meta_src.permissions())?;
+  Ok(is_cloned)
+} // fn clone_file() }}}
+
+// fn is_same() {{{
+// Files keep the size and modification time of their source
+fn is_same(meta_src : &fs::Metadata, path_prev : &Path) -> bool
+{
+  fs::symlink_metadata(path_prev).is_ok_and(|meta_prev|
+    meta_prev.is_file()
+    && meta_prev.len() == meta_src.len()
+    && meta_prev.mtime() == meta_src.mtime()
+    && meta_prev.mtime_nsec() == meta_src.mtime_nsec()
+  )
+} // fn is_same() }}}
+
+// pub fn replicate() {{{
+// Replicates a directory tree, symbolic links are kept as links. Unchanged files are hard linked
+// to the same file of 'opt_path_dir_prev', which is never modified in place since it belongs to a
+// snapshot. Other files are cloned or copied.
+pub fn replicate(path_dir_src : &Path, path_dir_dst : &Path, opt_path_dir_prev : Option<&Path>, snapshot : &mut Snapshot) -> anyhow::Result<()>
+{
+  for entry in walkdir::WalkDir::new(path_dir_src).follow_links(false)
+  {
+    let entry = entry?;
+    let path_rel = entry.path().strip_prefix(path_dir_src)?;
+    let path_dst = path_dir_dst.join(path_rel);
+    let meta = entry.path().symlink_metadata()?;
+    if meta.is_dir()
+    {
+      fs::create_dir_all(&path_dst)?;
+    } // if
+    else if meta.is_symlink()
+    {
+      std::os::unix::fs::symlink(fs::read_link(entry.path())?, &path_dst)?;
+    } // else if
+    else if meta.is_file()
+    {
+      if let Some(path_dir_prev) = opt_path_dir_prev
+      && is_same(&meta, &path_dir_prev.join(path_rel))
+      && fs::hard_link(path_dir_prev.join(path_rel), &path_dst).is_ok()
+      {
+        snapshot.count_linked += 1;
+        continue;
+      } // if
+      if clone_file(entry.path(), &path_dst, &meta)? { snapshot.count_cloned += 1; } // if
+      else { snapshot.count_copied += 1; } // else
+      snapshot.size_copied += meta.len();
+    } // else if
+  } // for
+  Ok(())
+} // fn replicate() }}}
+
+// fn path_dir_wine() {{{
+// Prefix of the current project
+fn path_dir_wine() -> anyhow::Result<PathBuf>
+{
+  Ok(db::project::current()?.get_dir_self()?.join("wine"))
+} // fn path_dir_wine() }}}
+
+// fn path_dir_snapshots() {{{
+// Snapshots are in the build directory, so they are not packaged
+fn path_dir_snapshots() -> anyhow::Result<PathBuf>
+{
+  let global = db::global::read()?;
+  Ok(global.path_dir_build.join("snapshot").join(&global.project))
+} // fn path_dir_snapshots() }}}
+
+// pub fn list() {{{
+// Snapshots of the current project, newest first
+pub fn list() -> anyhow::Result<Vec<Snapshot>>
+{
+  let path_dir_snapshots = path_dir_snapshots()?;
+  if ! path_dir_snapshots.exists() { return Ok(vec![]); } // if
+  let mut snapshots : Vec<Snapshot> = fs::read_dir(&path_dir_snapshots)?
+    .filter_map(|e| e.ok())
+    .filter_map(|e|
+    {
+      let mut snapshot : Snapshot = serde_json::from_reader(File::open(e.path().join("snapshot.json")).ok()?).ok()?;
+      snapshot.path_dir = e.path();
+      Some(snapshot)
+    })
+    .collect();
+  snapshots.sort_by(|a, b| b.time.cmp(&a.time).then(b.path_dir.cmp(&a.path_dir)));
+  Ok(snapshots)
+} // fn list() }}}
+
+// pub fn create() {{{
+// Takes a snapshot of the wine prefix of the current project
+pub fn create(label : &str) -> anyhow::Result<Snapshot>
+{
+  let path_dir_wine = path_dir_wine()?;
+  if ! path_dir_wine.exists() { return Err(ah!("Wine prefix does not exist")); } // if
+  let snapshots = list()?;
+  let time = SystemTime::now();
+  let (year, month, day, hour, min, sec) = lib::log::datetime(time);
+  let mut snapshot = Snapshot
+  {
+    time: time.duration_since(UNIX_EPOCH)?.as_secs(),
+    label: label.to_string(),
+    path_dir: path_dir_snapshots()?.join(format!("{:04}{:02}{:02}-{:02}{:02}{:02}-{}"
+      , year, month, day, hour, min, sec, time.duration_since(UNIX_EPOCH)?.subsec_nanos())),
+    ..Default::default()
+  };
+  fs::create_dir_all(&snapshot.path_dir)?;
+  let path_dir_prev = snapshots.first().map(|e| e.path_dir.join("wine"));
+  if let Err(e) = replicate(&path_dir_wine, &snapshot.path_dir.join("wine"), path_dir_prev.as_deref(), &mut snapshot)
+  {
+    let _ = fs::remove_dir_all(&snapshot.path_dir);
+    return Err(ah!("Could not create snapshot: {}", e));
+  } // if
+  // The description marks the snapshot as complete
+  serde_json::to_writer_pretty(File::create(snapshot.path_dir.join("snapshot.json"))?, &snapshot)?;
+  // Erase the oldest snapshots
+  for snapshot_old in snapshots.iter().skip(COUNT_SNAPSHOTS_MAX - 1)
+  {
+    let _ = erase(snapshot_old);
+  } // for
+  Ok(snapshot)
+} // fn create() }}}
+
+// pub fn restore() {{{
+// Replaces the wine prefix with the contents of the snapshot, the current prefix is snapshot first
+pub fn restore(snapshot : &Snapshot) -> anyhow::Result<()>
+{
+  let path_dir_wine = path_dir_wine()?;
+  let path_dir_restore = path_dir_wine.with_extension("restore");
+  let path_dir_old = path_dir_wine.with_extension("old");
+  // Files are never hard linked into the prefix, wine modifies them in place
+  let _ = fs::remove_dir_all(&path_dir_restore);
+  replicate(&snapshot.path_dir.join("wine"), &path_dir_restore, None, &mut Snapshot::default())?;
+  // The restored snapshot could be the oldest, which is erased by a new snapshot
+  if path_dir_wine.exists()
+  {
+    create(&format!("Before restoring '{}'", snapshot.label))?;
+  } // if
+  let _ = fs::remove_dir_all(&path_dir_old);
+  if path_dir_wine.exists() { fs::rename(&path_dir_wine, &path_dir_old)?; } // if
+  fs::rename(&path_dir_restore, &path_dir_wine)?;
+  let _ = fs::remove_dir_all(&path_dir_old);
+  Ok(())
+} // fn restore() }}}
+
+// pub fn erase() {{{
+pub fn erase(snapshot : &Snapshot) -> anyhow::Result<()>
+{
+  // Remove the description first, so a partial removal is not listed
+  fs::remove_file(snapshot.path_dir.join("snapshot.json"))?;
+  fs::remove_dir_all(&snapshot.path_dir)?;
+  Ok(())
+} // fn erase() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
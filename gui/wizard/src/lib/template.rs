@@ -0,0 +1,165 @@
+use std::
+{
+  fs,
+  fs::File,
+  path::PathBuf,
+  time::{SystemTime,UNIX_EPOCH,Duration},
+};
+
+use anyhow::anyhow as ah;
+use serde::{Deserialize, Serialize};
+
+use shared::std::PathBufExt;
+
+use crate::db;
+use crate::lib;
+
+// pub struct Template {{{
+// A configured wine prefix that new projects are initialized from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Template
+{
+  pub name      : String,
+  // Unix time of the creation
+  pub time      : u64,
+  // Project the prefix was saved from
+  pub project   : String,
+  // Winetricks verbs installed into the prefix
+  pub verbs     : Vec<String>,
+  pub dist_wine : String,
+  // Size of the files, cloned files share their data with the prefix
+  pub size      : u64,
+  #[serde(skip)]
+  pub path_dir  : PathBuf,
+} // struct Template }}}
+
+impl Template
+{
+
+// pub fn summary() {{{
+pub fn summary(&self) -> String
+{
+  format!("{}  ({} verbs)", self.name, self.verbs.len())
+} // fn summary() }}}
+
+// pub fn details() {{{
+pub fn details(&self) -> String
+{
+  let (year, month, day, hour, min, sec) = lib::log::datetime(UNIX_EPOCH + Duration::from_secs(self.time));
+  let mut text = String::new();
+  text.push_str(&format!("Template: {}\n", self.name));
+  text.push_str(&format!("Created: {:04}-{:02}-{:02} {:02}:{:02}:{:02}\n", year, month, day, hour, min, sec));
+  text.push_str(&format!("Saved from project: {}\n", self.project));
+  text.push_str(&format!("Wine distribution: {}\n", self.dist_wine));
+  text.push_str(&format!("Location: {}\n", self.path_dir.string()));
+  text.push_str(&format!("Size: {}\n", lib::format::format_size(self.size)));
+  text.push_str("Winetricks verbs:\n");
+  if self.verbs.is_empty() { text.push_str("  None\n"); } // if
+  self.verbs.iter().for_each(|e| text.push_str(&format!("  {}\n", e)));
+  text
+} // fn details() }}}
+
+} // impl Template
+
+// fn path_dir_templates() {{{
+// Templates are in the build directory, so they are shared by the projects and not packaged
+fn path_dir_templates() -> anyhow::Result<PathBuf>
+{
+  Ok(db::global::read()?.path_dir_build.join("template").join("wine"))
+} // fn path_dir_templates() }}}
+
+// pub fn list() {{{
+// Templates sorted by name
+pub fn list() -> anyhow::Result<Vec<Template>>
+{
+  let path_dir_templates = path_dir_templates()?;
+  if ! path_dir_templates.exists() { return Ok(vec![]); } // if
+  let mut templates : Vec<Template> = fs::read_dir(&path_dir_templates)?
+    .filter_map(|e| e.ok())
+    .filter_map(|e|
+    {
+      let mut template : Template = serde_json::from_reader(File::open(e.path().join("template.json")).ok()?).ok()?;
+      template.path_dir = e.path();
+      Some(template)
+    })
+    .collect();
+  templates.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+  Ok(templates)
+} // fn list() }}}
+
+// pub fn save() {{{
+// Saves the wine prefix of the current project as a template, a template with the same name is
+// replaced. The files are cloned when the filesystem supports it.
+pub fn save(name : &str) -> anyhow::Result<Template>
+{
+  let name = name.trim();
+  if name.is_empty() || name.starts_with('.') || name.contains('/')
+  {
+    return Err(ah!("Invalid template name '{}'", name));
+  } // if
+  let global = db::global::read()?;
+  let path_dir_project = db::global::get_current_project()?.path_dir_project;
+  let path_dir_wine = path_dir_project.join("wine");
+  if ! path_dir_wine.exists() { return Err(ah!("Wine prefix does not exist")); } // if
+  let mut verbs : Vec<String> = lib::winetricks::installed(&path_dir_project).into_iter().collect();
+  verbs.sort();
+  let mut template = Template
+  {
+    name: name.to_string(),
+    time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+    project: global.project.clone(),
+    verbs,
+    dist_wine: global.dist_wine.clone(),
+    size: 0,
+    path_dir: path_dir_templates()?.join(name),
+  };
+  // Replicate next to the template, so a failure keeps the previous one. Names do not start with
+  // '.', so the temporary directory is never listed.
+  let path_dir_tmp = path_dir_templates()?.join(format!(".{}.tmp", name));
+  let _ = fs::remove_dir_all(&path_dir_tmp);
+  fs::create_dir_all(&path_dir_tmp)?;
+  let mut snapshot = lib::snapshot::Snapshot::default();
+  if let Err(e) = lib::snapshot::replicate(&path_dir_wine, &path_dir_tmp.join("wine"), None, &mut snapshot)
+  {
+    let _ = fs::remove_dir_all(&path_dir_tmp);
+    return Err(ah!("Could not save template: {}", e));
+  } // if
+  template.size = snapshot.size_copied;
+  // The description marks the template as complete
+  serde_json::to_writer_pretty(File::create(path_dir_tmp.join("template.json"))?, &template)?;
+  if template.path_dir.exists() { erase(&template)?; } // if
+  fs::rename(&path_dir_tmp, &template.path_dir)?;
+  Ok(template)
+} // fn save() }}}
+
+// pub fn apply() {{{
+// Initializes the wine prefix of the current project from a template, an existing prefix is
+// snapshot first. Files are never hard linked into the prefix, wine modifies them in place.
+pub fn apply(template : &Template) -> anyhow::Result<()>
+{
+  let path_dir_wine = db::global::get_current_project()?.path_dir_project.join("wine");
+  let path_dir_template = path_dir_wine.with_extension("template");
+  let path_dir_old = path_dir_wine.with_extension("old");
+  let _ = fs::remove_dir_all(&path_dir_template);
+  lib::snapshot::replicate(&template.path_dir.join("wine"), &path_dir_template, None, &mut lib::snapshot::Snapshot::default())?;
+  if path_dir_wine.exists()
+  {
+    lib::snapshot::create(&format!("Before applying template '{}'", template.name))?;
+  } // if
+  let _ = fs::remove_dir_all(&path_dir_old);
+  if path_dir_wine.exists() { fs::rename(&path_dir_wine, &path_dir_old)?; } // if
+  fs::rename(&path_dir_template, &path_dir_wine)?;
+  let _ = fs::remove_dir_all(&path_dir_old);
+  Ok(())
+} // fn apply() }}}
+
+// pub fn erase() {{{
+pub fn erase(template : &Template) -> anyhow::Result<()>
+{
+  // Remove the description first, so a partial removal is not listed
+  fs::remove_file(template.path_dir.join("template.json"))?;
+  fs::remove_dir_all(&template.path_dir)?;
+  Ok(())
+} // fn erase() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+use shared::wine::registry::{Registry,Data};
+
+use crate::lib;
+use crate::gameimage;
+
+// Keys with the installed programs, for 64-bit and 32-bit installers
+const KEYS_UNINSTALL : [&str; 2] =
+[
+  "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\",
+  "Software\\Wow6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\",
+];
+
+// pub struct Program {{{
+// A program installed into the wine prefix, as registered by its installer
+#[derive(Debug, Clone, Default)]
+pub struct Program
+{
+  // Name of the key
+  pub id        : String,
+  pub name      : String,
+  pub publisher : Option<String>,
+  pub version   : Option<String>,
+  // Windows paths and commands
+  pub location  : Option<String>,
+  pub icon      : Option<String>,
+  pub uninstall : Option<String>,
+  // Size in KiB
+  pub size      : Option<u32>,
+  // Registered for the user instead of the machine
+  pub is_user   : bool,
+} // struct Program }}}
+
+// fn command_path() {{{
+// Path of the executable of a command, e.g., '"C:\game\unins000.exe" /SILENT' or 'C:\game\game.exe,0'
+fn command_path(command : &str) -> String
+{
+  let command = command.trim();
+  if let Some(rest) = command.strip_prefix('"')
+  {
+    return rest.split('"').next().unwrap_or_default().to_string();
+  } // if
+  if let Some(index) = command.to_ascii_lowercase().find(".exe")
+  {
+    return command[..index+4].to_string();
+  } // if
+  command.split(',').next().unwrap_or_default().to_string()
+} // fn command_path() }}}
+
+impl Program
+{
+
+// pub fn summary() {{{
+pub fn summary(&self) -> String
+{
+  match &self.version
+  {
+    Some(version) => format!("{} {}", self.name, version),
+    None => self.name.clone(),
+  } // match
+} // fn summary() }}}
+
+// pub fn details() {{{
+pub fn details(&self) -> String
+{
+  let f_value = |e : &Option<String>| e.clone().unwrap_or("-".to_string());
+  let mut text = String::new();
+  text.push_str(&format!("Name: {}\n", self.name));
+  text.push_str(&format!("Publisher: {}\n", f_value(&self.publisher)));
+  text.push_str(&format!("Version: {}\n", f_value(&self.version)));
+  text.push_str(&format!("Install location: {}\n", f_value(&self.location)));
+  text.push_str(&format!("Estimated size: {}\n", self.size
+    .map(|e| format!("{:.2} MiB", e as f64 / 1024.0))
+    .unwrap_or("-".to_string())
+  ));
+  text.push_str(&format!("Uninstall command: {}\n", f_value(&self.uninstall)));
+  text.push_str(&format!("Registered for: {}\n", if self.is_user { "user" } else { "machine" }));
+  text.push_str(&format!("Key: {}\n", self.id));
+  text
+} // fn details() }}}
+
+// pub fn path_dir() {{{
+// Installation directory, from the location or from the directories of the icon and uninstaller
+pub fn path_dir(&self, path_dir_project : &PathBuf) -> Option<PathBuf>
+{
+  let path_dir_prefix = path_dir_project.join("wine");
+  if let Some(location) = &self.location
+  && let Some(path_dir) = lib::lnk::to_unix(&path_dir_prefix, location.trim_end_matches('\\'))
+  && path_dir.is_dir()
+  {
+    return Some(path_dir);
+  } // if
+  [&self.icon, &self.uninstall].into_iter()
+    .flatten()
+    .filter_map(|e| lib::lnk::to_unix(&path_dir_prefix, &command_path(e)))
+    .find_map(|e| e.parent().map(|e| e.to_path_buf()).filter(|e| e.is_dir()))
+} // fn path_dir() }}}
+
+// pub fn executable() {{{
+// Likely main executable, relative to the project directory. The icon is often the game itself,
+// otherwise the executables of the installation directory are ranked.
+pub fn executable(&self, path_dir_project : &PathBuf) -> Option<PathBuf>
+{
+  let path_dir_prefix = path_dir_project.join("wine");
+  let f_relative = |e : &PathBuf| e.strip_prefix(path_dir_project).ok().map(|e| e.to_path_buf());
+  let f_is_exe = |e : &PathBuf| e.is_file() && e.extension().is_some_and(|e| e.eq_ignore_ascii_case("exe"));
+  if let Some(icon) = &self.icon
+  && let Some(path_file_icon) = lib::lnk::to_unix(&path_dir_prefix, &command_path(icon))
+  && f_is_exe(&path_file_icon)
+  && let Some(path_file_icon) = f_relative(&path_file_icon)
+  && lib::executable::inspect(path_dir_project, &path_file_icon).score > 0
+  {
+    return Some(path_file_icon);
+  } // if
+  let executables : Vec<PathBuf> = walkdir::WalkDir::new(self.path_dir(path_dir_project)?)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .map(|e| e.path().to_path_buf())
+    .filter(f_is_exe)
+    .filter_map(|e| f_relative(&e))
+    .collect();
+  lib::executable::rank(path_dir_project, executables).into_iter().next().map(|e| e.path_file)
+} // fn executable() }}}
+
+// pub fn uninstall() {{{
+// Runs the uninstall command in the prefix, through 'cmd' which parses its arguments
+pub fn uninstall(&self) -> anyhow::Result<()>
+{
+  let command = self.uninstall.clone().ok_or(ah!("'{}' has no uninstall command", self.name))?;
+  gameimage::install::wine(vec!["cmd".into(), "/c".into(), command])
+} // fn uninstall() }}}
+
+} // impl Program
+
+// fn read() {{{
+// Programs of a registry file of the prefix
+fn read(path_file_reg : &PathBuf, is_user : bool) -> anyhow::Result<Vec<Program>>
+{
+  if ! path_file_reg.exists() { return Ok(vec![]); } // if
+  let registry = Registry::open(path_file_reg)?;
+  Ok(registry.keys.iter().filter_map(|key|
+  {
+    let id = KEYS_UNINSTALL.iter().find_map(|prefix|
+    {
+      let len = prefix.len();
+      (key.path.len() > len && key.path.get(..len).is_some_and(|e| e.eq_ignore_ascii_case(prefix)))
+        .then(|| key.path[len..].to_string())
+    })?;
+    // Sub keys are not programs
+    if id.contains('\\') { return None; } // if
+    let f_text = |name : &str| key.get(name)
+      .map(|e| Data::decode(e).text())
+      .filter(|e| ! e.trim().is_empty());
+    let size = key.get("EstimatedSize").and_then(|e| match Data::decode(e)
+    {
+      Data::Dword(value) => Some(value),
+      _ => None,
+    }); // match
+    Some(Program
+    {
+      name: f_text("DisplayName").unwrap_or(id.clone()),
+      publisher: f_text("Publisher"),
+      version: f_text("DisplayVersion"),
+      location: f_text("InstallLocation"),
+      icon: f_text("DisplayIcon"),
+      uninstall: f_text("UninstallString"),
+      size,
+      is_user,
+      id,
+    })
+  }).collect())
+} // fn read() }}}
+
+// pub fn list() {{{
+// Programs installed into the wine prefix of the project, sorted by name. Entries without a name
+// and without an uninstall command are hidden.
+pub fn list(path_dir_project : &PathBuf) -> anyhow::Result<Vec<Program>>
+{
+  let path_dir_prefix = path_dir_project.join("wine");
+  if ! path_dir_prefix.exists() { return Err(ah!("Wine prefix '{}' does not exist", path_dir_prefix.string())); } // if
+  let mut programs = read(&path_dir_prefix.join("system.reg"), false)?;
+  programs.extend(read(&path_dir_prefix.join("user.reg"), true)?);
+  programs.retain(|e| e.name != e.id || e.uninstall.is_some());
+  programs.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+  Ok(programs)
+} // fn list() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
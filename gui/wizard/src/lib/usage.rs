@@ -0,0 +1,129 @@
+use std::
+{
+  collections::HashMap,
+  fs,
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+
+use shared::std::PathBufExt;
+
+use crate::log;
+
+// pub enum Order {{{
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order
+{
+  Size,
+  Name,
+  Files,
+} // enum Order }}}
+
+impl Order
+{
+  pub const ALL : [Order; 3] = [Order::Size, Order::Name, Order::Files];
+
+  pub fn as_str(&self) -> &'static str
+  {
+    match self
+    {
+      Order::Size => "Size",
+      Order::Name => "Name",
+      Order::Files => "Files",
+    } // match
+  } // as_str
+} // impl Order
+
+// pub struct Node {{{
+// A file or a directory with the total of its contents, symbolic links have no size
+#[derive(Debug, Clone, Default)]
+pub struct Node
+{
+  pub name        : String,
+  pub size        : u64,
+  pub count_files : u64,
+  pub is_dir      : bool,
+  pub children    : Vec<Node>,
+} // struct Node }}}
+
+impl Node
+{
+
+// pub fn find() {{{
+// Descendant from the names of the directories that lead to it
+pub fn find(&self, names : &[String]) -> Option<&Node>
+{
+  match names.split_first()
+  {
+    Some((name, rest)) => self.children.iter().find(|e| e.name == *name)?.find(rest),
+    None => Some(self),
+  } // match
+} // fn find() }}}
+
+// pub fn sort() {{{
+// Sorts the children recursively, sizes and counts of files from largest to smallest
+pub fn sort(&mut self, order : Order)
+{
+  match order
+  {
+    Order::Size => self.children.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name))),
+    Order::Name => self.children.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+    Order::Files => self.children.sort_by(|a, b| b.count_files.cmp(&a.count_files).then(b.size.cmp(&a.size))),
+  } // match
+  self.children.iter_mut().for_each(|e| e.sort(order));
+} // fn sort() }}}
+
+} // impl Node
+
+// pub fn scan() {{{
+// Sizes of the contents of a directory, directories are visited after their contents so each one
+// collects the nodes of its children
+pub fn scan(path_dir : &PathBuf) -> anyhow::Result<Node>
+{
+  if ! path_dir.is_dir() { return Err(ah!("'{}' is not a directory", path_dir.string())); } // if
+  let mut pending : HashMap<PathBuf, Vec<Node>> = HashMap::new();
+  let mut opt_root = None;
+  for entry in walkdir::WalkDir::new(path_dir).follow_links(false).contents_first(true)
+  {
+    let entry = match entry
+    {
+      Ok(entry) => entry,
+      Err(e) => { log!("Could not read entry: {}", e); continue; },
+    }; // match
+    let name = entry.file_name().to_string_lossy().to_string();
+    let node = if entry.file_type().is_dir()
+    {
+      let children = pending.remove(entry.path()).unwrap_or_default();
+      Node
+      {
+        name,
+        size: children.iter().map(|e| e.size).sum(),
+        count_files: children.iter().map(|e| e.count_files).sum(),
+        is_dir: true,
+        children,
+      }
+    }
+    else
+    {
+      let size = if entry.file_type().is_file() { entry.metadata().map(|e| e.len()).unwrap_or(0) } else { 0 };
+      Node{ name, size, count_files: 1, is_dir: false, children: vec![] }
+    }; // if
+    match entry.path().parent().filter(|_| entry.depth() > 0)
+    {
+      Some(parent) => pending.entry(parent.to_path_buf()).or_default().push(node),
+      None => opt_root = Some(node),
+    } // match
+  } // for
+  opt_root.ok_or(ah!("Could not read '{}'", path_dir.string()))
+} // fn scan() }}}
+
+// pub fn remove() {{{
+pub fn remove(path : &PathBuf) -> anyhow::Result<()>
+{
+  let metadata = fs::symlink_metadata(path)?;
+  if metadata.is_dir() { fs::remove_dir_all(path)?; } else { fs::remove_file(path)?; } // if
+  Ok(())
+} // fn remove() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
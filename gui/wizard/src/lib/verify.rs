@@ -0,0 +1,208 @@
+use std::
+{
+  fs,
+  io::Write,
+  os::unix::fs::PermissionsExt,
+  path::PathBuf,
+  time::{Duration,Instant},
+};
+
+use anyhow::anyhow as ah;
+use serde::Serialize;
+
+use shared::std::PathBufExt;
+
+use crate::lib::format::format_size;
+
+// Time the image has to list its games
+const SECS_SMOKE_TIMEOUT : u64 = 60;
+
+// pub struct Game {{{
+#[derive(Debug, Clone, Serialize)]
+pub struct Game
+{
+  pub name     : String,
+  pub platform : String,
+} // struct Game }}}
+
+// pub struct Report {{{
+// Checks of a produced image
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report
+{
+  pub path_file_image : PathBuf,
+  pub is_exists       : bool,
+  pub is_executable   : bool,
+  pub size_image      : u64,
+  pub sha256          : Option<String>,
+  // Size of the project directories that went into the image
+  pub size_projects   : u64,
+  // size_projects / size_image
+  pub ratio           : Option<f64>,
+  pub games           : Vec<Game>,
+  // Games listed by the launcher inside the image
+  pub smoke_games     : Vec<String>,
+  pub smoke_ok        : bool,
+  pub smoke_output    : String,
+  pub errors          : Vec<String>,
+} // struct Report }}}
+
+impl Report
+{
+
+// pub fn is_ok() {{{
+pub fn is_ok(&self) -> bool
+{
+  self.errors.is_empty()
+} // fn is_ok() }}}
+
+// pub fn markdown() {{{
+pub fn markdown(&self) -> String
+{
+  let f_yes_no = |value : bool| if value { "yes" } else { "no" };
+  let mut md = String::new();
+  md.push_str(&format!("# Verification of {}\n\n", self.path_file_image.file_name_string()));
+  md.push_str(&format!("- Path: {}\n", self.path_file_image.string()));
+  md.push_str(&format!("- Exists: {}\n", f_yes_no(self.is_exists)));
+  md.push_str(&format!("- Executable: {}\n", f_yes_no(self.is_executable)));
+  md.push_str(&format!("- Size: {}\n", format_size(self.size_image)));
+  md.push_str(&format!("- SHA256: {}\n", self.sha256.clone().unwrap_or("unavailable".into())));
+  md.push_str(&format!("- Projects size: {}\n", format_size(self.size_projects)));
+  md.push_str(&format!("- Compression ratio: {}\n", self.ratio.map(|e| format!("{:.2}", e)).unwrap_or("unavailable".into())));
+  md.push_str(&format!("- Smoke launch: {}\n", if self.smoke_ok { "passed" } else { "failed" }));
+  md.push_str("\n## Games\n\n");
+  md.push_str("| Name | Platform | Listed by the image |\n|---|---|---|\n");
+  for game in &self.games
+  {
+    md.push_str(&format!("| {} | {} | {} |\n", game.name, game.platform, f_yes_no(self.smoke_games.contains(&game.name))));
+  } // for
+  if ! self.errors.is_empty()
+  {
+    md.push_str("\n## Errors\n\n");
+    self.errors.iter().for_each(|e| md.push_str(&format!("- {}\n", e)));
+  } // if
+  md
+} // fn markdown() }}}
+
+// pub fn write() {{{
+// Writes '<image>.report.json' and '<image>.report.md' next to the image
+pub fn write(&self) -> anyhow::Result<(PathBuf, PathBuf)>
+{
+  let name = self.path_file_image.file_name_string();
+  let path_dir = self.path_file_image.parent().ok_or(ah!("Invalid image path"))?;
+  let path_file_json = path_dir.join(format!("{}.report.json", name));
+  let path_file_md = path_dir.join(format!("{}.report.md", name));
+  serde_json::to_writer_pretty(fs::File::create(&path_file_json)?, self)?;
+  write!(fs::File::create(&path_file_md)?, "{}", self.markdown())?;
+  Ok((path_file_json, path_file_md))
+} // fn write() }}}
+
+} // impl Report
+
+// fn size_dir() {{{
+fn size_dir(path_dir : &PathBuf) -> u64
+{
+  walkdir::WalkDir::new(path_dir)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter_map(|e| e.metadata().ok())
+    .filter(|e| e.is_file())
+    .map(|e| e.len())
+    .sum()
+} // fn size_dir() }}}
+
+// fn smoke() {{{
+// Starts the image in list mode, which mounts it and runs the launcher without a game
+fn smoke(report : &mut Report)
+{
+  let mut child = match std::process::Command::new(&report.path_file_image)
+    .arg("--select-list")
+    .env_remove("LD_PRELOAD")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::null())
+    .spawn()
+  {
+    Ok(child) => child,
+    Err(e) => { report.errors.push(format!("Could not start the image: {}", e)); return; },
+  }; // match
+  // Output is read while the image runs, a full pipe would block it
+  let opt_reader = child.stdout.take().map(|mut stdout| std::thread::spawn(move ||
+  {
+    let mut output = String::new();
+    let _ = std::io::Read::read_to_string(&mut stdout, &mut output);
+    output
+  }));
+  let instant = Instant::now();
+  let status = loop
+  {
+    match child.try_wait()
+    {
+      Ok(Some(status)) => break status,
+      Ok(None) if instant.elapsed() < Duration::from_secs(SECS_SMOKE_TIMEOUT) => std::thread::sleep(Duration::from_millis(200)),
+      Ok(None) =>
+      {
+        let _ = child.kill();
+        let _ = child.wait();
+        report.errors.push(format!("The image did not finish in {} seconds", SECS_SMOKE_TIMEOUT));
+        return;
+      },
+      Err(e) => { report.errors.push(format!("Could not wait for the image: {}", e)); return; },
+    } // match
+  }; // loop
+  let output = opt_reader.and_then(|e| e.join().ok()).unwrap_or_default();
+  // Lines are 'index: name' or 'index: name (reason)'
+  report.smoke_games = output.lines()
+    .filter_map(|e| e.split_once(": "))
+    .filter(|(index,_)| index.trim().parse::<usize>().is_ok())
+    .map(|(_,name)| name.split(" (").next().unwrap_or(name).trim().to_string())
+    .collect();
+  report.smoke_output = output;
+  report.smoke_ok = status.success();
+  if ! status.success()
+  {
+    report.errors.push(format!("The image exited with {}", status));
+  } // if
+  for game in report.games.iter().filter(|e| ! report.smoke_games.contains(&e.name))
+  {
+    report.errors.push(format!("Game '{}' is not listed by the image", game.name));
+  } // for
+} // fn smoke() }}}
+
+// pub fn verify() {{{
+// Checks the image, games are the names and platforms of the packaged projects, with their
+// directories
+pub fn verify(path_file_image : &PathBuf, projects : Vec<(Game, PathBuf)>) -> Report
+{
+  let mut report = Report{ path_file_image: path_file_image.clone(), ..Report::default() };
+  report.games = projects.iter().map(|e| e.0.clone()).collect();
+  report.size_projects = projects.iter().map(|e| size_dir(&e.1)).sum();
+  // File
+  let metadata = match fs::metadata(path_file_image)
+  {
+    Ok(metadata) => metadata,
+    Err(e) => { report.errors.push(format!("Could not read the image: {}", e)); return report; },
+  }; // match
+  report.is_exists = metadata.is_file();
+  report.is_executable = metadata.permissions().mode() & 0o111 != 0;
+  report.size_image = metadata.len();
+  if ! report.is_exists { report.errors.push("The image is not a regular file".into()); } // if
+  if ! report.is_executable { report.errors.push("The image is not executable".into()); } // if
+  if report.size_image > 0
+  {
+    report.ratio = Some(report.size_projects as f64 / report.size_image as f64);
+  } // if
+  // Checksum
+  match sha256::try_digest(path_file_image.as_path())
+  {
+    Ok(digest) => report.sha256 = Some(digest),
+    Err(e) => report.errors.push(format!("Could not compute the checksum: {}", e)),
+  } // match
+  // Launch
+  if report.is_exists && report.is_executable
+  {
+    smoke(&mut report);
+  } // if
+  report
+} // fn verify() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,444 @@
+// Subset of the VT100/xterm control sequences, enough for progress bars and curses installers
+
+// pub struct Style {{{
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style
+{
+  // Color index 0-7
+  pub fg   : Option<u8>,
+  pub bg   : Option<u8>,
+  pub bold : bool,
+} // struct Style }}}
+
+impl Style
+{
+
+// fn sgr() {{{
+// Escape sequence that selects this style, limited to what fltk's ansi mode understands
+fn sgr(&self) -> String
+{
+  let mut codes = vec!["0".to_string()];
+  if self.bold { codes.push("1".into()); } // if
+  if let Some(fg) = self.fg { codes.push(format!("{}", 30 + fg)); } // if
+  if let Some(bg) = self.bg { codes.push(format!("{}", 40 + bg)); } // if
+  format!("\x1b[{}m", codes.join(";"))
+} // fn sgr() }}}
+
+} // impl Style
+
+// struct Cell {{{
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell
+{
+  ch    : char,
+  style : Style,
+} // struct Cell }}}
+
+const CELL_BLANK : Cell = Cell{ ch: ' ', style: Style{ fg: None, bg: None, bold: false } };
+
+// enum State {{{
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State
+{
+  Ground,
+  Escape,
+  // Control sequence, ESC [
+  Csi,
+  // Operating system command, ESC ], terminated by BEL or ESC \
+  Osc,
+  OscEscape,
+  // Character set selection, ESC ( and similar, one more char
+  Charset,
+} // enum State }}}
+
+// pub struct Screen {{{
+pub struct Screen
+{
+  rows         : usize,
+  cols         : usize,
+  grid         : Vec<Vec<Cell>>,
+  row          : usize,
+  col          : usize,
+  // Cursor is past the last column, the next char wraps
+  wrap_pending : bool,
+  saved        : (usize, usize),
+  style        : Style,
+  // Scroll region, inclusive
+  top          : usize,
+  bottom       : usize,
+  state        : State,
+  params       : String,
+  // Incomplete utf-8 sequence from the previous chunk
+  pending      : Vec<u8>,
+  // Rendered lines that left the top of the screen
+  scrolled     : Vec<String>,
+} // struct Screen }}}
+
+impl Screen
+{
+
+// pub fn new() {{{
+pub fn new(rows : usize, cols : usize) -> Screen
+{
+  let (rows, cols) = (rows.max(1), cols.max(1));
+  Screen
+  {
+    rows,
+    cols,
+    grid: vec![vec![CELL_BLANK; cols]; rows],
+    row: 0,
+    col: 0,
+    wrap_pending: false,
+    saved: (0, 0),
+    style: Style::default(),
+    top: 0,
+    bottom: rows - 1,
+    state: State::Ground,
+    params: String::new(),
+    pending: vec![],
+    scrolled: vec![],
+  }
+} // fn new() }}}
+
+// pub fn resize() {{{
+pub fn resize(&mut self, rows : usize, cols : usize)
+{
+  let (rows, cols) = (rows.max(1), cols.max(1));
+  // Keep the cursor line on the screen when it shrinks
+  while self.row >= rows
+  {
+    self.scroll_out();
+    self.row -= 1;
+  } // while
+  self.grid.truncate(rows);
+  self.grid.resize(rows, vec![CELL_BLANK; cols]);
+  self.grid.iter_mut().for_each(|e| e.resize(cols, CELL_BLANK));
+  self.rows = rows;
+  self.cols = cols;
+  self.row = self.row.min(rows - 1);
+  self.col = self.col.min(cols - 1);
+  self.saved = (self.saved.0.min(rows - 1), self.saved.1.min(cols - 1));
+  self.top = 0;
+  self.bottom = rows - 1;
+  self.wrap_pending = false;
+} // fn resize() }}}
+
+// pub fn feed() {{{
+pub fn feed(&mut self, data : &[u8])
+{
+  self.pending.extend_from_slice(data);
+  let pending = std::mem::take(&mut self.pending);
+  let valid = match std::str::from_utf8(&pending)
+  {
+    Ok(_) => pending.len(),
+    // Keep an incomplete sequence at the end for the next chunk
+    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+    Err(_) => pending.len(),
+  }; // match
+  let text = String::from_utf8_lossy(&pending[..valid]).to_string();
+  self.pending = pending[valid..].to_vec();
+  for ch in text.chars() { self.feed_char(ch); } // for
+} // fn feed() }}}
+
+// pub fn take_scrolled() {{{
+// Lines that left the screen since the last call, with style escapes
+pub fn take_scrolled(&mut self) -> Vec<String>
+{
+  std::mem::take(&mut self.scrolled)
+} // fn take_scrolled() }}}
+
+// pub fn render() {{{
+// Visible lines with style escapes, trailing empty lines are omitted
+pub fn render(&self) -> Vec<String>
+{
+  let mut lines : Vec<String> = self.grid.iter().map(|e| render_line(e)).collect();
+  while lines.len() > self.row + 1 && lines.last().map(|e| e.is_empty()).unwrap_or(false)
+  {
+    lines.pop();
+  } // while
+  lines
+} // fn render() }}}
+
+// fn feed_char() {{{
+fn feed_char(&mut self, ch : char)
+{
+  match self.state
+  {
+    State::Ground => match ch
+    {
+      '\x1b' => self.state = State::Escape,
+      '\r' => { self.col = 0; self.wrap_pending = false; },
+      '\n' | '\x0b' | '\x0c' => self.line_feed(),
+      '\x08' => { self.col = self.col.saturating_sub(1); self.wrap_pending = false; },
+      '\t' => { self.col = ((self.col / 8 + 1) * 8).min(self.cols - 1); },
+      '\x07' => (),
+      ch if ch.is_control() => (),
+      ch => self.put(ch),
+    }, // Ground
+    State::Escape =>
+    {
+      self.state = State::Ground;
+      match ch
+      {
+        '[' => { self.state = State::Csi; self.params.clear(); },
+        ']' => self.state = State::Osc,
+        '(' | ')' | '*' | '+' | '#' => self.state = State::Charset,
+        '7' => self.saved = (self.row, self.col),
+        '8' => self.restore(),
+        'D' => self.line_feed(),
+        'E' => { self.col = 0; self.line_feed(); },
+        'M' => self.reverse_line_feed(),
+        'c' => { *self = Screen{ scrolled: std::mem::take(&mut self.scrolled), ..Screen::new(self.rows, self.cols) }; },
+        _ => (),
+      } // match
+    }, // Escape
+    State::Csi =>
+    {
+      if ('\x40'..='\x7e').contains(&ch)
+      {
+        self.state = State::Ground;
+        let params = std::mem::take(&mut self.params);
+        self.csi(&params, ch);
+      } // if
+      else
+      {
+        self.params.push(ch);
+      } // else
+    }, // Csi
+    State::Osc => match ch
+    {
+      '\x07' => self.state = State::Ground,
+      '\x1b' => self.state = State::OscEscape,
+      _ => (),
+    }, // Osc
+    State::OscEscape => self.state = if ch == '\\' { State::Ground } else { State::Osc },
+    State::Charset => self.state = State::Ground,
+  } // match
+} // fn feed_char() }}}
+
+// fn put() {{{
+fn put(&mut self, ch : char)
+{
+  if self.wrap_pending
+  {
+    self.col = 0;
+    self.line_feed();
+  } // if
+  self.grid[self.row][self.col] = Cell{ ch, style: self.style };
+  if self.col + 1 < self.cols { self.col += 1; } else { self.wrap_pending = true; } // else
+} // fn put() }}}
+
+// fn restore() {{{
+// Moves the cursor to the saved position, clamped to the current size
+fn restore(&mut self)
+{
+  self.row = self.saved.0.min(self.rows - 1);
+  self.col = self.saved.1.min(self.cols - 1);
+  self.wrap_pending = false;
+} // fn restore() }}}
+
+// fn scroll_out() {{{
+// Removes the first line of the screen, it goes to the scrollback
+fn scroll_out(&mut self)
+{
+  let line = self.grid.remove(0);
+  self.scrolled.push(render_line(&line));
+  self.grid.push(vec![CELL_BLANK; self.cols]);
+} // fn scroll_out() }}}
+
+// fn scroll_up() {{{
+fn scroll_up(&mut self, count : usize)
+{
+  for _ in 0..count.min(self.bottom - self.top + 1)
+  {
+    // Only a full screen scroll goes to the scrollback
+    if self.top == 0 && self.bottom == self.rows - 1 { self.scroll_out(); continue; } // if
+    self.grid.remove(self.top);
+    self.grid.insert(self.bottom, vec![CELL_BLANK; self.cols]);
+  } // for
+} // fn scroll_up() }}}
+
+// fn scroll_down() {{{
+fn scroll_down(&mut self, count : usize)
+{
+  for _ in 0..count.min(self.bottom - self.top + 1)
+  {
+    self.grid.remove(self.bottom);
+    self.grid.insert(self.top, vec![CELL_BLANK; self.cols]);
+  } // for
+} // fn scroll_down() }}}
+
+// fn line_feed() {{{
+fn line_feed(&mut self)
+{
+  self.wrap_pending = false;
+  if self.row == self.bottom { self.scroll_up(1); }
+  else if self.row + 1 < self.rows { self.row += 1; } // else if
+} // fn line_feed() }}}
+
+// fn reverse_line_feed() {{{
+fn reverse_line_feed(&mut self)
+{
+  self.wrap_pending = false;
+  if self.row == self.top { self.scroll_down(1); }
+  else { self.row = self.row.saturating_sub(1); } // else
+} // fn reverse_line_feed() }}}
+
+// fn erase() {{{
+fn erase(&mut self, row : usize, cols : std::ops::Range<usize>)
+{
+  let style = Style{ fg: None, ..self.style };
+  for col in cols.start.min(self.cols)..cols.end.min(self.cols)
+  {
+    self.grid[row][col] = Cell{ ch: ' ', style };
+  } // for
+} // fn erase() }}}
+
+// fn csi() {{{
+fn csi(&mut self, params : &str, cmd : char)
+{
+  // Private modes, e.g., ?25h or ?1049h
+  if let Some(modes) = params.strip_prefix('?')
+  {
+    if (cmd == 'h' || cmd == 'l') && modes.split(';').any(|e| e == "1049" || e == "47" || e == "1047")
+    {
+      // The alternate screen starts clean, the previous contents are not restored
+      for row in 0..self.rows { self.erase(row, 0..self.cols); } // for
+      if cmd == 'h' { self.saved = (self.row, self.col); } else { self.restore(); } // else
+    } // if
+    return;
+  } // if
+  let args : Vec<usize> = params.split(';').map(|e| e.parse::<usize>().unwrap_or(0)).collect();
+  let arg = |i : usize, default : usize| -> usize { args.get(i).cloned().filter(|e| *e != 0).unwrap_or(default) };
+  self.wrap_pending = false;
+  match cmd
+  {
+    'A' => self.row = self.row.saturating_sub(arg(0, 1)).max(if self.row >= self.top { self.top } else { 0 }),
+    'B' | 'e' => self.row = self.row.saturating_add(arg(0, 1)).min(if self.row <= self.bottom { self.bottom } else { self.rows - 1 }),
+    'C' | 'a' => self.col = self.col.saturating_add(arg(0, 1)).min(self.cols - 1),
+    'D' => self.col = self.col.saturating_sub(arg(0, 1)),
+    'E' => { self.row = self.row.saturating_add(arg(0, 1)).min(self.rows - 1); self.col = 0; },
+    'F' => { self.row = self.row.saturating_sub(arg(0, 1)); self.col = 0; },
+    'G' | '`' => self.col = (arg(0, 1) - 1).min(self.cols - 1),
+    'd' => self.row = (arg(0, 1) - 1).min(self.rows - 1),
+    'H' | 'f' =>
+    {
+      self.row = (arg(0, 1) - 1).min(self.rows - 1);
+      self.col = (arg(1, 1) - 1).min(self.cols - 1);
+    },
+    'J' =>
+    {
+      let (row, col) = (self.row, self.col);
+      match args.first().cloned().unwrap_or(0)
+      {
+        0 => { self.erase(row, col..self.cols); for r in row+1..self.rows { self.erase(r, 0..self.cols); } },
+        1 => { self.erase(row, 0..col+1); for r in 0..row { self.erase(r, 0..self.cols); } },
+        _ => for r in 0..self.rows { self.erase(r, 0..self.cols); },
+      } // match
+    },
+    'K' =>
+    {
+      let (row, col) = (self.row, self.col);
+      match args.first().cloned().unwrap_or(0)
+      {
+        0 => self.erase(row, col..self.cols),
+        1 => self.erase(row, 0..col+1),
+        _ => self.erase(row, 0..self.cols),
+      } // match
+    },
+    'X' => { let (row, col) = (self.row, self.col); self.erase(row, col..col.saturating_add(arg(0, 1))); },
+    'P' =>
+    {
+      let (row, col, count) = (self.row, self.col, arg(0, 1).min(self.cols - self.col));
+      self.grid[row].drain(col..col+count);
+      self.grid[row].resize(self.cols, CELL_BLANK);
+    },
+    '@' =>
+    {
+      let (row, col, count) = (self.row, self.col, arg(0, 1).min(self.cols - self.col));
+      for _ in 0..count { self.grid[row].insert(col, CELL_BLANK); } // for
+      self.grid[row].truncate(self.cols);
+    },
+    'L' | 'M' if self.row >= self.top && self.row <= self.bottom =>
+    {
+      let top = self.top;
+      self.top = self.row;
+      if cmd == 'L' { self.scroll_down(arg(0, 1)); } else { self.scroll_up(arg(0, 1)); } // else
+      self.top = top;
+      self.col = 0;
+    },
+    'S' => self.scroll_up(arg(0, 1)),
+    'T' => self.scroll_down(arg(0, 1)),
+    'r' =>
+    {
+      let (top, bottom) = (arg(0, 1) - 1, arg(1, self.rows) - 1);
+      if top < bottom && bottom < self.rows { self.top = top; self.bottom = bottom; } // if
+      self.row = 0;
+      self.col = 0;
+    },
+    's' => self.saved = (self.row, self.col),
+    'u' => self.restore(),
+    'm' => self.sgr(&args),
+    _ => (),
+  } // match
+} // fn csi() }}}
+
+// fn sgr() {{{
+fn sgr(&mut self, args : &[usize])
+{
+  let mut iter = args.iter().cloned();
+  while let Some(code) = iter.next()
+  {
+    match code
+    {
+      0 => self.style = Style::default(),
+      1 => self.style.bold = true,
+      22 => self.style.bold = false,
+      30..=37 => self.style.fg = Some((code - 30) as u8),
+      39 => self.style.fg = None,
+      40..=47 => self.style.bg = Some((code - 40) as u8),
+      49 => self.style.bg = None,
+      90..=97 => { self.style.fg = Some((code - 90) as u8); self.style.bold = true; },
+      100..=107 => self.style.bg = Some((code - 100) as u8),
+      // Extended colors are approximated to the basic palette
+      38 | 48 =>
+      {
+        let color = match iter.next()
+        {
+          Some(5) => iter.next().map(|e| (e % 8) as u8),
+          Some(2) =>
+          {
+            let (r, g, b) = (iter.next().unwrap_or(0), iter.next().unwrap_or(0), iter.next().unwrap_or(0));
+            Some(((r > 127) as u8) | (((g > 127) as u8) << 1) | (((b > 127) as u8) << 2))
+          },
+          _ => None,
+        }; // match
+        if code == 38 { self.style.fg = color; } else { self.style.bg = color; } // else
+      },
+      _ => (),
+    } // match
+  } // while
+} // fn sgr() }}}
+
+} // impl Screen
+
+// fn render_line() {{{
+fn render_line(cells : &[Cell]) -> String
+{
+  let len = cells.iter().rposition(|e| e.ch != ' ' || e.style.bg.is_some()).map(|e| e + 1).unwrap_or(0);
+  let mut line = String::new();
+  let mut style = Style::default();
+  for cell in &cells[..len]
+  {
+    if cell.style != style
+    {
+      line.push_str(&cell.style.sgr());
+      style = cell.style;
+    } // if
+    line.push(cell.ch);
+  } // for
+  if style != Style::default() { line.push_str("\x1b[0m"); } // if
+  line
+} // fn render_line() }}}
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -0,0 +1,174 @@
+use std::
+{
+  collections::HashSet,
+  fs,
+  path::PathBuf,
+};
+
+use anyhow::anyhow as ah;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use shared::std::PathBufExt;
+
+use crate::db;
+use crate::lib;
+use crate::gameimage;
+
+// Categories of verbs listed by winetricks
+pub const CATEGORIES : [&str; 4] = ["dlls", "fonts", "settings", "apps"];
+
+// pub struct Verb {{{
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verb
+{
+  pub name        : String,
+  pub category    : String,
+  pub description : String,
+} // struct Verb }}}
+
+impl Verb
+{
+
+// pub fn matches() {{{
+// Every term of the query is in the name or in the description, ignoring case
+pub fn matches(&self, query : &str) -> bool
+{
+  let name = self.name.to_lowercase();
+  let description = self.description.to_lowercase();
+  query.to_lowercase()
+    .split_whitespace()
+    .all(|e| name.contains(e) || description.contains(e))
+} // fn matches() }}}
+
+} // impl Verb
+
+// fn path_dir_cache() {{{
+// The catalog depends on the bundled winetricks, not on the project
+fn path_dir_cache() -> anyhow::Result<PathBuf>
+{
+  Ok(db::global::read()?.path_dir_build.join("winetricks"))
+} // fn path_dir_cache() }}}
+
+// fn parse() {{{
+// Verbs from the output of 'winetricks <category> list', lines are the name followed by the
+// description, e.g., 'd3dx9    MS d3dx9_??.dll from DirectX 9 redistributable (Microsoft, 2010) [downloadable]'.
+// Lines logged by the backend are prefixed with 'I :: stdout(<program>): ', which is removed.
+fn parse(category : &str, text : &str) -> Vec<Verb>
+{
+  let regex_prefix = regex::Regex::new(r"^[A-Z] :: std(out|err)\([^)]*\): ").unwrap();
+  let regex = regex::Regex::new(r"^([a-zA-Z0-9_.+=-]+)\s{2,}(.+)$").unwrap();
+  text.lines()
+    .map(|line| regex_prefix.replace(line, ""))
+    .filter_map(|line| regex.captures(line.trim_end()).map(|captures| (captures[1].to_string(), captures[2].to_string())))
+    .map(|(name, description)|
+    {
+      let description = description
+        .trim()
+        .trim_end_matches("[downloadable]")
+        .trim_end_matches("[cached]")
+        .trim()
+        .to_string();
+      Verb{ name, category: category.to_string(), description }
+    })
+    .collect()
+} // fn parse() }}}
+
+// fn list() {{{
+// Lists the verbs of a category with the winetricks of the backend, which writes the output of
+// winetricks to a file
+fn list(category : &str) -> anyhow::Result<Vec<Verb>>
+{
+  let path_file_list = path_dir_cache()?.join(format!("{}.list", category));
+  fs::create_dir_all(path_dir_cache()?)?;
+  // Winetricks comes with the distribution of the project
+  lib::distribution::activate_project()?;
+  let _ = fs::remove_file(&path_file_list);
+  let mut json_args = json!({});
+  json_args["op"] = "install".into();
+  json_args["install"]["op"] = "install".into();
+  json_args["install"]["sub_op"] = "winetricks_list".into();
+  json_args["install"]["args"] = vec![category.to_string(), path_file_list.string()].into();
+  let code = gameimage::gameimage::gameimage_sync(vec![&json_args.to_string()]);
+  let text = fs::read_to_string(&path_file_list).unwrap_or_default();
+  let _ = fs::remove_file(&path_file_list);
+  if code != 0 { return Err(ah!("Could not list the winetricks {}: {}", category, code)); } // if
+  Ok(parse(category, &text))
+} // fn list() }}}
+
+// pub fn refresh() {{{
+// Lists the verbs of all categories and caches them in the build directory
+pub fn refresh() -> anyhow::Result<Vec<Verb>>
+{
+  let mut verbs = vec![];
+  for category in CATEGORIES
+  {
+    verbs.extend(list(category)?);
+  } // for
+  if verbs.is_empty() { return Err(ah!("Winetricks listed no verbs")); } // if
+  fs::write(path_dir_cache()?.join("verbs.json"), serde_json::to_string_pretty(&verbs)?)?;
+  Ok(verbs)
+} // fn refresh() }}}
+
+// pub fn catalog() {{{
+// Cached verbs, they are listed when there is no cache yet
+pub fn catalog() -> anyhow::Result<Vec<Verb>>
+{
+  let path_file_cache = path_dir_cache()?.join("verbs.json");
+  match fs::read_to_string(&path_file_cache).ok().and_then(|e| serde_json::from_str(&e).ok())
+  {
+    Some(verbs) => Ok(verbs),
+    None => refresh(),
+  } // match
+} // fn catalog() }}}
+
+// pub fn installed() {{{
+// Verbs installed into the wine prefix of the project, winetricks logs each one it installs
+pub fn installed(path_dir_project : &PathBuf) -> HashSet<String>
+{
+  fs::read_to_string(path_dir_project.join("wine").join("winetricks.log"))
+    .unwrap_or_default()
+    .lines()
+    .map(|e| e.trim().to_string())
+    .filter(|e| ! e.is_empty())
+    .collect()
+} // fn installed() }}}
+
+#[cfg(test)]
+mod tests
+{
+
+use super::parse;
+
+// fn parse_logged() {{{
+// Output of winetricks as logged by the backend
+#[test]
+fn parse_logged()
+{
+  let text = "I :: stdout(/fim/static/fim_portal): d3dx9                    MS d3dx9_??.dll from DirectX 9 redistributable (Microsoft, 2010) [downloadable]\n\
+    I :: stdout(/fim/static/fim_portal): vcrun2019                MS Visual C++ 2015-2019 libraries (mfc140.dll,msvcp140.dll) (Microsoft, 2019) [downloadable]\n\
+    I :: stderr(/fim/static/fim_portal): ------------------------------------------------------\n\
+    I :: stdout(/fim/static/fim_portal): dotnet48                 MS .NET 4.8 (Microsoft, 2019) [cached]\n";
+  let verbs = parse("dlls", text);
+  assert_eq!(verbs.len(), 3);
+  assert_eq!(verbs[0].name, "d3dx9");
+  assert_eq!(verbs[0].category, "dlls");
+  assert_eq!(verbs[0].description, "MS d3dx9_??.dll from DirectX 9 redistributable (Microsoft, 2010)");
+  assert_eq!(verbs[1].name, "vcrun2019");
+  assert_eq!(verbs[2].description, "MS .NET 4.8 (Microsoft, 2019)");
+} // fn parse_logged() }}}
+
+// fn parse_raw() {{{
+// Output of winetricks written to a file
+#[test]
+fn parse_raw()
+{
+  let verbs = parse("fonts", "corefonts                MS Arial, Courier, Times fonts (Microsoft, 2008) [downloadable]\n");
+  assert_eq!(verbs.len(), 1);
+  assert_eq!(verbs[0].name, "corefonts");
+  assert_eq!(verbs[0].description, "MS Arial, Courier, Times fonts (Microsoft, 2008)");
+} // fn parse_raw() }}}
+
+} // mod tests
+
+// vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
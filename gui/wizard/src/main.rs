@@ -135,7 +135,11 @@ fn redraw(&mut self, msg : Msg)
     Msg::DrawWineConfigure => wizard::wine::configure(self.tx, "Configure Wine"),
     Msg::DrawWineTricks => wizard::wine::winetricks(self.tx, "Install Libraries"),
     Msg::DrawWineEnvironment => wizard::wine::environment(self.tx, "Configure the Environment"),
+    Msg::DrawWineDllOverrides => wizard::wine::dll_overrides(self.tx, "Configure DLL Overrides"),
+    Msg::DrawWineFonts => wizard::wine::fonts(self.tx, "Configure Fonts"),
+    Msg::DrawWineGraphics => wizard::wine::graphics(self.tx, "Configure Graphics and Synchronization"),
     Msg::DrawWineRom => wizard::wine::rom(self.tx, "Install/Test the Application(s)"),
+    Msg::DrawWineSlim => wizard::wine::slim(self.tx, "Remove Unneeded Content"),
     Msg::DrawWineCompress => wizard::wine::compress(self.tx, "Compress the Created Package"),
     // Retroarch
     Msg::DrawRetroarchName => wizard::retroarch::name(self.tx, "Select the Application Name"),
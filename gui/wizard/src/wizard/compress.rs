@@ -1,17 +1,24 @@
 use std::env;
+use std::path::PathBuf;
+use std::time::{Duration,Instant};
 
 // Gui
 use fltk::
 {
   prelude::*,
   app::Sender,
+  button,
   menu,
+  text,
 };
 
 use shared::fltk::SenderExt;
+use shared::fltk::WidgetExtExtra;
 use shared::std::PathBufExt;
 
+use crate::db;
 use crate::dimm;
+use crate::lib;
 use crate::gameimage;
 use crate::frame;
 use crate::common;
@@ -20,25 +27,135 @@ use crate::log_alert;
 use crate::log_err;
 use shared::{column,row,fixed};
 
+// Level used when the project has none saved
+const LEVEL_DEFAULT : &str = "7";
+
+// fn get_path_db_compression() {{{
+fn get_path_db_compression() -> anyhow::Result<PathBuf>
+{
+  let global = db::global::read()?;
+  Ok(global.get_project_dir(&global.project)?.join("gameimage.compression.json"))
+} // get_path_db_compression() }}}
+
+// fn level_read() {{{
+// Compression level saved for the current project
+fn level_read() -> String
+{
+  get_path_db_compression()
+    .and_then(|path_file_db| shared::db::kv::read(&path_file_db))
+    .ok()
+    .and_then(|kv| kv.get("level").cloned())
+    .filter(|level| level.parse::<u32>().is_ok_and(|e| e <= 10))
+    .unwrap_or(LEVEL_DEFAULT.to_string())
+} // fn level_read() }}}
+
+// fn level_write() {{{
+fn level_write(level : &String) -> anyhow::Result<()>
+{
+  shared::db::kv::write(&get_path_db_compression()?, &"level".to_string(), level)
+} // fn level_write() }}}
+
+// fn estimate() {{{
+// Benchmarks the levels on a sample of the current project, lines are sent to f_line as they
+// are computed
+fn estimate(f_line : impl Fn(String)) -> anyhow::Result<()>
+{
+  let global = db::global::read()?;
+  let path_dir_project = global.get_project_dir(&global.project)?;
+  f_line(format!("Sampling files of '{}'...", global.project));
+  let sample = lib::compression::sample(&path_dir_project)?;
+  f_line(format!("Sampled {:.1} MiB from {} files, project size is {}"
+    , sample.data.len() as f64 / (1u64 << 20) as f64
+    , sample.count_files
+    , lib::format::format_size(sample.size_total)
+  ));
+  f_line(format!("{:<8}{:<16}{:<16}{}", "Level", "Size", "Time", "Ratio"));
+  for level in lib::compression::LEVELS_BENCHMARK
+  {
+    let estimate = lib::compression::benchmark(&sample, level)?;
+    f_line(format!("{:<8}{:<16}{:<16}{:.2}"
+      , estimate.level
+      , lib::format::format_size(estimate.size)
+      , lib::format::format_time(estimate.time)
+      , estimate.ratio
+    ));
+  } // for
+  f_line("Estimates are approximate, the time depends on the number of CPU cores".into());
+  Ok(())
+} // fn estimate() }}}
+
+// fn progress_label() {{{
+// Percentage, throughput and remaining time from the progress reported by the backend
+fn progress_label(percentage : f64, size_total : u64, elapsed : Duration) -> String
+{
+  if percentage <= 0.0 || elapsed.is_zero() { return format!("{:.0}%", percentage); } // if
+  let size_done = size_total as f64 * percentage / 100.0;
+  let throughput = size_done / elapsed.as_secs_f64() / (1u64 << 20) as f64;
+  let remaining = elapsed.mul_f64((100.0 - percentage) / percentage);
+  format!("{:.0}%, {:.1} MiB/s, {} remaining", percentage, throughput, lib::format::format_time(remaining))
+} // fn progress_label() }}}
+
 // fn compress_next() {{{
-pub fn compress_next(tx: Sender<common::Msg>, term: frame::term::Term)
+pub fn compress_next(tx: Sender<common::Msg>, term: frame::term::Term, prog: fltk::misc::Progress)
 {
   tx.send_awake(common::Msg::WindDeactivate);
   let backend = match gameimage::gameimage::binary()
   {
     Ok(backend) => backend,
-    Err(e) => { log_alert!("Error to execute backend: {}", e); return; }
+    Err(e) => { log_alert!("Error to execute backend: {}", e); tx.send_activate(common::Msg::WindActivate); return; }
   };
   let mut term = term.clone();
   std::thread::spawn(move ||
   {
-    let handle = term.dispatch(vec![&backend.string(), r#"{ "op": "compress" }"#], |_| {});
-    match handle
+    // Size of the input to compute the throughput
+    let size_total = db::global::get_current_project()
+      .map(|e| lib::compression::size(&e.path_dir_project_root))
+      .unwrap_or(0);
+    // Receive the progress, the queue is created before the backend starts so no message is lost
+    let ipc = match lib::ipc::Ipc::new()
+    {
+      Ok(ipc) => Some(ipc),
+      Err(e) => { log!("Progress is not available: {}", e); None },
+    }; // match
+    let instant = Instant::now();
+    let handle_progress = std::thread::spawn({
+      let mut prog = prog.clone();
+      move ||
+      {
+        let Some(ipc) = ipc else { return; };
+        while let Ok(msg) = ipc.recv()
+        {
+          let Ok(percentage) = msg.trim().parse::<f64>() else { continue; };
+          prog.set_value(percentage);
+          prog.set_label(&progress_label(percentage, size_total, instant.elapsed()));
+          fltk::app::awake();
+        } // while
+      }
+    });
+    // Run compression, the callback receives the exit code
+    let (tx_code, rx_code) = std::sync::mpsc::channel();
+    let code = match term.dispatch(vec![&backend.string(), r#"{ "op": "compress" }"#], move |code| { let _ = tx_code.send(code); })
+    {
+      Ok(_) => rx_code.recv().unwrap_or(1),
+      Err(e) => { log!("Could not start compression: {}", e); 1 },
+    }; // match
+    // Stop receiving progress
+    lib::ipc::Ipc::close();
+    log_err!(handle_progress.join());
+    let mut prog = prog.clone();
+    if code == 0
+    {
+      log!("Compression finished in {}", lib::format::format_time(instant.elapsed()));
+      prog.set_value(100.0);
+      prog.set_label("Compression finished");
+      tx.send_activate(common::Msg::DrawCreator);
+    } // if
+    else
     {
-      Ok(handle) => log_err!(handle.lock().unwrap().wait().map(|_|{})),
-      Err(e) => log!("{}", e),
-    };
-    tx.send_activate(common::Msg::DrawCreator);
+      prog.set_label(&format!("Compression failed with exit code {}", code));
+      tx.send_activate(common::Msg::WindActivate);
+      log_alert!("Compression failed with exit code {}, check the terminal output for details", code);
+    } // else
   });
 
 } // fn compress_next() }}}
@@ -55,8 +172,23 @@ pub fn compress(tx: Sender<common::Msg>
   column!(col,
     let term = frame::term::Term::default();
     col.add(&term.group);
+    let prog = shared::fltk::progress::progress()
+      .with_label("Compression has not started")
+      .with_align(fltk::enums::Align::Inside | fltk::enums::Align::Center)
+      .with_color(fltk::enums::Color::BackGround)
+      .with_color_selected(fltk::enums::Color::Blue);
+    col.fixed(&prog, dimm::height_button_wide());
+    let mut display_estimate = text::TextDisplay::default()
+      .with_color(fltk::enums::Color::BackGround)
+      .with_frame(fltk::enums::FrameType::BorderBox);
+    display_estimate.set_text_font(fltk::enums::Font::Courier);
+    display_estimate.set_buffer(text::TextBuffer::default());
+    display_estimate.insert("Click on estimate to compare the size and time of the compression levels");
+    col.fixed(&display_estimate, dimm::height_text()*9);
     row!(row,
       fixed!(row, btn_level, menu::MenuButton::default(), dimm::width_button_wide());
+      fixed!(row, btn_estimate, button::Button::default().with_label("Estimate"), dimm::width_button_wide()*2);
+      fixed!(row, btn_usage, button::Button::default().with_label("Disk usage"), dimm::width_button_wide()*2);
       row.add(&fltk::frame::Frame::default()
         .with_align(fltk::enums::Align::Inside | fltk::enums::Align::Center)
         .with_label("Select the compression level before clicking on start")
@@ -69,7 +201,7 @@ pub fn compress(tx: Sender<common::Msg>
   ui.btn_prev.clone().emit(tx.clone(), msg_prev);
   let mut btn_next = ui.btn_next.clone();
   btn_next.set_label("Start");
-  btn_next.set_callback(move |_| { compress_next(tx, term.clone()); });
+  btn_next.set_callback(move |_| { compress_next(tx, term.clone(), prog.clone()); });
 
   // Open space for compress level button
   let mut btn_level = btn_level.clone();
@@ -78,6 +210,7 @@ pub fn compress(tx: Sender<common::Msg>
     let str_level = e.choice().unwrap_or(String::from("7"));
     log!("Set compression level to {}", str_level);
     env::set_var("FIM_COMPRESSION_LEVEL", &str_level);
+    log_err!(level_write(&str_level));
     e.set_value(e.value());
     e.set_label(&str_level);
   });
@@ -85,9 +218,39 @@ pub fn compress(tx: Sender<common::Msg>
   {
     btn_level.add_choice(&i.to_string());
   } // for
-  env::set_var("FIM_COMPRESSION_LEVEL", "7");
-  btn_level.set_value(8);
-  btn_level.set_label("7");
+  // Restore the level of the project
+  let str_level = level_read();
+  env::set_var("FIM_COMPRESSION_LEVEL", &str_level);
+  btn_level.set_value(btn_level.find_index(&str_level));
+  btn_level.set_label(&str_level);
+
+  // Show what takes space in the project
+  btn_usage.clone().set_callback(|_| frame::usage::usage());
+
+  // Run the estimates in the background
+  let mut btn_estimate = btn_estimate.clone();
+  btn_estimate.set_callback(move |e|
+  {
+    e.deactivate();
+    let mut btn_estimate = e.clone();
+    let display_estimate = display_estimate.clone();
+    if let Some(mut buffer) = display_estimate.buffer() { buffer.set_text(""); } // if
+    std::thread::spawn(move ||
+    {
+      let f_line = |line : String|
+      {
+        let display_estimate = display_estimate.clone();
+        display_estimate.insert(&format!("{}\n", line));
+        fltk::app::awake();
+      };
+      if let Err(e) = estimate(&f_line)
+      {
+        f_line(format!("Could not estimate: {}", e));
+      } // if
+      btn_estimate.activate();
+      fltk::app::awake();
+    });
+  });
 } // fn compress() }}}
 
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
@@ -7,7 +7,6 @@ use std::
 
 use serde_json::json;
 
-use std::io::Write;
 
 use fltk::
 {
@@ -208,7 +207,7 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
   // Input to send commands to the running process
   let mut input_cmd = input_cmd.clone();
   input_cmd.deactivate();
-  let clone_arc_process = arc_process.clone();
+  let clone_term = term.clone();
   input_cmd.handle(move |input, ev|
   {
     if ! ( ev == fltk::enums::Event::KeyUp && fltk::app::event_key() == fltk::enums::Key::Enter )
@@ -216,33 +215,16 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
       return false;
     } // if
 
-    let arc_inner = if let Some(arc_inner) = clone_arc_process.lock().unwrap().as_ref()
-    {
-      arc_inner.clone()
-    } // if
-    else
-    {
-      input.deactivate();
-      log_status!("No process running?");
-      return false;
-    }; // else
-
-    let mut lock = match arc_inner.lock()
-    {
-      Ok(lock) => lock,
-      Err(e) => { input.deactivate(); log_status!("Could not get lock to currently running process: {}", e); return false; },
-    };
-
-    match lock.stdin.as_mut()
+    // Send the line to the process as if typed in the terminal
+    match clone_term.write(&format!("{}\r", input.value()))
     {
-      Some(stdin) =>
+      Ok(()) =>
       {
-        let _ = writeln!(stdin, "{}", input.value());
         input.set_value("");
         let _ = input.take_focus();
-      }, // Some
-      None => input.deactivate(),
-    } // if
+      },
+      Err(e) => { input.deactivate(); log_status!("Could not send input to the process: {}", e); return false; },
+    } // match
 
     true
   });
@@ -350,8 +332,8 @@ fn default_play(path_file_item: &std::path::PathBuf) -> anyhow::Result<()>
 {
   // Set the selected binary as default
   gameimage::select::select("rom", &path_file_item)?;
-  // Test the selected binary
-  gameimage::test::test()?;
+  // Test the selected binary, its exit code is in the project history
+  gameimage::test::test();
   Ok(())
 }
 // fn default_play() }}}
@@ -551,11 +533,15 @@ pub fn default(tx: Sender<common::Msg>, title: &str)
     );
     col.fixed(&col_search, dimm::height_button_wide() + dimm::height_text() + dimm::border());
     col.fixed(&shared::fltk::separator::horizontal(col.w()), dimm::height_sep());
-    let mut btn_show_selected = shared::fltk::button::rect::checkbutton()
-      .with_align(Align::Inside | Align::Left)
-      .with_color(Color::BackGround)
-      .with_label(" Only show modified entries");
-    col.fixed(&btn_show_selected, dimm::width_checkbutton() + dimm::border());
+    row!(row_options,
+      let mut btn_show_selected = shared::fltk::button::rect::checkbutton()
+        .with_align(Align::Inside | Align::Left)
+        .with_color(Color::BackGround)
+        .with_label(" Only show modified entries");
+      row_options.add(&btn_show_selected);
+      fixed!(row_options, btn_history, shared::fltk::button::rect::list(), dimm::width_button_rec());
+    );
+    col.fixed(&row_options, dimm::width_checkbutton().max(dimm::height_button_rec()) + dimm::border());
     col.fixed(&shared::fltk::separator::horizontal(col.w()), dimm::height_sep());
     scroll!(scroll,
       scroll.set_type(fltk::group::ScrollType::VerticalAlways);
@@ -670,6 +656,11 @@ pub fn default(tx: Sender<common::Msg>, title: &str)
       tx.send_activate(common::Msg::DrawLinuxDefault);
     } // else
   });
+  // Show previous test runs
+  hover_blink!(btn_history);
+  btn_history.clone()
+    .with_color(Color::Blue)
+    .with_callback(|_| frame::history::history());
   // Configure next button
   let clone_tx = tx.clone();
   ui.btn_next.clone().set_callback(move |_|
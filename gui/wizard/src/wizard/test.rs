@@ -15,7 +15,10 @@ use shared::{column,row,fixed,hover_blink};
 use crate::gameimage;
 use crate::dimm;
 use crate::frame;
+use crate::lib;
 use crate::common;
+use crate::log;
+use crate::log_err;
 use crate::log_alert;
 
 // pub fn test() {{{
@@ -32,12 +35,14 @@ pub fn test(tx: Sender<common::Msg>
     row!(row,
       row.add(&fltk::frame::Frame::default());
       fixed!(row, btn_test, shared::fltk::button::wide::default(), dimm::width_button_wide());
+      fixed!(row, btn_history, shared::fltk::button::wide::default(), dimm::width_button_wide());
       row.add(&fltk::frame::Frame::default());
     );
     col.fixed(&row, dimm::height_button_wide());
   );
   // Cofigure buttons
   hover_blink!(btn_test);
+  hover_blink!(btn_history);
   ui.btn_prev.clone().emit(tx.clone(), msg_prev);
   ui.btn_next.clone().emit(tx.clone(), msg_next);
 
@@ -57,11 +62,36 @@ pub fn test(tx: Sender<common::Msg>
     };
     let mut json_args = json!({});
     json_args["op"] = "test".into();
-    let _ = term.dispatch(vec![&backend.string(), &json_args.to_string()], move |_|
+    // Record the run in the project history
+    let mut opt_recorder = match lib::history::begin()
     {
+      Ok(recorder) => Some(recorder),
+      Err(e) => { log!("Could not record test run: {}", e); None },
+    }; // match
+    // The output is complete when the callback runs, the tee is replaced on the next test
+    log_err!(term.set_tee(opt_recorder.as_ref().map(|e| e.path_file_log()).as_ref()));
+    let clone_tx = clone_tx.clone();
+    let result = term.dispatch(vec![&backend.string(), &json_args.to_string()], move |code|
+    {
+      if let Some(recorder) = opt_recorder.take()
+      {
+        log_err!(recorder.finish(code).map(|_| ()));
+      } // if
       clone_tx.send_awake(common::Msg::WindActivate);
     });
+    if let Err(e) = result
+    {
+      log_alert!("Could not start the test: {}", e);
+      log_err!(term.set_tee(None));
+      clone_tx.send_awake(common::Msg::WindActivate);
+    } // if
   });
+
+  // Show previous runs
+  btn_history.clone()
+    .with_label("History")
+    .with_color(Color::Blue)
+    .set_callback(|_| frame::history::history());
 } // fn test() }}}
 
 // vim: set expandtab fdm=marker ts=2 sw=2 tw=100 et :
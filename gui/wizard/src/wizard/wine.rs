@@ -1,6 +1,7 @@
 // Gui
 use std::
 {
+  collections::HashMap,
   path,
   path::PathBuf,
   sync::{Arc,Mutex,LazyLock}
@@ -13,7 +14,8 @@ use fltk::{
   output,
   frame::Frame,
   dialog,
-  enums::{Color,Align},
+  enums::{Color,Align,FrameType},
+  text::{TextBuffer,TextDisplay,WrapMode},
 };
 
 use clown::clown;
@@ -33,6 +35,7 @@ use crate::log_status;
 use crate::log_err_status;
 use crate::log_return_void;
 use crate::db;
+use crate::lib;
 use crate::common;
 use crate::frame;
 use crate::wizard;
@@ -172,6 +175,14 @@ fn get_path_db_args() -> anyhow::Result<PathBuf>
   Ok(path_file_db)
 } // get_path_db_args() }}}
 
+// get_path_db_dir() {{{
+fn get_path_db_dir() -> anyhow::Result<PathBuf>
+{
+  let mut path_file_db = get_path_db()?;
+  path_file_db.push("gameimage.dir.json");
+  Ok(path_file_db)
+} // get_path_db_dir() }}}
+
 // get_path_db_alias() {{{
 fn get_path_db_alias() -> anyhow::Result<PathBuf>
 {
@@ -292,6 +303,323 @@ pub fn environment(tx: Sender<common::Msg>, title: &str)
   });
 } // }}}
 
+// pub fn graphics() {{{
+pub fn graphics(tx: Sender<common::Msg>, title: &str)
+{
+  let ui = crate::GUI.lock().unwrap().ui.clone()(title);
+  // Layout
+  column!(col,
+    let mut frame_conf = Frame::default().with_align(Align::Inside | Align::Left);
+    col.fixed(&frame_conf, dimm::height_text());
+    scroll!(scroll,
+      hpack!(col_content,);
+    );
+  );
+  // Configure footer
+  ui.btn_next.clone().deactivate();
+  ui.btn_prev.clone().emit(tx, common::Msg::DrawWineConfigure);
+  // Resize content to scroll width
+  scroll.set_type(fltk::group::ScrollType::VerticalAlways);
+  scroll.resize_callback({
+    let mut col_content = col_content.clone();
+    move |_,x,y,w,_|
+    {
+      col_content.resize(x,y,w-dimm::border_half()*3,col_content.h());
+    }
+  });
+  // The configuration file of dxvk goes in the directory of the default executable
+  let path_file_env = match get_path_db_env()
+  {
+    Ok(path_file_env) => path_file_env,
+    Err(e) => log_return_void!("{}", e),
+  }; // match
+  let opt_path_file_conf = db::project::current().ok().and_then(|project|
+  {
+    let path_file_rom = project.get_path_relative(db::project::EntryName::PathFileRom).ok()?;
+    Some(project.get_dir_self().ok()?.join(path_file_rom).parent()?.join("dxvk.conf"))
+  });
+  frame_conf.set_label(&match &opt_path_file_conf
+  {
+    Some(path_file_conf) => format!("DXVK options are written to '{}'", path_file_conf.string()),
+    None => "Select the default executable to write DXVK options to its directory".to_string(),
+  }); // match
+  rescope!(col_content,
+    let _ = shared::fltk::graphics::panel(path_file_env, opt_path_file_conf);
+  );
+} // fn graphics() }}}
+
+// pub fn dll_overrides() {{{
+pub fn dll_overrides(tx: Sender<common::Msg>, title: &str)
+{
+  let ui = crate::GUI.lock().unwrap().ui.clone()(title);
+  // Layout
+  row!(row,
+    scroll!(scroll,
+      hpack!(col_content,);
+    );
+    column!(col_btn,
+      fixed!(col_btn, btn_add, shared::fltk::button::rect::add(), dimm::height_button_rec());
+      col_btn.add(&Frame::default());
+    );
+    row.fixed(&col_btn, dimm::width_button_rec());
+  );
+  // Buttons
+  hover_blink!(btn_add);
+  // Configure footer
+  ui.btn_next.clone().deactivate();
+  ui.btn_prev.clone().emit(tx, common::Msg::DrawWineConfigure);
+  // Resize content to scroll width
+  col_content.set_spacing(dimm::border_half());
+  scroll.set_type(fltk::group::ScrollType::VerticalAlways);
+  scroll.resize_callback({
+    let mut col_content = col_content.clone();
+    move |_,x,y,w,_|
+    {
+      col_content.resize(x,y,w-dimm::border_half()*3,col_content.h());
+    }
+  });
+  // Prefix and directory of the default executable
+  let project = match db::project::current()
+  {
+    Ok(project) => project,
+    Err(e) => log_return_void!("{}", e),
+  }; // match
+  let path_dir_project = match project.get_dir_self()
+  {
+    Ok(path_dir_project) => path_dir_project,
+    Err(e) => log_return_void!("{}", e),
+  }; // match
+  let path_dir_wine = path_dir_project.join("wine");
+  let path_dir_game = project.get_path_relative(db::project::EntryName::PathFileRom)
+    .ok()
+    .and_then(|e| path_dir_project.join(e).parent().map(|e| e.to_path_buf()));
+  let path_file_env = match get_path_db_env()
+  {
+    Ok(path_file_env) => path_file_env,
+    Err(e) => log_return_void!("{}", e),
+  }; // match
+  // Libraries found and the ones already overridden
+  let overrides = Arc::new(Mutex::new(shared::wine::dll::read(&path_file_env, &path_dir_wine)));
+  let mut libraries = shared::wine::dll::list(path_dir_game.as_ref(), &path_dir_wine);
+  overrides.lock().unwrap().keys().for_each(|e| { libraries.entry(e.clone()).or_default(); });
+  rescope!(col_content,
+    for (name, origin) in libraries
+    {
+      let opt_mode = overrides.lock().unwrap().get(&name).copied();
+      let _ = shared::fltk::overrides::entry(&name, &origin, opt_mode, #[clown] move |opt_mode|
+      {
+        let name = honk!(name).clone();
+        let mut overrides = honk!(overrides).lock().unwrap();
+        match opt_mode
+        {
+          Some(mode) => { overrides.insert(name.clone(), mode); },
+          None => { overrides.remove(&name); },
+        } // match
+        match shared::wine::dll::write(&honk!(path_file_env), &honk!(path_dir_wine), &overrides)
+        {
+          Ok(()) => log_status!("Set override of '{}' to '{}'", name, opt_mode.map(|e| e.label()).unwrap_or("Default")),
+          Err(e) => log_status!("Could not write overrides: {}", e),
+        } // match
+      });
+    } // for
+  );
+  // Add a library that was not found
+  btn_add.clone().with_color(Color::Green).set_callback(#[clown] move |_|
+  {
+    let Some(name) = dialog::input_default("Enter the name of the library, e.g., d3d9", "") else { return; };
+    let name = name.trim().to_lowercase().trim_end_matches(".dll").to_string();
+    if name.is_empty() { return; } // if
+    let mut overrides = honk!(overrides).lock().unwrap();
+    overrides.insert(name.clone(), shared::wine::dll::Mode::NativeBuiltin);
+    log_err_status!(shared::wine::dll::write(&honk!(path_file_env), &honk!(path_dir_wine), &overrides));
+    tx.send_awake(common::Msg::DrawWineDllOverrides);
+  });
+} // fn dll_overrides() }}}
+
+// pub fn fonts() {{{
+pub fn fonts(tx: Sender<common::Msg>, title: &str)
+{
+  let ui = crate::GUI.lock().unwrap().ui.clone()(title);
+  // Layout
+  row!(row,
+    column!(col,
+      col.fixed(&Frame::default().with_align(Align::Inside | Align::Left).with_label("Installed Fonts"), dimm::height_text());
+      add!(col, browser_fonts, fltk::browser::HoldBrowser::default());
+      col.fixed(&Frame::default().with_align(Align::Inside | Align::Left).with_label("Font Substitutes"), dimm::height_text());
+      fixed!(col, browser_substitutes, fltk::browser::HoldBrowser::default(), dimm::height_wizard() / 5);
+      row!(row_substitutes,
+        let btn_substitute_add = shared::fltk::button::wide::default().with_label("Add substitute");
+        let btn_substitute_erase = shared::fltk::button::wide::default().with_label("Erase substitute");
+      );
+      col.fixed(&row_substitutes, dimm::height_button_wide());
+    );
+    column!(col_btn,
+      fixed!(col_btn, btn_add, shared::fltk::button::rect::add(), dimm::height_button_rec());
+      fixed!(col_btn, btn_del, shared::fltk::button::rect::del(), dimm::height_button_rec());
+      fixed!(col_btn, btn_register, shared::fltk::button::rect::check(), dimm::height_button_rec());
+      col_btn.add(&Frame::default());
+    );
+    row.fixed(&col_btn, dimm::width_button_rec());
+  );
+  // Buttons
+  hover_blink!(btn_add);
+  hover_blink!(btn_del);
+  hover_blink!(btn_register);
+  // Configure footer
+  ui.btn_next.clone().deactivate();
+  ui.btn_prev.clone().emit(tx, common::Msg::DrawWineConfigure);
+  let path_dir_project = match db::global::get_current_project()
+  {
+    Ok(project) => project.path_dir_project,
+    Err(e) => log_return_void!("{}", e),
+  }; // match
+  // Fonts grouped by family, the lines of the families have no font
+  let families = lib::font::list(&path_dir_project).unwrap_or_else(|e| { log_status!("Could not list fonts: {}", e); vec![] });
+  let mut lines : Vec<Option<lib::font::Font>> = vec![];
+  let mut browser_fonts = browser_fonts.clone();
+  browser_fonts.set_text_size(dimm::height_text());
+  for family in &families
+  {
+    browser_fonts.add(&format!("@b@.{}", family.name));
+    lines.push(None);
+    for font in &family.fonts
+    {
+      browser_fonts.add(&format!("@.    {}  ({}{})"
+        , if font.style.is_empty() { &font.full_name } else { &font.style }
+        , font.file_name
+        , if font.is_registered { "" } else { ", not registered" }
+      ));
+      lines.push(Some(font.clone()));
+    } // for
+  } // for
+  if families.is_empty() { browser_fonts.add("@.No fonts in the windows/Fonts directory of the prefix"); } // if
+  let lines = Arc::new(lines);
+  // Font names replaced by other fonts
+  let substitutes : Vec<(String, String)> = lib::font::substitutes(&path_dir_project).unwrap_or_default().into_iter().collect();
+  let mut browser_substitutes = browser_substitutes.clone();
+  browser_substitutes.set_text_size(dimm::height_text());
+  substitutes.iter().for_each(|(k, v)| browser_substitutes.add(&format!("@.{} -> {}", k, v)));
+  // Add fonts from disk
+  btn_add.clone().with_color(Color::Green).set_callback({
+    let path_dir_project = path_dir_project.clone();
+    move |_|
+    {
+      let mut chooser = dialog::FileChooser::new("."
+        , "*.{ttf,otf,ttc,otc,TTF,OTF,TTC,OTC}"
+        , dialog::FileChooserType::Multi
+        , "Pick the fonts to install into the prefix");
+      chooser.show();
+      while chooser.shown() { fltk::app::wait(); } // while
+      let paths : Vec<PathBuf> = (1..=chooser.count()).filter_map(|e| chooser.value(e)).map(PathBuf::from).collect();
+      if paths.is_empty() { return; } // if
+      let path_dir_project = path_dir_project.clone();
+      tx.send_awake(common::Msg::WindDeactivate);
+      std::thread::spawn(move ||
+      {
+        for path_file in &paths
+        {
+          match lib::font::add(&path_dir_project, path_file)
+          {
+            Ok(font) => log_status!("Installed font '{}'", font.full_name),
+            Err(e) => log_status!("Could not install font '{}': {}", path_file.string(), e),
+          } // match
+        } // for
+        tx.send_activate(common::Msg::DrawWineFonts);
+      });
+    }
+  });
+  // Remove the selected font
+  btn_del.clone().with_color(Color::Red).set_callback({
+    let path_dir_project = path_dir_project.clone();
+    let browser_fonts = browser_fonts.clone();
+    let lines = lines.clone();
+    move |_|
+    {
+      // Lines start at one
+      let index = browser_fonts.value() as usize;
+      let Some(Some(font)) = index.checked_sub(1).and_then(|e| lines.get(e)) else { log_status!("No font was selected"); return; };
+      if dialog::choice2_default(&format!("Remove font '{}' from the prefix?", font.full_name), "No", "Yes", "") != Some(1)
+      {
+        return;
+      } // if
+      match lib::font::remove(&path_dir_project, font)
+      {
+        Ok(()) => log_status!("Removed font '{}'", font.full_name),
+        Err(e) => log_status!("Could not remove font '{}': {}", font.full_name, e),
+      } // match
+      tx.send_awake(common::Msg::DrawWineFonts);
+    }
+  });
+  // Register the fonts that were copied without registering them
+  btn_register.clone().with_color(Color::Blue).set_callback({
+    let path_dir_project = path_dir_project.clone();
+    let lines = lines.clone();
+    move |_|
+    {
+      let fonts : Vec<lib::font::Font> = lines.iter().flatten().filter(|e| ! e.is_registered).cloned().collect();
+      if fonts.is_empty() { log_status!("All fonts are registered"); return; } // if
+      match lib::font::register(&path_dir_project, &fonts)
+      {
+        Ok(()) => log_status!("Registered {} font(s)", fonts.len()),
+        Err(e) => log_status!("Could not register fonts: {}", e),
+      } // match
+      tx.send_awake(common::Msg::DrawWineFonts);
+    }
+  });
+  // Replace a font name, the selected font is the default replacement
+  btn_substitute_add.clone().with_color(Color::Green).set_callback({
+    let path_dir_project = path_dir_project.clone();
+    let browser_fonts = browser_fonts.clone();
+    let lines = lines.clone();
+    move |_|
+    {
+      let Some(name) = dialog::input_default("Enter the font to replace, e.g., MS Gothic", "") else { return; };
+      let name = name.trim().to_string();
+      if name.is_empty() { return; } // if
+      let family = (browser_fonts.value() as usize).checked_sub(1)
+        .and_then(|e| lines.iter().skip(e).flatten().next())
+        .map(|e| e.family.clone())
+        .unwrap_or_default();
+      let Some(replacement) = dialog::input_default(&format!("Enter the font that replaces '{}'", name), &family) else { return; };
+      let replacement = replacement.trim().to_string();
+      if replacement.is_empty() { return; } // if
+      match lib::font::set_substitute(&path_dir_project, &name, Some(&replacement))
+      {
+        Ok(()) => log_status!("'{}' is replaced by '{}'", name, replacement),
+        Err(e) => log_status!("Could not set substitute: {}", e),
+      } // match
+      tx.send_awake(common::Msg::DrawWineFonts);
+    }
+  });
+  btn_substitute_erase.clone().with_color(Color::Red).set_callback({
+    let path_dir_project = path_dir_project.clone();
+    let browser_substitutes = browser_substitutes.clone();
+    move |_|
+    {
+      let index = browser_substitutes.value() as usize;
+      let Some((name, _)) = index.checked_sub(1).and_then(|e| substitutes.get(e)) else { log_status!("No substitute was selected"); return; };
+      match lib::font::set_substitute(&path_dir_project, name, None)
+      {
+        Ok(()) => log_status!("Erased the substitute of '{}'", name),
+        Err(e) => log_status!("Could not erase substitute: {}", e),
+      } // match
+      tx.send_awake(common::Msg::DrawWineFonts);
+    }
+  });
+} // fn fonts() }}}
+
+// fn snapshot_before() {{{
+// Snapshots the wine prefix before an action that modifies it
+fn snapshot_before(label: &str)
+{
+  log_status!("Creating snapshot of the wine prefix...");
+  match lib::snapshot::create(label)
+  {
+    Ok(_) => log!("Created snapshot '{}'", label),
+    Err(e) => log_status!("Could not create snapshot of the wine prefix: {}", e),
+  } // match
+} // fn snapshot_before() }}}
+
 // fn configure_entry() {{{
 fn configure_entry(tx: Sender<common::Msg>
   , label: &str
@@ -324,6 +652,7 @@ fn configure_entry(tx: Sender<common::Msg>
     tx.send_awake(common::Msg::WindDeactivate);
     std::thread::spawn(move ||
     {
+      snapshot_before(&format!("Before {}", args_owned.join(" ")));
       let mut json_args = json!({});
       json_args["op"] = "install".into();
       json_args["install"]["op"] = "install".into();
@@ -377,6 +706,13 @@ pub fn configure(tx: Sender<common::Msg>, title: &str)
 
     if ! path_dir_wine_prefix.exists()
     {
+      // Templates avoid installing the same libraries for every project
+      if lib::template::list().is_ok_and(|e| ! e.is_empty())
+      && dialog::choice2_default("Create an empty wine prefix or initialize it from a template?", "Empty", "Template", "") == Some(1)
+      {
+        frame::template::template();
+        return;
+      } // if
       log_status!("Wine prefix does not exist, creating...");
       tx.send_awake(common::Msg::WindDeactivate);
       std::thread::spawn(move ||
@@ -403,7 +739,11 @@ pub fn configure(tx: Sender<common::Msg>, title: &str)
       , || Some(vec!["winetricks".into(), "-f".into(), "vkd3d".into()])
     );
     let _ = configure_entry(tx.clone(),  "Run regedit", || Some(vec!["wine".into(), "regedit".into()]));
+    let mut btn = configure_entry(tx.clone(),  "Edit the registry", || None);
+    btn.set_callback(|_| frame::registry::registry());
     let _ = configure_entry(tx.clone(),  "Run add/remove programs", || Some(vec!["wine".into(), "uninstaller".into()]));
+    let mut btn = configure_entry(tx.clone(),  "Installed programs", || None);
+    btn.set_callback(|_| frame::uninstall::uninstall());
     let _ = configure_entry(tx.clone(),  "Run winetricks GUI", || Some(vec!["winetricks".into(), "--gui".into()]));
     let _ = configure_entry(tx.clone(),  "Run a custom winetricks command" , ||
       dialog::input_default("Enter the winetricks command to execute", "").map(|e| vec!["winetricks".into(), "-f".into(), e])
@@ -412,25 +752,75 @@ pub fn configure(tx: Sender<common::Msg>, title: &str)
       dialog::input_default("Enter the wine command to execute", "").map(|e| vec!["wine".into(), e])
     );
     let mut btn = configure_entry(tx.clone(),  "Configure environment", || None);
-    btn.emit(tx, common::Msg::DrawWineEnvironment);
+    btn.emit(tx.clone(), common::Msg::DrawWineEnvironment);
+    let mut btn = configure_entry(tx.clone(),  "Configure graphics and synchronization", || None);
+    btn.emit(tx.clone(), common::Msg::DrawWineGraphics);
+    let mut btn = configure_entry(tx.clone(),  "Configure DLL overrides", || None);
+    btn.emit(tx.clone(), common::Msg::DrawWineDllOverrides);
+    let mut btn = configure_entry(tx.clone(),  "Configure fonts", || None);
+    btn.emit(tx.clone(), common::Msg::DrawWineFonts);
+    let mut btn = configure_entry(tx.clone(),  "Snapshots of the wine prefix", || None);
+    btn.set_callback(|_| frame::snapshot::snapshot());
+    let mut btn = configure_entry(tx.clone(),  "Wine prefix templates", || None);
+    btn.set_callback(|_| frame::template::template());
+    let mut btn = configure_entry(tx.clone(),  "Compare wine distributions", || None);
+    btn.set_callback(|_| frame::distribution::distribution());
   );
 } // fn: configure }}}
 
+// fn winetricks_executables() {{{
+// Executables that can be inspected, the selected rom comes first
+fn winetricks_executables() -> Vec<PathBuf>
+{
+  let mut executables : Vec<PathBuf> = gameimage::search::search_local("rom")
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|e| e.extension().is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case("exe")))
+    .collect();
+  if let Ok(project) = db::project::current()
+  && let Ok(path_file_rom) = project.get_path_relative(db::project::EntryName::PathFileRom)
+  {
+    executables.retain(|e| *e != path_file_rom);
+    executables.insert(0, path_file_rom);
+  } // if
+  executables
+} // fn winetricks_executables() }}}
+
+// fn winetricks_detected() {{{
+// Verbs for the dependencies of the executable, with the reason
+fn winetricks_detected(path_file_exe : &PathBuf) -> Vec<lib::dependencies::Recommendation>
+{
+  let path_dir_project = match db::global::get_current_project()
+  {
+    Ok(project) => project.path_dir_project,
+    Err(e) => { log!("Could not read current project: {}", e); return vec![]; },
+  }; // match
+  match lib::dependencies::recommend(&path_dir_project.join(path_file_exe))
+  {
+    Ok(recommendations) => recommendations,
+    Err(e) => { log!("Could not inspect '{}': {}", path_file_exe.string(), e); vec![] },
+  } // match
+} // fn winetricks_detected() }}}
+
 // pub fn winetricks() {{{
 pub fn winetricks(tx: Sender<common::Msg>, title: &str)
 {
   static YEAR: LazyLock<Mutex<u32>> = LazyLock::new(|| Mutex::new(2024));
+  static EXECUTABLE: LazyLock<Mutex<Option<PathBuf>>> = LazyLock::new(|| Mutex::new(None));
   let ui = crate::GUI.lock().unwrap().ui.clone()(title);
   // Layout
   row!(row,
     column!(col,
       col.fixed(&fltk::frame::Frame::default().with_label("Select the Game Release Year"), dimm::height_text());
       fixed!(col, menu_year, fltk::menu::MenuButton::default(), dimm::height_button_wide());
+      col.fixed(&fltk::frame::Frame::default().with_label("Select the Executable to Inspect"), dimm::height_text());
+      fixed!(col, menu_executable, fltk::menu::MenuButton::default(), dimm::height_button_wide());
       col.fixed(&fltk::frame::Frame::default().with_label("Recommended Libraries"), dimm::height_text());
       add!(col, browser, fltk::browser::CheckBrowser::default());
     );
     column!(col_btn,
       fixed!(col_btn, btn_install, shared::fltk::button::rect::install(), dimm::width_button_rec());
+      fixed!(col_btn, btn_catalog, shared::fltk::button::rect::search(), dimm::width_button_rec());
       col_btn.add(&Frame::default());
     );
     row.fixed(&col_btn, dimm::width_button_rec());
@@ -452,10 +842,40 @@ pub fn winetricks(tx: Sender<common::Msg>, title: &str)
       honk!(tx).send(common::Msg::DrawWineTricks)
     }
   });
-  // Recommend libraries by year
-  let vec_lib = get_recomends_winetricks(*YEAR.lock().unwrap());
+  // Select executable
+  let executables = winetricks_executables();
+  let mut executable = EXECUTABLE.lock().unwrap();
+  if executable.as_ref().is_none_or(|e| ! executables.contains(e))
+  {
+    *executable = executables.first().cloned();
+  } // if
+  let mut menu_executable = menu_executable.clone();
+  executables.iter().for_each(|e| { menu_executable.add_choice(&e.string().replace("/", "\\/")); });
+  menu_executable.set_label(&executable.as_ref().map(|e| e.string()).unwrap_or("No executable found".into()));
+  menu_executable.set_callback(#[clown] move |e|
+  {
+    let index = e.value();
+    if index < 0 { return; } // if
+    *EXECUTABLE.lock().unwrap() = honk!(executables).get(index as usize).cloned();
+    honk!(tx).send(common::Msg::DrawWineTricks)
+  });
+  // Recommend libraries from the dependencies of the executable, then by year
+  let detected = executable.as_ref().map(|e| winetricks_detected(e)).unwrap_or_default();
+  let year = *YEAR.lock().unwrap();
   let mut browser = browser.clone();
-  for lib in vec_lib { browser.add(lib, true); }
+  for recommendation in &detected
+  {
+    browser.add(&format!("{}  ({})", recommendation.verb, recommendation.reason), true);
+  } // for
+  for lib in get_recomends_winetricks(year).into_iter().filter(|e| ! detected.iter().any(|d| d.verb == *e))
+  {
+    browser.add(&format!("{}  (common for games released in {})", lib, year), detected.is_empty());
+  } // for
+  // Search all the verbs of winetricks
+  hover_blink!(btn_catalog);
+  let mut btn_catalog = btn_catalog.clone().with_color(Color::Blue);
+  btn_catalog.set_tooltip("Search all winetricks verbs");
+  btn_catalog.set_callback(|_| frame::winetricks::catalog());
   // Install button to the right
   btn_install.clone()
     .right_of(&col.as_base_widget(), dimm::border())
@@ -467,10 +887,14 @@ pub fn winetricks(tx: Sender<common::Msg>, title: &str)
       let clone_browser = browser.clone();
       std::thread::spawn(move ||
       {
-        // Must install one at the time, winetricks exits if at least one verb fails
-        for lib in &mut (1..=clone_browser.size())
+        let libs : Vec<String> = (1..=clone_browser.size())
           .filter(|e| clone_browser.checked(*e as i32))
-          .map(|e| clone_browser.text(e as i32).unwrap())
+          .filter_map(|e| clone_browser.text(e as i32))
+          .filter_map(|e| e.split_whitespace().next().map(|e| e.to_string()))
+          .collect();
+        if ! libs.is_empty() { snapshot_before(&format!("Before winetricks {}", libs.join(" "))); } // if
+        // Must install one at the time, winetricks exits if at least one verb fails
+        for lib in &libs
         {
           log_status!("Installing '{}'", lib);
           let mut json_args = json!({});
@@ -512,8 +936,10 @@ fn rom_exec(path_file_item: PathBuf) -> anyhow::Result<()>
 {
   // Set the selected binary as default
   gameimage::select::select("rom", &path_file_item)?;
-  // Test the selected binary
-  gameimage::test::test()?;
+  // Test with the distribution of the project
+  lib::distribution::activate_project()?;
+  // Test the selected binary, its exit code is in the project history
+  gameimage::test::test();
   Ok(())
 }
 // rom_exec() }}}
@@ -532,6 +958,7 @@ fn rom_add() -> anyhow::Result<()>
   while chooser.shown() { std::thread::sleep(std::time::Duration::from_millis(100)) } // while
   // Check if choice is valid
   let str_choice = chooser.value(1).ok_or(ah!("No file selected"))?;
+  snapshot_before(&format!("Before installing {}", PathBuf::from(&str_choice).file_name_string()));
   // Execute wine
   gameimage::install::wine(vec![str_choice])?;
   Ok(())
@@ -653,6 +1080,51 @@ fn rom_entry(tx: Sender<common::Msg>
   });
 } // rom_entry() }}}
 
+// rom_shortcut() {{{
+// Writes the alias and arguments of a shortcut to the executable it targets and makes the executable
+// selectable in the launcher
+fn rom_shortcut(shortcut : &lib::lnk::Shortcut) -> anyhow::Result<()>
+{
+  let key = shortcut.path_file_target.string();
+  shared::db::kv::write(&get_path_db_alias()?, &key, &shortcut.alias())?;
+  if ! shortcut.arguments().trim().is_empty()
+  {
+    shared::db::kv::write(&get_path_db_args()?, &key, &shortcut.arguments())?;
+  } // if
+  // The launcher starts from the directory of the executable without a working directory
+  match shortcut.working_dir()
+  {
+    Some(path_dir_working) => shared::db::kv::write(&get_path_db_dir()?, &key, &path_dir_working.string())?,
+    None => { let _ = shared::db::kv::erase(&get_path_db_dir()?, key.clone()); },
+  } // match
+  shared::db::kv::write(&get_path_db_executable()?, &key, &"1".to_string())?;
+  Ok(())
+} // rom_shortcut() }}}
+
+// Inspected executables of the project, until the list is refreshed
+static CANDIDATES : LazyLock<Mutex<HashMap<PathBuf, lib::executable::Candidate>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// rom_candidates_clear() {{{
+// Executables are inspected again on the next draw
+fn rom_candidates_clear()
+{
+  CANDIDATES.lock().unwrap().clear();
+} // rom_candidates_clear() }}}
+
+// rom_candidate() {{{
+// Inspects an executable of the project, the results are cached until the list is refreshed
+fn rom_candidate(path_file : &PathBuf) -> Option<lib::executable::Candidate>
+{
+  if let Some(candidate) = CANDIDATES.lock().unwrap().get(path_file)
+  {
+    return Some(candidate.clone());
+  } // if
+  let path_dir_project = db::global::get_current_project().ok()?.path_dir_project;
+  let candidate = lib::executable::inspect(&path_dir_project, path_file);
+  CANDIDATES.lock().unwrap().insert(path_file.clone(), candidate.clone());
+  Some(candidate)
+} // rom_candidate() }}}
+
 // rom_search() {{{
 fn rom_search(query: &str) -> Vec<PathBuf>
 {
@@ -662,18 +1134,17 @@ fn rom_search(query: &str) -> Vec<PathBuf>
     .filter(|e| e.string().to_lowercase().contains(&query.to_lowercase()))
     .map(|e| e.clone())
     .collect();
+  // Likely game binaries first, ties by number of components and string length
+  let scores : HashMap<PathBuf, i32> = results.iter()
+    .map(|e| (e.clone(), rom_candidate(e).map(|e| e.score).unwrap_or(i32::MIN)))
+    .collect();
   results.sort_by(|a, b|
   {
     let a_components = a.components().count();
     let b_components = b.components().count();
-    if a_components == b_components
-    {
-      a.string().len().cmp(&b.string().len())
-    } // if
-    else
-    {
-      a_components.cmp(&b_components)
-    } // else
+    scores[b].cmp(&scores[a])
+      .then(a_components.cmp(&b_components))
+      .then(a.string().len().cmp(&b.string().len()))
   });
   results
 } // rom_search() }}}
@@ -759,15 +1230,23 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
       col.fixed(&col_paginator, col_paginator.h());
     );
     row.add(&col);
+    let mut display_details = TextDisplay::default()
+      .with_color(Color::BackGround)
+      .with_frame(FrameType::BorderBox);
+    row.fixed(&display_details, dimm::width_wizard() / 3);
     column!(col_sidebar,
       fixed!(col_sidebar, btn_add, shared::fltk::button::rect::add(), dimm::height_button_rec());
       fixed!(col_sidebar, btn_refresh, shared::fltk::button::rect::refresh(), dimm::height_button_rec());
+      fixed!(col_sidebar, btn_history, shared::fltk::button::rect::list(), dimm::height_button_rec());
+      fixed!(col_sidebar, btn_shortcut, shared::fltk::button::rect::search(), dimm::height_button_rec());
     );
     row.fixed(&col_sidebar, dimm::width_button_rec());
   );
   // Configure buttons
   hover_blink!(btn_add);
   hover_blink!(btn_refresh);
+  hover_blink!(btn_history);
+  hover_blink!(btn_shortcut);
   // Configure scroll
   scroll.set_type(fltk::group::ScrollType::VerticalAlways);
   scroll.resize_callback({
@@ -844,6 +1323,22 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
         , &mut vec_radio_path.lock().unwrap())
     } // for
   );
+  // Configure details of the selected executable
+  display_details.wrap_mode(WrapMode::AtBounds, 0);
+  display_details.set_text_size(dimm::height_text());
+  display_details.set_buffer(TextBuffer::default());
+  let f_details = move |path_file : &PathBuf|
+  {
+    // Nothing is selected
+    let opt_candidate = if path_file.as_os_str().is_empty() { None } else { rom_candidate(path_file) };
+    let text = match opt_candidate
+    {
+      Some(candidate) => candidate.details(),
+      None => "Select an executable to show its details".to_string(),
+    }; // match
+    if let Some(mut buffer) = display_details.buffer() { buffer.set_text(&text); } // if
+  };
+  f_details(&SELECTED.lock().unwrap().clone());
   // Set callbacks for toggle group
   for (btn, path) in vec_radio_path.lock().unwrap().iter_mut()
   {
@@ -851,11 +1346,13 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
     {
       btn.set_value(true);
     } // if
+    let f_details = f_details.clone();
     btn.set_callback(#[clown] move |e|
     {
       for i in honk!(vec_radio_path).lock().unwrap().iter_mut() { i.0.toggle(false); }
       *SELECTED.lock().unwrap() = honk!(path).clone();
       e.toggle(true);
+      f_details(&honk!(path));
     });
   } // for
   // Add new item
@@ -871,7 +1368,44 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
   let clone_tx = tx.clone();
   btn_refresh.clone()
     .with_color(Color::Blue)
-    .with_callback(move |_| { clone_tx.send_awake(common::Msg::DrawWineRom); });
+    .with_callback(move |_|
+    {
+      // Inspect the executables again
+      rom_candidates_clear();
+      clone_tx.send_awake(common::Msg::DrawWineRom);
+    });
+  // Show previous test runs
+  btn_history.clone()
+    .with_color(Color::Blue)
+    .with_callback(|_| frame::history::history());
+  // Show shortcuts of the prefix, using one fills the alias and arguments of its executable
+  let clone_tx = tx.clone();
+  btn_shortcut.clone()
+    .with_color(Color::Blue)
+    .with_callback(move |_|
+    {
+      let Ok(project) = db::global::get_current_project() else { log_status!("Could not read current project"); return; };
+      frame::shortcut::shortcut(lib::lnk::list(&project.path_dir_project), move |shortcut|
+      {
+        if let Err(e) = rom_shortcut(shortcut)
+        {
+          log_status!("Could not use shortcut '{}': {}", shortcut.alias(), e);
+          return;
+        } // if
+        match shortcut.working_dir_outside()
+        {
+          Some(working_dir) => log_status!("Added launch profile '{}' for '{}', it starts from the directory of the executable instead of '{}'"
+            , shortcut.alias(), shortcut.path_file_target.string(), working_dir),
+          None => log_status!("Added launch profile '{}' for '{}'", shortcut.alias(), shortcut.path_file_target.string()),
+        } // match
+        *SELECTED.lock().unwrap() = shortcut.path_file_target.clone();
+        // Show the modified entries, which include the new profile
+        *RESULTS.lock().unwrap() = rom_modified();
+        *SHOW_SELECTED.lock().unwrap() = true;
+        *PAGE.lock().unwrap() = 0;
+        clone_tx.send_awake(common::Msg::DrawWineRom);
+      });
+    });
   // Go to next frame iff a default executable was selected
   let clone_tx = tx.clone();
   ui.btn_next.clone().set_callback(move |_|
@@ -889,17 +1423,136 @@ pub fn rom(tx: Sender<common::Msg>, title: &str)
       log_status!("{}", e);
       return;
     } // if
-    clone_tx.send_awake(common::Msg::DrawWineCompress);
+    clone_tx.send_awake(common::Msg::DrawWineSlim);
   });
 
 } // }}}
 
+// pub fn slim() {{{
+// Finds content of the prefix that is not needed by the game, selected categories can be reviewed
+// with a dry run and removed before compression
+pub fn slim(tx: Sender<common::Msg>, title: &str)
+{
+  static GROUPS : LazyLock<Mutex<Option<Vec<lib::slim::Group>>>> = LazyLock::new(|| Mutex::new(None));
+  // Nothing is selected until the user reviewed the categories
+  static SELECTED : LazyLock<Mutex<Vec<usize>>> = LazyLock::new(|| Mutex::new(vec![]));
+  let ui = crate::GUI.lock().unwrap().ui.clone()(title);
+  // Layout
+  column!(col,
+    hpack!(col_content,
+      col_content.set_spacing(dimm::border_half());
+    );
+    col.fixed(&col_content, (dimm::height_button_wide() + dimm::border_half()) * lib::slim::CATEGORIES.len() as i32);
+    col.fixed(&shared::fltk::separator::horizontal(col.w()), dimm::height_sep());
+    let mut display_report = TextDisplay::default()
+      .with_color(Color::BackGround)
+      .with_frame(FrameType::BorderBox);
+    col.add(&display_report);
+    row!(row_buttons,
+      fixed!(row_buttons, btn_analyze, shared::fltk::button::wide::default().with_label("Analyze"), dimm::width_button_wide()*2);
+      fixed!(row_buttons, btn_report, shared::fltk::button::wide::default().with_label("Dry run"), dimm::width_button_wide()*2);
+      fixed!(row_buttons, btn_remove, shared::fltk::button::wide::default().with_label("Remove selected"), dimm::width_button_wide()*3);
+      row_buttons.add(&Frame::default());
+    );
+    col.fixed(&row_buttons, dimm::height_button_wide());
+  );
+  // Report
+  display_report.set_text_font(fltk::enums::Font::Courier);
+  display_report.wrap_mode(WrapMode::None, 0);
+  display_report.set_buffer(TextBuffer::default());
+  // Configure footer, the prefix can change before coming back so it is analyzed again
+  ui.btn_prev.clone().set_callback(move |_| { *GROUPS.lock().unwrap() = None; tx.send_awake(common::Msg::DrawWineRom); });
+  ui.btn_next.clone().set_callback(move |_| { *GROUPS.lock().unwrap() = None; tx.send_awake(common::Msg::DrawWineCompress); });
+  // Analyze in the background and draw the screen again
+  let f_analyze = move ||
+  {
+    tx.send_awake(common::Msg::WindDeactivate);
+    log_status!("Analyzing the wine prefix...");
+    std::thread::spawn(move ||
+    {
+      match db::global::get_current_project()
+      {
+        Ok(project) => *GROUPS.lock().unwrap() = Some(lib::slim::analyze(&project.path_dir_project)),
+        Err(e) => log_status!("Could not read current project: {}", e),
+      } // match
+      tx.send_activate(common::Msg::DrawWineSlim);
+    });
+  };
+  btn_analyze.clone().with_color(Color::Blue).set_callback(move |_| f_analyze());
+  let Some(groups) = GROUPS.lock().unwrap().clone() else
+  {
+    display_report.insert("Analyzing the wine prefix...");
+    f_analyze();
+    return;
+  };
+  // Categories
+  rescope!(col_content,
+    for group in groups.iter()
+    {
+      let index = group.index;
+      let mut btn_check = shared::fltk::button::rect::checkbutton()
+        .with_size(0, dimm::height_button_wide())
+        .with_align(Align::Inside | Align::Left)
+        .with_color(Color::BackGround)
+        .with_label(&format!(" {}", group.summary()));
+      btn_check.set_tooltip(group.category().description);
+      btn_check.set_value(SELECTED.lock().unwrap().contains(&index));
+      btn_check.set_callback(move |e|
+      {
+        let mut selected = SELECTED.lock().unwrap();
+        selected.retain(|e| *e != index);
+        if e.is_checked() { selected.push(index); } // if
+      });
+    } // for
+  );
+  let size : u64 = groups.iter().map(|e| e.size()).sum();
+  display_report.insert(&format!("Found {} of removable content, select the categories to remove and click on dry run to review them\n"
+    , lib::format::format_size(size)
+  ));
+  // Dry run
+  btn_report.clone().with_color(Color::Blue).set_callback({
+    let groups = groups.clone();
+    move |_|
+    {
+      let report = lib::slim::report(&groups, &SELECTED.lock().unwrap());
+      log!("{}", report);
+      if let Some(mut buffer) = display_report.buffer() { buffer.set_text(&report); } // if
+    }
+  });
+  // Remove the selected categories
+  btn_remove.clone().with_color(Color::Red).set_callback(move |_|
+  {
+    let selected = SELECTED.lock().unwrap().clone();
+    let size : u64 = groups.iter().filter(|e| selected.contains(&e.index)).map(|e| e.size()).sum();
+    if size == 0 { log_status!("Nothing to remove"); return; } // if
+    if dialog::choice2_default(&format!("Remove {} from the wine prefix?", lib::format::format_size(size)), "No", "Yes", "") != Some(1)
+    {
+      return;
+    } // if
+    let groups = groups.clone();
+    tx.send_awake(common::Msg::WindDeactivate);
+    std::thread::spawn(move ||
+    {
+      snapshot_before("Before removing unneeded content");
+      match db::global::get_current_project().and_then(|e| lib::slim::remove(&e.path_dir_project, &groups, &selected))
+      {
+        Ok(size) => log_status!("Removed {} from the wine prefix", lib::format::format_size(size)),
+        Err(e) => log_status!("Could not remove content of the wine prefix: {}", e),
+      } // match
+      *GROUPS.lock().unwrap() = Some(db::global::get_current_project()
+        .map(|e| lib::slim::analyze(&e.path_dir_project))
+        .unwrap_or_default());
+      tx.send_activate(common::Msg::DrawWineSlim);
+    });
+  });
+} // fn slim() }}}
+
 // pub fn compress() {{{
 pub fn compress(tx: Sender<common::Msg>, title: &str)
 {
   wizard::compress::compress(tx.clone()
     , title
-    , common::Msg::DrawWineRom
+    , common::Msg::DrawWineSlim
     , common::Msg::DrawWineCompress
     , common::Msg::DrawCreator);
 } // }}}
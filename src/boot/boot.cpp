@@ -117,6 +117,32 @@ std::vector<std::string> args(fs::path const& path_dir_self, fs::path const& pat
   return ns_string::split(*expected_arguments, ' ');
 } // args() }}}
 
+// dir() {{{
+std::optional<fs::path> dir(fs::path const& path_dir_self, fs::path const& path_file_executable)
+{
+  // Standard path for the working directories of the executables, e.g., from windows shortcuts
+  fs::path path_file_dir = path_dir_self / "gameimage.dir.json";
+
+  // Query if the executable has a working directory
+  auto expected_dir = ns_db::from_file<std::string>(path_file_dir, [&](auto&& db)
+  {
+    return db.template value<std::string>(path_file_executable);
+  }, ns_db::Mode::READ);
+
+  // Check if has a working directory
+  return_if(not expected_dir.has_value() or expected_dir->empty()
+    , (ns_log::write('i', "No working directory for ", path_file_executable), std::nullopt)
+  )
+
+  // The directory is relative to the project
+  fs::path path_dir_working = path_dir_self / *expected_dir;
+  return_if(not fs::is_directory(path_dir_working)
+    , (ns_log::write('e', "Working directory '", path_dir_working, "' does not exist"), std::nullopt)
+  )
+
+  return path_dir_working;
+} // dir() }}}
+
 // env() {{{
 void env(fs::path const& path_dir_self)
 {
@@ -196,8 +222,10 @@ void boot_wine(ns_db::ns_project::Project& db_project, fs::path const& path_dir_
     path_file_rom = path_dir_self / path_file_rom_relative;
   } // if
 
-  // Enter directory of rom file
-  fs::current_path(ns_fs::ns_path::dir_exists<true>(path_file_rom.parent_path())._ret);
+  // Enter the working directory of the rom file, or its directory
+  fs::current_path(dir(path_dir_self, path_file_rom_relative)
+    .value_or(ns_fs::ns_path::dir_exists<true>(path_file_rom.parent_path())._ret)
+  );
 
   // Start application
   std::ignore = ns_subprocess::Subprocess(ns_env::get_or_throw("FIM_BINARY_WINE"))
@@ -5,6 +5,8 @@
 
 #pragma once
 
+#include <regex>
+
 #include "../enum.hpp"
 
 #include "../std/filesystem.hpp"
@@ -12,6 +14,7 @@
 #include "../lib/db/build.hpp"
 #include "../lib/db/project.hpp"
 #include "../lib/subprocess.hpp"
+#include "../lib/ipc.hpp"
 
 namespace ns_compress
 {
@@ -105,6 +108,32 @@ inline void validate(ns_db::ns_build::Metadata& db_metadata, ns_db::ns_project::
 
 } // validate() }}}
 
+// send_progress() {{{
+// Forwards the percentage found in a line of the compressor output, e.g., '[===   ] 10/40  25%'
+inline void send_progress(std::string const& line)
+{
+  static std::regex const regex_percentage{R"((\d{1,3})(?:\.\d+)?%)"};
+  static int percentage_prev = -1;
+  ns_log::write('i', "compress: ", line);
+  // Last percentage in the line
+  std::optional<int> opt_percentage;
+  for(auto it = std::sregex_iterator(line.begin(), line.end(), regex_percentage); it != std::sregex_iterator(); ++it)
+  {
+    opt_percentage = std::stoi((*it)[1].str());
+  } // for
+  qreturn_if(not opt_percentage or *opt_percentage > 100 or *opt_percentage == percentage_prev);
+  percentage_prev = *opt_percentage;
+  // No frontend is listening when called from the command line
+  try
+  {
+    ns_ipc::ipc().send(*opt_percentage);
+  } // try
+  catch(std::exception const& e)
+  {
+    ns_log::write('d', "Could not send progress: ", e.what());
+  } // catch
+} // send_progress() }}}
+
 // compress() {{{
 inline decltype(auto) compress()
 {
@@ -132,15 +161,19 @@ inline decltype(auto) compress()
   // Execute portal
   auto f_portal = []<typename... Args>(Args&&... args)
   {
-    std::ignore = ns_subprocess::Subprocess("/fim/static/fim_portal")
+    return ns_subprocess::Subprocess("/fim/static/fim_portal")
       .with_piped_outputs()
+      .with_stdout_handle([](auto&& e){ send_progress(e); })
+      .with_stderr_handle([](auto&& e){ send_progress(e); })
       .with_args(std::forward<Args>(args)...)
       .spawn()
       .wait();
   };
 
   // Compress
-  f_portal(db_build->path_file_image , "fim-layer" , "create" , db_metadata.path_dir_project_root , path_file_layer);
+  auto opt_code = f_portal(db_build->path_file_image , "fim-layer" , "create" , db_metadata.path_dir_project_root , path_file_layer);
+  ethrow_if(not opt_code, "Compressor was terminated abnormally");
+  ethrow_if(*opt_code != 0, "Compressor exited with code {}"_fmt(*opt_code));
 
   ns_log::write('i', "Wrote file to '", path_file_layer, "'");
 } // compress() }}}
@@ -5,9 +5,13 @@
 
 #pragma once
 
+#include <optional>
+#include <ranges>
+
 #include "../lib/image.hpp"
 #include "../lib/db/build.hpp"
 #include "../lib/subprocess.hpp"
+#include "../std/env.hpp"
 #include "../std/vector.hpp"
 
 namespace ns_desktop
@@ -35,7 +39,9 @@ enum class IntegrationItems
 
 
 // icon() {{{
-inline void icon(fs::path const& path_file_icon)
+// path_dir_hicolor is an optional directory with the layout of the hicolor theme,
+// '<size>x<size>/apps/icon.png' and 'scalable/apps/icon.svg', it is installed by desktop()
+inline void icon(fs::path const& path_file_icon, std::optional<fs::path> const& path_dir_hicolor)
 {
   // Open databases
   auto db_build = ns_db::ns_build::read();
@@ -47,8 +53,51 @@ inline void icon(fs::path const& path_file_icon)
 
   // Resize icon to specified icon path
   ns_image::resize(path_file_icon, path_file_icon_dst, 300, 450);
+
+  // Replace the icon set of a previous configuration
+  fs::path path_dir_hicolor_dst = db_build->path_dir_build / "desktop.hicolor";
+  lec(fs::remove_all, path_dir_hicolor_dst);
+  qreturn_if(not path_dir_hicolor);
+  fs::copy(ns_fs::ns_path::dir_exists<true>(*path_dir_hicolor)._ret
+    , path_dir_hicolor_dst
+    , fs::copy_options::recursive | fs::copy_options::overwrite_existing
+  );
+  ns_log::write('i', "Copied icon set to ", path_dir_hicolor_dst);
 } // icon() }}}
 
+// hicolor() {{{
+// Installs the icon set in the hicolor theme of the user with the icon name of the desktop entry
+// of flatimage, so menus use the icon of each size instead of scaling a single image
+inline void hicolor(std::string const& str_name, fs::path const& path_dir_hicolor)
+{
+  // Data directory of the user
+  fs::path path_dir_xdg_data;
+  if ( const char* str_dir_data = ns_env::get("XDG_DATA_HOME"); str_dir_data )
+  {
+    path_dir_xdg_data = str_dir_data;
+  } // if
+  else if ( const char* str_dir_home = ns_env::get("HOME"); str_dir_home )
+  {
+    path_dir_xdg_data = fs::path{str_dir_home} / ".local/share";
+  } // else if
+  ereturn_if(path_dir_xdg_data.empty(), "Could not determine XDG_DATA_HOME, is HOME set?");
+  fs::path path_dir_theme = path_dir_xdg_data / "icons" / "hicolor";
+
+  // Install each size and the scalable icon
+  for(auto&& path_file_src : fs::recursive_directory_iterator(path_dir_hicolor)
+    | std::views::filter([](auto&& e){ return e.is_regular_file(); })
+    | std::views::transform([](auto&& e){ return e.path(); })
+    | std::views::filter([](auto&& e){ return e.extension() == ".png" or e.extension() == ".svg"; }))
+  {
+    fs::path path_file_dst = path_dir_theme
+      / fs::relative(path_file_src.parent_path(), path_dir_hicolor)
+      / "flatimage_{}{}"_fmt(str_name, path_file_src.extension().string());
+    ns_log::exception([&]{ fs::create_directories(path_file_dst.parent_path()); });
+    ns_log::exception([&]{ fs::copy_file(path_file_src, path_file_dst, fs::copy_options::overwrite_existing); });
+    ns_log::write('i', "Installed icon ", path_file_dst);
+  } // for
+} // hicolor() }}}
+
 // desktop() {{{
 inline void desktop(std::string str_name, std::vector<IntegrationItems> vec_items)
 {
@@ -90,6 +139,13 @@ inline void desktop(std::string str_name, std::vector<IntegrationItems> vec_item
       , ns_string::from_container(vec_items , ',', [](auto&& e){ return ns_enum::to_string(e); }))
     .spawn()
     .wait();
+
+  // Install the icon set of the selected icon
+  fs::path path_dir_hicolor = path_dir_build / "desktop.hicolor";
+  if ( std::ranges::contains(vec_items, IntegrationItems::ICON) and fs::is_directory(path_dir_hicolor) )
+  {
+    hicolor(str_name, path_dir_hicolor);
+  } // if
 } // desktop() }}}
 
 } // namespace ns_test
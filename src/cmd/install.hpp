@@ -106,11 +106,26 @@ inline void wine(fs::path const& path_file_image,
       .wait();
   };
 
+  // Write the output of a winetricks list to a file, the log prefixes and splits its lines
+  auto f_winetricks_list = [&](std::string const& category, fs::path const& path_file_output)
+  {
+    std::ignore = ns_subprocess::Subprocess("/fim/static/fim_portal")
+      .with_piped_outputs()
+      .with_args(path_file_image, "fim-exec", "sh", "-c", R"("$1" winetricks "$2" list > "$3")"
+        , "sh", "/opt/wine/bin/wine.sh", category, path_file_output)
+      .spawn()
+      .wait();
+  };
+
   // Execute operation
   switch(op)
   {
     case Op::WINE       : f_wine(args); break;
     case Op::WINETRICKS : f_winetricks(args); break;
+    case Op::WINETRICKS_LIST :
+      ethrow_if(args.size() != 2, "Expected a category and an output file to list winetricks verbs");
+      f_winetricks_list(args.at(0), args.at(1));
+      break;
     case Op::DXVK       : f_winetricks("dxvk"); break;
     case Op::VKD3D      : f_winetricks("vkd3d"); break;
     default             :  "Unsupported wine operation '{}'"_throw(ns_enum::to_string_lower(op)); break;
@@ -12,7 +12,8 @@ namespace ns_test
 {
 
 // test() {{{
-inline decltype(auto) test()
+// Runs the project and returns the exit code of the application
+inline int test()
 {
   // Open db
   auto db_build = ns_db::ns_build::read();
@@ -20,11 +21,13 @@ inline decltype(auto) test()
   auto db_metadata = db_build->find(db_build->project);
 
   // Start application
-  std::ignore = ns_subprocess::Subprocess("/fim/static/fim_portal")
+  auto opt_code = ns_subprocess::Subprocess("/fim/static/fim_portal")
     .with_piped_outputs()
     .with_args(db_build->path_file_image, "fim-exec", db_metadata.path_dir_project / "boot")
     .spawn()
     .wait();
+  ns_log::write('i', "Application exited with code ", opt_code.value_or(EXIT_FAILURE));
+  return opt_code.value_or(EXIT_FAILURE);
 } // test() }}}
  
 } // namespace ns_test
@@ -64,6 +64,7 @@ enum class Op
   GUI,
 	WINE,
 	WINETRICKS,
+	WINETRICKS_LIST,
 	DXVK,
 	VKD3D
 }; // enum class Op }}}
@@ -216,6 +216,7 @@ struct Desktop
 {
   OpDesktop op;
   std::optional<fs::path> path_file_icon;
+  std::optional<fs::path> path_dir_hicolor;
   std::optional<std::string> name;
   std::optional<std::vector<ns_desktop::IntegrationItems>> items;
 };
@@ -230,6 +231,11 @@ struct Desktop
     case OpDesktop::ICON:
     {
       desktop.path_file_icon = ehope(db.template value<std::string>("desktop", "path_file_icon"));
+      // Optional set of icons in the layout of the hicolor theme
+      if ( auto path_dir_hicolor = db.template value<std::string>("desktop", "path_dir_hicolor") )
+      {
+        desktop.path_dir_hicolor = *path_dir_hicolor;
+      } // if
     } // case
     break;
     case OpDesktop::SETUP:
@@ -115,9 +115,9 @@ void select(ns_parser::Select const& parser)
 } // select() }}}
 
 // test() {{{
-void test()
+int test()
 {
-  ns_test::test();
+  return ns_test::test();
 } // test() }}}
 
 // desktop() {{{
@@ -125,7 +125,7 @@ void desktop(ns_parser::Desktop const& parser)
 {
   switch(parser.op)
   {
-    case ns_parser::OpDesktop::ICON: ns_desktop::icon(parser.path_file_icon.value()); break;
+    case ns_parser::OpDesktop::ICON: ns_desktop::icon(parser.path_file_icon.value(), parser.path_dir_hicolor); break;
     case ns_parser::OpDesktop::SETUP: ns_desktop::desktop(parser.name.value(), parser.items.value()); break;
   }
 } // desktop() }}}
@@ -173,7 +173,7 @@ int parse(int argc, char** argv)
   } // else if
   else if ( std::get_if<ns_parser::Test>(&parsed.value()) )
   {
-    test();
+    return test();
   } // else if
   else if ( auto* cmd = std::get_if<ns_parser::Desktop>(&parsed.value()) )
   {